@@ -1,38 +1,43 @@
-use std::path::PathBuf;
-
-use crate::AdventOfCode;
+use crate::{
+    parse::{int, lines},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Default)]
 pub struct Data {
-    input: String,
+    input: Vec<i32>,
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, "day1.txt");
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (self.puzzle1() as u64, self.puzzle2() as u64)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: &str) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        self.input = std::fs::read_to_string(input_file).expect("failed to read file");
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = lines(input, int)?;
+        Ok(())
     }
 
     fn puzzle1(&self) -> i32 {
         let mut larger = 0;
         let mut last = None;
 
-        for line in self.input.lines() {
-            let num: i32 = line.parse().unwrap();
-
+        for &num in &self.input {
             last = match last {
                 None => Some(num),
                 Some(last) => {
@@ -49,9 +54,9 @@ impl Data {
 
     fn puzzle2(&self) -> i32 {
         let mut larger = 0;
-        let numbers: Vec<i32> = self.input.lines().map(|str| str.parse().unwrap()).collect();
+        let numbers = &self.input;
 
-        for i in 0..numbers.len() - 3 {
+        for i in 0..numbers.len().saturating_sub(3) {
             let a: i32 = numbers[i..=i + 2].iter().sum();
             let b: i32 = numbers[i + 1..=i + 3].iter().sum();
 
@@ -65,29 +70,31 @@ impl Data {
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
 
     use super::Data;
 
+    const INPUT: &str = "199
+200
+208
+210
+200
+207
+240
+269
+260
+263";
+
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, "day1_test.txt");
+        let data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 7);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, "day1_test.txt");
+        let data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 5);
     }
 }
@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use nom::{character::complete::one_of, combinator::recognize, error::context, multi::many1};
 
-use crate::AdventOfCode;
-
-const DAY: &str = "day10";
+use crate::{
+    parse::{lines, IResult},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Default)]
 pub struct Data {
@@ -10,30 +11,29 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        self.input = lines.map(|f| f.to_string()).collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        let chunks: Vec<&str> = lines(input, parse)?;
+        self.input = chunks.into_iter().map(|f| f.to_string()).collect();
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -96,17 +96,11 @@ fn valid_op(op: &char) -> bool {
 }
 
 fn valid_op_open(op: &char) -> bool {
-    match op {
-        '{' | '(' | '<' | '[' => true,
-        _ => false,
-    }
+    matches!(op, '{' | '(' | '<' | '[')
 }
 
 fn valid_op_close(op: &char) -> bool {
-    match op {
-        '}' | ')' | '>' | ']' => true,
-        _ => false,
-    }
+    matches!(op, '}' | ')' | '>' | ']')
 }
 
 fn match_op_code(a: char, b: char) -> bool {
@@ -145,30 +139,36 @@ fn calc_complete_score(input: Vec<char>) -> u64 {
         .fold(0, |acc, score| acc * 5 + score)
 }
 
-#[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+fn parse(input: &str) -> IResult<'_, &str> {
+    context("brackets", recognize(many1(one_of("()[]{}<>"))))(input)
+}
 
-    use super::{Data, DAY};
+#[cfg(test)]
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
+
+    const INPUT: &str = "[({(<(())[]>[[{[]{<()<>>
+[(()[<>])]({[<{<<[]>>(
+{([(<{}[<>[]}>{[]{[(<()>
+(((({<>}<{<{<>}{[]{[]{}
+[[<[([]))<([[{}[[()]]]
+[{[{({}]{}}([{[{{{}}([]
+{<[[]]>}<{[{[{[]{()[[[]
+[<(<(<(<{}))><([]([]()
+<{([([[(<>()){}]>(<<{{
+<{([{{}}[<[[[<>{}]]]>[]]";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 26397);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 288957);
     }
 }
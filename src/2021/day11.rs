@@ -1,11 +1,12 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    path::PathBuf,
-};
+use std::collections::{HashSet, VecDeque};
 
-use crate::AdventOfCode;
+use glam::IVec2;
 
-const DAY: &str = "day11";
+use crate::{
+    grid::Grid,
+    visual::{self, Colour, Frame, Visualise},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Default)]
 pub struct Data {
@@ -13,42 +14,36 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        (a, b)
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        self.input = lines
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_digit(10).unwrap() as i32)
-                    .collect()
-            })
-            .collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        let grid = Grid::parse(input, |c| Some(c.to_digit(10)? as i32))?;
+        self.input = grid.rows().map(|row| row.to_vec()).collect();
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
         let mut sum = 0;
-        for _ in 0..100 {
+        for round in 1..=100 {
             sum += self.step();
+            visual::show(|| self.frame().with_title(format!("after step {round}")));
         }
         sum
     }
@@ -59,6 +54,7 @@ impl Data {
             round += 1; // start at 0 end increase at first
 
             let flashes = self.step();
+            visual::show(|| self.frame().with_title(format!("after step {round}")));
             assert!(flashes <= 100);
             if flashes == 100 {
                 return round;
@@ -124,42 +120,52 @@ impl Data {
 
         flashes
     }
+}
 
-    #[allow(dead_code)]
-    fn plot(&self) {
-        for y in 0..10 {
-            for x in 0..10 {
-                print!("{}", self.input[y][x]);
+/// The energy levels, with the octopuses that just flashed highlighted.
+impl Visualise for Data {
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(IVec2::ZERO, IVec2::splat(10), '.');
+        for (y, row) in self.input.iter().enumerate() {
+            for (x, &energy) in row.iter().enumerate() {
+                let pos = IVec2::new(x as i32, y as i32);
+                let c = char::from_digit(energy as u32, 10).unwrap();
+                match energy {
+                    0 => frame.paint(pos, c, Colour::Yellow),
+                    _ => frame.set(pos, c),
+                }
             }
-            println!();
         }
+        frame
     }
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
-
-    use super::{Data, DAY};
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
+
+    const INPUT: &str = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 1656);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 195);
     }
 }
@@ -1,11 +1,14 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    path::PathBuf,
-};
+use std::collections::{HashMap, VecDeque};
 
-use crate::AdventOfCode;
+use nom::{
+    character::complete::{alpha1, char},
+    sequence::separated_pair,
+};
 
-const DAY: &str = "day12";
+use crate::{
+    parse::{lines, IResult},
+    AdventOfCode, Error, Result,
+};
 
 #[derive(Debug, Default, PartialEq)]
 struct Cave {
@@ -20,69 +23,49 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        (a, b)
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-
-        // collect information
-        let mut caves = HashSet::new();
-        let mut connections = vec![];
-        lines.for_each(|line| {
-            let mut cs = line.split_terminator('-');
-            let a = cs.next().unwrap();
-            let b = cs.next().unwrap();
-            caves.insert(a.to_owned());
-            caves.insert(b.to_owned());
-            connections.push((a.to_owned(), b.to_owned()));
-        });
-
-        // store data
-        self.input = caves
-            .into_iter()
-            .map(|name| {
-                (
-                    name.to_owned(),
-                    Cave {
-                        name: name.to_owned(),
-                        connections: vec![],
-                        small: name.to_uppercase() != name,
-                    },
-                )
-            })
-            .collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        let connections = lines(input, parse)?;
 
+        self.input.clear();
         for (a, b) in connections {
-            self.input
-                .get_mut(&a)
-                .unwrap()
-                .connections
-                .push(b.to_owned());
-            self.input
-                .get_mut(&b)
-                .unwrap()
-                .connections
-                .push(a.to_owned());
+            for (from, to) in [(a, b), (b, a)] {
+                self.input
+                    .entry(from.to_owned())
+                    .or_insert_with(|| Cave {
+                        name: from.to_owned(),
+                        connections: vec![],
+                        small: from.to_uppercase() != from,
+                    })
+                    .connections
+                    .push(to.to_owned());
+            }
         }
+
+        for cave in ["start", "end"] {
+            if !self.input.contains_key(cave) {
+                return Err(Error::Parse(format!("no `{cave}` cave")));
+            }
+        }
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -101,41 +84,55 @@ impl Data {
         paths.len() as u64
     }
 
-    #[allow(dead_code)]
+    /// Part 1 with a recursive depth-first search instead of the breadth-first one.
+    pub fn part1_recursive(&mut self) -> Result<u64> {
+        let mut path = vec![String::from("start")];
+        Ok(self.visit_connections_recursive(&mut path, false).len() as u64)
+    }
+
+    /// Part 2 with a recursive depth-first search instead of the breadth-first one.
+    pub fn part2_recursive(&mut self) -> Result<u64> {
+        let mut path = vec![String::from("start")];
+        Ok(self.visit_connections_recursive(&mut path, true).len() as u64)
+    }
+
+    /// The ways to the end from the last cave of `path`, as the caves after it.
+    ///
+    /// A single small cave may be visited twice as long as `can_double_visit` is set.
     fn visit_connections_recursive(
         &self,
-        node: &Cave,
-        previous_path: Vec<String>,
+        path: &mut Vec<String>,
+        can_double_visit: bool,
     ) -> Vec<Vec<String>> {
+        let node = self.input.get(path.last().unwrap()).unwrap();
+
         let mut visits = vec![];
         for con in &node.connections {
-            let small = &con.to_owned().to_uppercase() != con;
-
-            // small caves check
-            if small && previous_path.contains(con) {
-                continue;
-            }
-
             // the end?
             if con == "end" {
-                let mut p = previous_path.to_owned();
-                p.push(con.to_owned());
-                visits.push(p);
+                visits.push(vec![con.to_owned()]);
+                continue;
+            }
+            if con == "start" {
                 continue;
             }
 
+            // small caves check
+            let mut can_double_visit = can_double_visit;
+            if self.input[con].small && path.contains(con) {
+                if !can_double_visit {
+                    continue;
+                }
+                can_double_visit = false;
+            }
+
             // visit connections
-            let new_node = self.input.get(con).unwrap();
-            let mut new_path = previous_path.to_owned();
-            new_path.push(con.to_owned());
-            let new_paths = self.visit_connections_recursive(new_node, new_path);
-
-            for mut new_path in new_paths {
-                let mut p = previous_path.to_owned();
-                p.push(con.to_owned());
-                p.append(&mut new_path);
-                visits.push(p);
+            path.push(con.to_owned());
+            for mut rest in self.visit_connections_recursive(path, can_double_visit) {
+                rest.insert(0, con.to_owned());
+                visits.push(rest);
             }
+            path.pop();
         }
 
         visits
@@ -210,60 +207,90 @@ impl Data {
     }
 }
 
-#[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+fn parse(input: &str) -> IResult<'_, (&str, &str)> {
+    separated_pair(alpha1, char('-'), alpha1)(input)
+}
 
-    use super::{Data, DAY};
+#[cfg(test)]
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
+
+    const INPUT_1: &str = "start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end";
+
+    const INPUT_2: &str = "dc-end
+HN-start
+start-kj
+dc-start
+dc-HN
+LN-dc
+HN-end
+kj-sa
+kj-HN
+kj-dc";
+
+    const INPUT_3: &str = "fs-end
+he-DX
+fs-he
+start-DX
+pj-DX
+end-zg
+zg-sl
+zg-pj
+pj-he
+RW-he
+fs-DX
+pj-RW
+zg-RW
+start-pj
+he-WI
+zg-he
+pj-fs
+start-RW";
 
     #[test]
     fn puzzle1_1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test_2.txt");
+        let mut data = Data::parse(INPUT_2).unwrap();
         assert_eq!(data.puzzle1(), 19);
     }
 
     #[test]
     fn puzzle1_2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test_3.txt");
+        let mut data = Data::parse(INPUT_3).unwrap();
         assert_eq!(data.puzzle1(), 226);
     }
 
     #[test]
     fn puzzle2_1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test_1.txt");
+        let mut data = Data::parse(INPUT_1).unwrap();
         assert_eq!(data.puzzle2(), 36);
     }
 
     #[test]
     fn puzzle2_2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test_2.txt");
+        let mut data = Data::parse(INPUT_2).unwrap();
         assert_eq!(data.puzzle2(), 103);
     }
 
     #[test]
     fn puzzle2_3() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test_3.txt");
+        let mut data = Data::parse(INPUT_3).unwrap();
         assert_eq!(data.puzzle2(), 3509);
     }
+
+    #[test]
+    fn recursive() {
+        for input in [INPUT_1, INPUT_2, INPUT_3] {
+            let mut data = Data::parse(input).unwrap();
+            assert_eq!(data.part1_recursive().unwrap(), data.puzzle1());
+            assert_eq!(data.part2_recursive().unwrap(), data.puzzle2());
+        }
+    }
 }
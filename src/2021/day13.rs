@@ -1,8 +1,20 @@
-use std::path::PathBuf;
-
-use crate::AdventOfCode;
-
-const DAY: &str = "day13";
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, line_ending, one_of},
+    combinator::map,
+    error::context,
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+};
+
+use glam::IVec2;
+
+use crate::{
+    ocr,
+    parse::{blank_line, coords2, finish, int, IResult},
+    visual::{self, Frame, Visualise},
+    AdventOfCode, Answer, Result,
+};
 
 #[derive(Debug, Default)]
 struct Fold {
@@ -17,73 +29,40 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = Answer;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        (a, b)
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        let mut dots = vec![];
-        let mut max_x = 0;
-        let mut max_y = 0;
-        self.folds.clear();
-        self.input.clear();
-        for line in lines {
-            // check empty line
-            if line.is_empty() {
-                continue;
-            }
-
-            // check for folds
-            if line.contains("fold") {
-                let mut parts = line.split_ascii_whitespace();
-                assert_eq!(parts.next().unwrap(), "fold");
-                assert_eq!(parts.next().unwrap(), "along");
-                let mut fold = parts.next().unwrap().split('=');
-                self.folds.push(Fold {
-                    is_x: fold.next().unwrap() == "x",
-                    pos: fold.next().unwrap().parse().unwrap(),
-                });
-                continue;
-            }
-
-            // handle dots
-            let mut coords = line.split(',');
-            let x: usize = coords.next().unwrap().parse().unwrap();
-            let y: usize = coords.next().unwrap().parse().unwrap();
-            dots.push((x, y));
-            if x > max_x {
-                max_x = x;
-            }
-            if y > max_y {
-                max_y = y;
-            }
-        }
+    fn load(&mut self, input: &str) -> Result<()> {
+        let (dots, folds) = finish(input, parse(input))?;
+        self.folds = folds;
 
+        let max_x = dots.iter().map(|&(x, _)| x).max().unwrap_or_default();
+        let max_y = dots.iter().map(|&(_, y)| y).max().unwrap_or_default();
+        self.input.clear();
         for y in 0..=max_y {
             let mut v = vec![];
             for x in 0..=max_x {
-                v.push(if dots.contains(&(x, y)) { true } else { false });
+                v.push(dots.contains(&(x, y)));
             }
             self.input.push(v)
         }
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -91,11 +70,9 @@ impl Data {
         self.input.iter().flatten().filter(|&&b| b).count() as u64
     }
 
-    fn puzzle2(&mut self) -> u64 {
+    fn puzzle2(&mut self) -> Answer {
         self.fold(true);
-        self.plot();
-
-        0
+        ocr::answer(self.input.iter().map(|row| row.iter().copied()))
     }
 
     fn fold(&mut self, task_two: bool) {
@@ -141,52 +118,123 @@ impl Data {
                 }
             }
 
+            let axis = if fold.is_x { 'x' } else { 'y' };
+            visual::show(|| {
+                self.frame()
+                    .with_title(format!("after folding along {axis}={}", fold.pos))
+            });
+
             if !task_two {
                 break;
             }
         }
     }
+}
 
-    #[allow(dead_code)]
-    fn plot(&self) {
-        for y in &self.input {
-            for x in y {
-                if *x {
-                    print!("#");
-                } else {
-                    print!(".");
-                }
+/// The dots on the paper as `#`.
+impl Visualise for Data {
+    fn frame(&self) -> Frame {
+        let (width, height) = (self.input.first().map_or(0, Vec::len), self.input.len());
+        let mut frame = Frame::new(IVec2::ZERO, IVec2::new(width as i32, height as i32), '.');
+        for (y, row) in self.input.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, &dot)| dot) {
+                frame.set(IVec2::new(x as i32, y as i32), '#');
             }
-            println!();
         }
-        println!();
+        frame
     }
 }
 
-#[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+type Dot = (usize, usize);
+
+fn parse(input: &str) -> IResult<'_, (Vec<Dot>, Vec<Fold>)> {
+    let fold = map(
+        preceded(
+            tag("fold along "),
+            separated_pair(one_of("xy"), char('='), int),
+        ),
+        |(axis, pos)| Fold {
+            pos,
+            is_x: axis == 'x',
+        },
+    );
+    separated_pair(
+        separated_list1(line_ending, coords2),
+        blank_line,
+        separated_list1(line_ending, context("fold", fold)),
+    )(input)
+}
 
-    use super::{Data, DAY};
+#[cfg(test)]
+mod tests {
+    use crate::{visual::Visualise, AdventOfCode};
+
+    use super::Data;
+
+    const INPUT: &str = "6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 17);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
-        assert_eq!(data.puzzle2(), 0);
+        let mut data = Data::parse(INPUT).unwrap();
+        let code = "#####
+#...#
+#...#
+#...#
+#####
+.....
+.....";
+        assert_eq!(data.puzzle2().to_string(), code);
+    }
+
+    #[test]
+    fn frame() {
+        let mut data = Data::parse(INPUT).unwrap();
+        data.puzzle1();
+        assert_eq!(
+            data.frame().to_string(),
+            "\
+#.##..#..#.
+#...#......
+......#...#
+#...#......
+.#.#..#.###
+...........
+..........."
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let err = Data::parse("6,10\n0,14\n\nfold along z=7").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 4, column 12: expected oneof (in fold)"
+        );
     }
 }
@@ -1,8 +1,17 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-use crate::AdventOfCode;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, line_ending, satisfy},
+    error::context,
+    multi::separated_list1,
+    sequence::{pair, separated_pair},
+};
 
-const DAY: &str = "day14";
+use crate::{
+    parse::{blank_line, finish, ws, IResult},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Default)]
 pub struct Data {
@@ -18,30 +27,27 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let mut lines = input.lines();
-        let first = lines.next().unwrap();
+    fn load(&mut self, input: &str) -> Result<()> {
+        let (first, rules) = finish(input, parse(input))?;
         // insert elem tuples
         self.input.clear();
         first
@@ -56,16 +62,9 @@ impl Data {
             .chars()
             .for_each(|c| *self.elements.entry(c).or_default() += 1);
 
-        assert!(lines.next().unwrap().is_empty());
-        
         // insert rules
-        self.rules.clear();
-        for rule in lines {
-            self.rules.insert(
-                (rule.chars().nth(0).unwrap(), rule.chars().nth(1).unwrap()),
-                rule.chars().nth(6).unwrap(),
-            );
-        }
+        self.rules = rules.into_iter().collect();
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -92,30 +91,52 @@ impl Data {
     }
 }
 
-#[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+type Rule = ((char, char), char);
 
-    use super::{Data, DAY};
+fn parse(input: &str) -> IResult<'_, (&str, Vec<Rule>)> {
+    let element = || satisfy(|c| c.is_ascii_alphabetic());
+    let rule = separated_pair(pair(element(), element()), ws(tag("->")), element());
+    separated_pair(
+        alpha1,
+        blank_line,
+        separated_list1(line_ending, context("rule", rule)),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
+
+    const INPUT: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 1588);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 2188189693529);
     }
 }
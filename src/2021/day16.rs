@@ -1,15 +1,81 @@
-use std::{ops::ShlAssign, path::PathBuf};
+use std::{fmt, ops::ShlAssign};
+
+use nom::{
+    bits::complete::take,
+    error::{Error, ErrorKind},
+    IResult,
+};
+
+use crate::{parse::ParseError, AdventOfCode, Result};
+
+/// Operation of an operator packet, by packet type id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Sum = 0,
+    Product = 1,
+    Minimum = 2,
+    Maximum = 3,
+    GreaterThan = 5,
+    LessThan = 6,
+    EqualTo = 7,
+}
 
-use nom::{bits::complete::take, IResult};
+impl Operator {
+    /// The operator with this type id, `None` for literals.
+    pub fn from_type(ty: u8) -> Option<Self> {
+        match ty {
+            0 => Some(Operator::Sum),
+            1 => Some(Operator::Product),
+            2 => Some(Operator::Minimum),
+            3 => Some(Operator::Maximum),
+            5 => Some(Operator::GreaterThan),
+            6 => Some(Operator::LessThan),
+            7 => Some(Operator::EqualTo),
+            _ => None,
+        }
+    }
 
-use crate::AdventOfCode;
+    /// Whether the operator can take `count` sub-packets.
+    fn accepts(self, count: usize) -> bool {
+        match self {
+            Operator::GreaterThan | Operator::LessThan | Operator::EqualTo => count == 2,
+            _ => count > 0,
+        }
+    }
 
-const DAY: &str = "day16";
+    fn apply(self, values: &[u64]) -> u64 {
+        match self {
+            Operator::Sum => values.iter().sum(),
+            Operator::Product => values.iter().product(),
+            Operator::Minimum => *values.iter().min().unwrap(),
+            Operator::Maximum => *values.iter().max().unwrap(),
+            Operator::GreaterThan => (values[0] > values[1]) as u64,
+            Operator::LessThan => (values[0] < values[1]) as u64,
+            Operator::EqualTo => (values[0] == values[1]) as u64,
+        }
+    }
 
-#[derive(Debug)]
-enum Packet {
+    pub fn name(self) -> &'static str {
+        match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => "gt",
+            Operator::LessThan => "lt",
+            Operator::EqualTo => "eq",
+        }
+    }
+}
+
+/// Type id of the literal packets.
+const LITERAL: u8 = 4;
+
+/// A decoded packet, with its version first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
     Literal(u8, u64),
-    Operator(u8, u8, Vec<Packet>),
+    Operator(u8, Operator, Vec<Packet>),
 }
 
 impl Default for Packet {
@@ -22,7 +88,7 @@ impl Packet {
     fn get_sum(&self) -> u64 {
         match self {
             Packet::Literal(ver, _val) => *ver as u64,
-            Packet::Operator(ver, _ty, next) => {
+            Packet::Operator(ver, _op, next) => {
                 next.iter().fold(*ver as u64, |acc, p| acc + p.get_sum())
             }
         }
@@ -31,57 +97,91 @@ impl Packet {
     fn process(&self) -> u64 {
         match self {
             &Packet::Literal(_ver, val) => val,
-            Packet::Operator(_ver, ty, next) => {
-                match ty {
-                    0 => {
-                        // sum
-                        next.iter().fold(0, |acc, p| acc + p.process())
-                    }
-                    1 => {
-                        // product
-                        next.iter().fold(1, |acc, p| acc * p.process())
-                    }
-                    2 => {
-                        // minimun
-                        next.iter().map(|p| p.process()).min().unwrap()
-                    }
-                    3 => {
-                        // maximum
-                        next.iter().map(|p| p.process()).max().unwrap()
-                    }
-                    5 => {
-                        // greater than
-                        assert_eq!(next.len(), 2);
-                        if next.first().unwrap().process() > next.last().unwrap().process() {
-                            1
-                        } else {
-                            0
-                        }
+            Packet::Operator(_ver, op, next) => {
+                let values: Vec<u64> = next.iter().map(|p| p.process()).collect();
+                op.apply(&values)
+            }
+        }
+    }
+
+    /// The transmission of the packet in hexadecimal, padded with zeros to whole bytes.
+    ///
+    /// Sub-packets are counted when there are few enough of them, as that takes fewer bits than
+    /// their length.
+    pub fn encode(&self) -> String {
+        let mut bits = vec![];
+        self.encode_bits(&mut bits);
+        bits.resize(bits.len().next_multiple_of(8), false);
+        bits.chunks(4)
+            .map(|nibble| {
+                let digit = nibble.iter().fold(0, |n, &b| n << 1 | b as u32);
+                char::from_digit(digit, 16).unwrap().to_ascii_uppercase()
+            })
+            .collect()
+    }
+
+    fn encode_bits(&self, bits: &mut Vec<bool>) {
+        match self {
+            &Packet::Literal(ver, val) => {
+                push_bits(bits, ver as u64, 3);
+                push_bits(bits, LITERAL as u64, 3);
+                let groups = (64 - val.leading_zeros() as usize).div_ceil(4).max(1);
+                for i in (0..groups).rev() {
+                    push_bits(bits, (i > 0) as u64, 1);
+                    push_bits(bits, val >> (4 * i), 4);
+                }
+            }
+            Packet::Operator(ver, op, next) => {
+                push_bits(bits, *ver as u64, 3);
+                push_bits(bits, *op as u64, 3);
+                if next.len() < 1 << 11 {
+                    push_bits(bits, 1, 1);
+                    push_bits(bits, next.len() as u64, 11);
+                    for p in next {
+                        p.encode_bits(bits);
                     }
-                    6 => {
-                        // less then
-                        assert_eq!(next.len(), 2);
-                        if next.first().unwrap().process() < next.last().unwrap().process() {
-                            1
-                        } else {
-                            0
-                        }
+                } else {
+                    let mut sub = vec![];
+                    for p in next {
+                        p.encode_bits(&mut sub);
                     }
-                    7 => {
-                        // equal
-                        assert_eq!(next.len(), 2);
-                        if next.first().unwrap().process() == next.last().unwrap().process() {
-                            1
+                    assert!(sub.len() < 1 << 15, "sub-packets too long to encode");
+                    push_bits(bits, 0, 1);
+                    push_bits(bits, sub.len() as u64, 15);
+                    bits.extend(sub);
+                }
+            }
+        }
+    }
+}
+
+/// Appends the `width` lowest bits of `value`, most significant first.
+fn push_bits(bits: &mut Vec<bool>, value: u64, width: usize) {
+    bits.extend((0..width).rev().map(|i| value >> i & 1 == 1));
+}
+
+/// S-expression of the packet, like `(sum 1 (max 2 3))`, indented one sub-packet per line with
+/// `{:#}`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write(packet: &Packet, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+            match packet {
+                Packet::Literal(_, val) => write!(f, "{val}"),
+                Packet::Operator(_, op, next) => {
+                    write!(f, "({}", op.name())?;
+                    for p in next {
+                        if f.alternate() {
+                            write!(f, "\n{:1$}", "", 2 * (depth + 1))?;
                         } else {
-                            0
+                            write!(f, " ")?;
                         }
+                        write(p, f, depth + 1)?;
                     }
-                    _ => {
-                        unreachable!();
-                    }
+                    write!(f, ")")
                 }
             }
         }
+        write(self, f, 0)
     }
 }
 
@@ -93,36 +193,45 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
+    fn load(&mut self, input: &str) -> Result<()> {
         // prepare input
-        let mut lines = input.lines();
-        let line = lines.next().unwrap();
+        let line = input.lines().next().unwrap_or_default();
+        if let Some(i) = line.find(|c: char| !c.is_ascii_hexdigit()) {
+            return Err(ParseError::new(1, i + 1, "expected a hexadecimal digit").into());
+        }
+        if !line.len().is_multiple_of(2) {
+            return Err(ParseError::new(1, line.len(), "odd number of hexadecimal digits").into());
+        }
         self.input = (0..line.len())
             .step_by(2)
             .map(|i| u8::from_str_radix(&line[i..i + 2], 16).unwrap())
             .collect();
 
-        self.parse_packets();
+        self.parse_packets()
+    }
+
+    /// The outermost packet of the transmission.
+    pub fn packet(&self) -> &Packet {
+        &self.packets
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -133,14 +242,29 @@ impl Data {
         self.packets.process()
     }
 
-    fn parse_packets(&mut self) {
+    fn parse_packets(&mut self) -> Result<()> {
         let ptr = (self.input.as_slice(), self.offset);
-        let (_ptr, p) = parse_packet(ptr).unwrap();
+        let (_ptr, p) = parse_packet(ptr).map_err(|err| {
+            // locate the error as the hex digit holding the bit that could not be read
+            let (bit, message) = match err {
+                nom::Err::Error(e) | nom::Err::Failure(e) => (
+                    (self.input.len() - e.input.0.len()) * 8 + e.input.1,
+                    match e.code {
+                        ErrorKind::Verify => "wrong number of sub-packets",
+                        _ => "truncated packet",
+                    },
+                ),
+                nom::Err::Incomplete(_) => (self.input.len() * 8, "truncated packet"),
+            };
+            ParseError::new(1, bit / 4 + 1, message)
+        })?;
+        tracing::debug!("transmission:\n{p:#}");
         self.packets = p;
 
         let copy = ptr.0.to_vec();
         self.offset = ptr.1;
         self.input = copy;
+        Ok(())
     }
 }
 
@@ -148,18 +272,18 @@ fn parse_packet(input: (&[u8], usize)) -> IResult<(&[u8], usize), Packet> {
     let mut ptr_g = input;
 
     // header
-    let (ptr, version) = eat_bits(ptr_g, 3).unwrap();
-    let (ptr, ty) = eat_bits(ptr, 3).unwrap();
+    let (ptr, version) = eat_bits(ptr_g, 3)?;
+    let (ptr, ty) = eat_bits(ptr, 3)?;
 
-    let p = match ty {
-        4 => {
+    let p = match Operator::from_type(ty) {
+        None => {
             // literal
             let mut num = 0u64;
             ptr_g = ptr;
             loop {
-                let (ptr, last) = eat_bits(ptr_g, 1).unwrap();
+                let (ptr, last) = eat_bits(ptr_g, 1)?;
 
-                let (ptr, part) = eat_bits(ptr, 4).unwrap();
+                let (ptr, part) = eat_bits(ptr, 4)?;
                 num.shl_assign(4);
                 num += part as u64;
 
@@ -170,29 +294,29 @@ fn parse_packet(input: (&[u8], usize)) -> IResult<(&[u8], usize), Packet> {
             }
             Packet::Literal(version, num)
         }
-        _ => {
+        Some(op) => {
             // operator
-            let (ptr, i) = eat_bits(ptr, 1).unwrap();
+            let (ptr, i) = eat_bits(ptr, 1)?;
             let mut counter = 0usize;
             ptr_g = ptr;
 
             if i == 0 {
                 // length in bits
                 // split 15 bit read in two chunks
-                let (ptr, x) = eat_bits(ptr_g, 8).unwrap();
+                let (ptr, x) = eat_bits(ptr_g, 8)?;
                 counter.shl_assign(8);
                 counter += x as usize;
-                let (ptr, x) = eat_bits(ptr, 7).unwrap();
+                let (ptr, x) = eat_bits(ptr, 7)?;
                 counter.shl_assign(7);
                 counter += x as usize;
                 ptr_g = ptr;
             } else {
                 // length in packets
                 // split 11 bit read in two chunks
-                let (ptr, x) = eat_bits(ptr_g, 8).unwrap();
+                let (ptr, x) = eat_bits(ptr_g, 8)?;
                 counter.shl_assign(8);
                 counter += x as usize;
-                let (ptr, x) = eat_bits(ptr, 3).unwrap();
+                let (ptr, x) = eat_bits(ptr, 3)?;
                 counter.shl_assign(3);
                 counter += x as usize;
                 ptr_g = ptr;
@@ -200,7 +324,7 @@ fn parse_packet(input: (&[u8], usize)) -> IResult<(&[u8], usize), Packet> {
 
             let mut packets = vec![];
             while counter > 0 {
-                let (ptr, p) = parse_packet(ptr_g).unwrap();
+                let (ptr, p) = parse_packet(ptr_g)?;
                 packets.push(p);
 
                 if i == 0 {
@@ -215,7 +339,10 @@ fn parse_packet(input: (&[u8], usize)) -> IResult<(&[u8], usize), Packet> {
                 ptr_g = ptr;
             }
 
-            Packet::Operator(version, ty, packets)
+            if !op.accepts(packets.len()) {
+                return Err(nom::Err::Failure(Error::new(input, ErrorKind::Verify)));
+            }
+            Packet::Operator(version, op, packets)
         }
     };
 
@@ -229,29 +356,125 @@ fn eat_bits(input: (&[u8], usize), count: usize) -> IResult<(&[u8], usize), u8>
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::{Data, Operator, Packet};
 
-    use super::{Data, DAY};
+    const INPUT: &str = "9C0141080250320F1802104A08";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 20);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 1);
     }
+
+    #[test]
+    fn invalid() {
+        let err = Data::parse("9C01x1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 5: expected a hexadecimal digit"
+        );
+        let err = Data::parse("D2FE").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 5: truncated packet"
+        );
+
+        let three = Packet::Operator(0, Operator::LessThan, vec![Packet::Literal(0, 1); 3]);
+        let err = Data::parse(&three.encode()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 1: wrong number of sub-packets"
+        );
+    }
+
+    fn decode(hex: &str) -> Packet {
+        Data::parse(hex).unwrap().packet().clone()
+    }
+
+    #[test]
+    fn encode() {
+        // counted sub-packets and literals come back as they were sent
+        for hex in ["D2FE28", "EE00D40C823060"] {
+            assert_eq!(decode(hex).encode(), hex);
+        }
+        assert_eq!(Packet::Literal(2, 0).encode(), "5000");
+
+        for (hex, value) in [
+            ("38006F45291200", 1),
+            ("8A004A801A8002F478", 15),
+            ("C0015000016115A2E0802F182340", 46),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            (INPUT, 1),
+        ] {
+            let packet = decode(hex);
+            assert_eq!(decode(&packet.encode()), packet, "{hex}");
+            assert_eq!(packet.process(), value, "{hex}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(decode("C200B40A82").to_string(), "(sum 1 2)");
+        assert_eq!(decode("D2FE28").to_string(), "2021");
+
+        let packet = decode(INPUT);
+        assert_eq!(packet.to_string(), "(eq (sum 1 3) (product 2 2))");
+        assert_eq!(
+            format!("{packet:#}"),
+            "(eq\n  (sum\n    1\n    3)\n  (product\n    2\n    2))"
+        );
+    }
+
+    /// A random packet of at most `depth` levels of operators, from a xorshift generator.
+    fn generate(seed: &mut u64, depth: usize) -> Packet {
+        let mut next = |n: u64| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            *seed % n
+        };
+
+        let version = next(8) as u8;
+        let Some(op) = Operator::from_type(next(8) as u8).filter(|_| depth > 0) else {
+            // literals of all sizes, up to 16 groups
+            return Packet::Literal(version, next(u64::MAX) >> next(64));
+        };
+        let count = if op.accepts(3) { 1 + next(4) } else { 2 };
+        let next = (0..count).map(|_| generate(seed, depth - 1)).collect();
+        Packet::Operator(version, op, next)
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut seed = 0x2021_1216;
+        for _ in 0..200 {
+            let packet = generate(&mut seed, 4);
+            let mut data = Data::parse(&packet.encode()).unwrap();
+            assert_eq!(data.packets, packet);
+            assert_eq!(data.puzzle1(), packet.get_sum());
+        }
+
+        // too many sub-packets to count, so they are measured
+        let packet = Packet::Operator(5, Operator::Sum, vec![Packet::Literal(1, 3); 2500]);
+        let hex = packet.encode();
+        // the bit after the version and type is the length type
+        assert_eq!(u8::from_str_radix(&hex[..2], 16).unwrap() & 0b10, 0);
+        let mut data = Data::parse(&hex).unwrap();
+        assert_eq!(data.packets, packet);
+        assert_eq!(data.puzzle2(), 7500);
+    }
 }
@@ -1,8 +1,17 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::collections::HashSet;
 
-use crate::AdventOfCode;
+use nom::{
+    bytes::complete::tag,
+    character::complete::char,
+    combinator::map,
+    error::context,
+    sequence::{pair, preceded, separated_pair},
+};
 
-const DAY: &str = "day17";
+use crate::{
+    parse::{finish, int, ws, IResult},
+    AdventOfCode, Result,
+};
 
 type Pos2D = (i64, i64);
 
@@ -14,44 +23,29 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let mut lines = input.lines();
-        let mut parts = lines.next().unwrap().split_ascii_whitespace();
-        // target area: x=281..311, y=-74..-54
-        assert_eq!(parts.next().unwrap(), "target");
-        assert_eq!(parts.next().unwrap(), "area:");
-
-        let split_coord = |s: &str| -> (i64, i64) {
-            let parts = s.split_once("..").unwrap();
-
-            let x = parts.0[2..].parse().unwrap();
-            let y = parts.1.parse().unwrap();
-
-            (x, y)
-        };
-        let part_x = parts.next().unwrap();
-        let (x_1, x_2) = split_coord(&part_x[..part_x.len() - 1]);
-        let part_y = parts.next().unwrap();
-        let (y_1, y_2) = split_coord(part_y);
-
+    fn load(&mut self, input: &str) -> Result<()> {
         // y_1 is less than y_2!
-        self.input = ((x_1, y_1), (x_2, y_2));
+        self.input = finish(input, parse(input))?;
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -79,7 +73,7 @@ impl Data {
     }
 
     fn puzzle2(&mut self) -> u64 {
-        #[cfg(test)]
+        // the answer is computed alongside part 1
         self.puzzle1();
 
         self.part_2
@@ -123,30 +117,49 @@ impl Data {
     }
 }
 
+/// `target area: x=281..311, y=-74..-54`
+fn parse(input: &str) -> IResult<'_, (Pos2D, Pos2D)> {
+    let range = |axis| {
+        preceded(
+            pair(char(axis), char('=')),
+            separated_pair(int, context("`..`", tag("..")), int),
+        )
+    };
+    map(
+        preceded(
+            tag("target area: "),
+            separated_pair(range('x'), ws(char(',')), range('y')),
+        ),
+        |((x_1, x_2), (y_1, y_2))| ((x_1, y_1), (x_2, y_2)),
+    )(input)
+}
+
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
 
-    use super::{Data, DAY};
+    use super::Data;
+
+    const INPUT: &str = "target area: x=20..30, y=-10..-5";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 45);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 112);
     }
+
+    #[test]
+    fn invalid() {
+        let err = Data::parse("target area: x=20..30, y=-10").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 29: expected tag (in `..`)"
+        );
+    }
 }
@@ -1,8 +1,17 @@
-use std::{fmt::Display, ops::Add, path::PathBuf};
+use std::{fmt::Display, ops::Add};
 
-use crate::AdventOfCode;
+use nom::{
+    branch::alt,
+    character::complete::char,
+    combinator::map,
+    error::context,
+    sequence::{delimited, separated_pair},
+};
 
-const DAY: &str = "day18";
+use crate::{
+    parse::{int, lines, IResult},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum SnailFishNumber {
@@ -10,45 +19,6 @@ enum SnailFishNumber {
     Nested(Box<(SnailFishNumber, SnailFishNumber)>),
 }
 
-impl From<&str> for SnailFishNumber {
-    fn from(s: &str) -> Self {
-        if s.chars().nth(0).unwrap() == '[' {
-            assert_eq!(s.chars().last().unwrap(), ']');
-
-            // find middle
-            let mut stack = 0;
-            let mut pos = 0;
-            let pos = s
-                .chars()
-                .find_map(|c| {
-                    pos += 1;
-                    match c {
-                        '[' => {
-                            stack += 1;
-                            None
-                        }
-                        ']' => {
-                            stack -= 1;
-                            None
-                        }
-                        i if i.is_digit(10) => None,
-                        ',' if stack == 1 => Some(pos - 1),
-                        ',' if stack != 1 => None,
-                        _ => unreachable!("invalid character"),
-                    }
-                })
-                .unwrap();
-
-            let a = SnailFishNumber::from(&s[1..pos]);
-            let b = SnailFishNumber::from(&s[pos + 1..s.len() - 1]);
-            SnailFishNumber::Nested(Box::new((a, b)))
-        } else {
-            assert_eq!(s.len(), 1);
-            SnailFishNumber::Regular(s.parse().unwrap())
-        }
-    }
-}
-
 impl Add for SnailFishNumber {
     type Output = SnailFishNumber;
     fn add(self, rhs: Self) -> Self::Output {
@@ -197,25 +167,28 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        self.input = lines.map(|line| SnailFishNumber::from(line)).collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = lines(input, parse)?;
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -254,30 +227,58 @@ impl Data {
     }
 }
 
+fn parse(input: &str) -> IResult<'_, SnailFishNumber> {
+    context(
+        "snailfish number",
+        alt((
+            map(int, SnailFishNumber::Regular),
+            map(
+                delimited(
+                    char('['),
+                    separated_pair(parse, char(','), parse),
+                    char(']'),
+                ),
+                |pair| SnailFishNumber::Nested(Box::new(pair)),
+            ),
+        )),
+    )(input)
+}
+
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
 
-    use super::{Data, DAY};
+    const INPUT: &str = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 4140);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 3993);
     }
+
+    #[test]
+    fn invalid() {
+        let err = Data::parse("[1,2]\n[[1,2],3").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 9: expected `]` (in snailfish number)"
+        );
+    }
 }
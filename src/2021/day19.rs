@@ -1,9 +1,19 @@
 use itertools::Itertools;
-use std::{collections::HashSet, path::PathBuf, slice::Iter, vec};
+use std::{collections::HashSet, slice::Iter};
 
-use crate::AdventOfCode;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{digit1, line_ending},
+    combinator::map,
+    error::context,
+    multi::separated_list1,
+    sequence::{delimited, preceded},
+};
 
-const DAY: &str = "day19";
+use crate::{
+    parse::{blocks, coords3, finish, IResult},
+    AdventOfCode, Result,
+};
 
 type Pos = [i64; 3];
 
@@ -71,56 +81,35 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        let mut beacons = vec![];
-        self.input.clear();
-        lines.filter(|line| !line.is_empty()).for_each(|line| {
-            if line.contains("scanner") {
-                if !beacons.is_empty() {
-                    self.input.push(Scanner {
-                        orientation: 0,
-                        beacons: beacons.to_owned(),
-                        position: (None),
-                    })
-                }
-                beacons.clear();
-            } else {
-                let mut parts = line.split_terminator(',');
-                let x = parts.next().unwrap().parse().unwrap();
-                let y = parts.next().unwrap().parse().unwrap();
-                let z = parts.next().unwrap().parse().unwrap();
-                beacons.push([x, y, z]);
-            }
-        });
-        self.input.push(Scanner {
-            orientation: 0,
-            beacons: beacons.to_owned(),
-            position: (None),
-        });
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = finish(input, blocks(parse_scanner)(input))?;
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
         // we need to start somewhere
         self.known_beacons.clear();
         self.known_beacons
-            .extend(self.input.first().unwrap().beacons.to_owned().into_iter());
+            .extend(self.input.first().unwrap().beacons.to_owned());
 
         self.find_mapping();
 
@@ -128,7 +117,7 @@ impl Data {
     }
 
     fn puzzle2(&mut self) -> u64 {
-        #[cfg(test)]
+        // the scanner positions are found while solving part 1
         self.puzzle1();
 
         let manhattan = |a: Pos, b: Pos| -> i64 {
@@ -181,30 +170,177 @@ fn diff_scans(ground_truth: &mut HashSet<Pos>, b: &mut Scanner, rot: u8) -> Opti
     None
 }
 
+fn parse_scanner(input: &str) -> IResult<'_, Scanner> {
+    let header = context(
+        "scanner",
+        delimited(tag("--- scanner "), digit1, tag(" ---")),
+    );
+    let beacon = map(coords3, |(x, y, z)| [x, y, z]);
+    map(
+        preceded(
+            header,
+            preceded(line_ending, separated_list1(line_ending, beacon)),
+        ),
+        |beacons| Scanner {
+            orientation: 0,
+            beacons,
+            position: None,
+        },
+    )(input)
+}
+
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
 
-    use super::{Data, DAY};
+    use super::Data;
+
+    const INPUT: &str = "--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 79);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 3621);
     }
 }
@@ -1,8 +1,14 @@
-use std::path::PathBuf;
+use nom::{
+    branch::alt, bytes::complete::tag, character::complete::space1, combinator::value,
+    error::context, sequence::separated_pair,
+};
 
-use crate::AdventOfCode;
+use crate::{
+    parse::{int, lines, IResult},
+    AdventOfCode, Result,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum Direction {
     Forward,
     Down,
@@ -15,38 +21,28 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, "day2.txt");
+    type Answer1 = i32;
+    type Answer2 = i32;
 
-        (self.puzzle1() as u64, self.puzzle2() as u64)
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: &str) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-        self.input = input
-            .lines()
-            .map(|str| {
-                let mut split = str.split_ascii_whitespace();
-                let dir = match split.next().expect("failed to split") {
-                    "forward" => Direction::Forward,
-                    "down" => Direction::Down,
-                    "up" => Direction::Up,
-                    _ => {
-                        unreachable!("unkown direction")
-                    }
-                };
-                let num: i32 = split.next().expect("failed to split").parse().unwrap();
-                return (dir, num);
-            })
-            .collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = lines(input, parse)?;
+        Ok(())
     }
 
     fn puzzle1(&self) -> i32 {
@@ -84,30 +80,37 @@ impl Data {
     }
 }
 
+fn parse(input: &str) -> IResult<'_, (Direction, i32)> {
+    let direction = alt((
+        value(Direction::Forward, tag("forward")),
+        value(Direction::Down, tag("down")),
+        value(Direction::Up, tag("up")),
+    ));
+    separated_pair(context("direction", direction), space1, int)(input)
+}
+
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
 
     use super::Data;
 
+    const INPUT: &str = "forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2";
+
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, "day2_test.txt");
+        let data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 150);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, "day2_test.txt");
+        let data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 900);
     }
 }
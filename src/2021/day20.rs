@@ -1,189 +1,149 @@
-use std::path::PathBuf;
-
-use crate::AdventOfCode;
-
-const DAY: &str = "day20";
-const PAD: usize = 1;
+use glam::IVec2;
+
+use nom::{
+    character::complete::one_of,
+    combinator::{map_opt, verify},
+    error::context,
+    multi::many1,
+    sequence::separated_pair,
+};
+
+use crate::{
+    grid::Grid,
+    parse::{blank_line, finish, grid, IResult},
+    visual::{self, Frame},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Default)]
 pub struct Data {
     input: Vec<bool>,
-    image: Vec<Vec<bool>>,
+    image: Grid<bool>,
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let halfs = input.split_once("\n\n").unwrap();
-        self.input = halfs.0.chars().map(|c| c == '#').collect();
-        let image: Vec<Vec<bool>> = halfs
-            .1
-            .lines()
-            .map(|line| line.chars().map(|c| c == '#').collect())
-            .collect();
-
-        // add a "ring" of '.' around the input image
-        let max_x = image[0].len();
-        let max_y = image.len();
-        self.image = vec![vec![false; max_x + PAD * 2]; max_y + PAD * 2];
-        for y in 0..max_y {
-            for x in 0..max_x {
-                self.image[y + PAD][x + PAD] = image[y][x];
-            }
-        }
+    fn load(&mut self, input: &str) -> Result<()> {
+        (self.input, self.image) = finish(input, parse(input))?;
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
-        #[cfg(test)]
-        {
-            // test case has `.` at 0
-            self.enhance(false, false);
-            self.enhance(false, false);
-        }
-        #[cfg(not(test))]
-        {
-            // super mean real case has `#` at 0 and `.` at 511
-            self.enhance(false, true);
-            self.enhance(true, false);
-        }
+        self.enhance_times(2);
 
-        self.image
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|cell| if *cell { 1 } else { 0 })
-                    .sum::<u64>()
-            })
-            .sum()
+        self.image.iter().filter(|&(_, &cell)| cell).count() as u64
     }
 
     fn puzzle2(&mut self) -> u64 {
-        // (0..50).for_each(|i| self.enhance(i % 2 == 0));
-        #[cfg(test)]
-        {
-            // test case has `.` at 0
-            (0..50).for_each(|_| self.enhance(false, false));
-        }
-        #[cfg(not(test))]
-        {
-            // super mean real case has `#` at 0 and `.` at 511
-            (0..50).for_each(|i| self.enhance(i % 2 != 0, i % 2 == 0));
-        }
+        self.enhance_times(50);
 
-        self.image
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|cell| if *cell { 1 } else { 0 })
-                    .sum::<u64>()
-            })
-            .sum()
+        self.image.iter().filter(|&(_, &cell)| cell).count() as u64
     }
 
-    fn enhance(&mut self, void: bool, void_new: bool) {
-        let max_x = self.image[0].len();
-        let max_y = self.image.len();
-
-        let copy = self.image.clone();
-        self.image = vec![vec![void_new; max_x + PAD * 2]; max_y + PAD * 2];
-
-        for y in 0..max_y {
-            for x in 0..max_x {
-                let mut digits = vec![];
-                for d in [
-                    (-1, -1),
-                    (0, -1),
-                    (1, -1),
-                    (-1, 0),
-                    (0, 0),
-                    (1, 0),
-                    (-1, 1),
-                    (0, 1),
-                    (1, 1),
-                ] {
-                    digits.push({
-                        let mut input = void;
-                        if !(x == 0 && d.0 < 0
-                            || y == 0 && d.1 < 0
-                            || x == max_x - 1 && d.0 > 0
-                            || y == max_y - 1 && d.1 > 0)
-                        {
-                            let new_x = (x as i64 + d.0) as usize;
-                            let new_y = (y as i64 + d.1) as usize;
-                            input = copy[new_y][new_x];
-                        }
-                        // convert
-                        if input {
-                            '1'
-                        } else {
-                            '0'
-                        }
-                    });
-                }
-                let num = usize::from_str_radix(&String::from_iter(digits), 2).unwrap();
-                let lookup = self.input[num];
-                self.image[y + PAD][x + PAD] = lookup;
-            }
+    /// Enhances the image `steps` times while tracking the infinite background.
+    fn enhance_times(&mut self, steps: usize) {
+        let mut void = false;
+        for _ in 0..steps {
+            // the test case has `.` at 0, the super mean real case has `#` at 0 and `.` at 511
+            let void_new = self.input[if void { 511 } else { 0 }];
+            self.enhance(void, void_new);
+            void = void_new;
+            visual::show(|| Frame::from_grid(&self.image, |&c| if c { '#' } else { '.' }));
         }
     }
 
-    #[allow(dead_code)]
-    fn plot(&self) {
-        let max_x = self.image[0].len();
-        let max_y = self.image.len();
-        for y in 0..max_y {
-            for x in 0..max_x {
-                if self.image[y][x] {
-                    print!("#");
-                } else {
-                    print!(".");
+    /// Grows the image by one pixel on each side, everything further out is `void`.
+    fn enhance(&mut self, void: bool, void_new: bool) {
+        let old = &self.image;
+        let mut image = Grid::new(old.width() + 2, old.height() + 2, void_new);
+
+        for pos in image.positions().collect::<Vec<_>>() {
+            let center = pos - IVec2::ONE;
+            let mut num = 0;
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    let input = old.get(center + IVec2::new(x, y)).unwrap_or(&void);
+                    num = num << 1 | *input as usize;
                 }
             }
-            println!();
+            image[pos] = self.input[num];
         }
-        println!();
+
+        self.image = image;
+    }
+}
+
+fn pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
     }
 }
 
+fn parse_algorithm(input: &str) -> IResult<'_, Vec<bool>> {
+    context(
+        "algorithm of 512 pixels",
+        verify(many1(map_opt(one_of("#."), pixel)), |a: &Vec<bool>| {
+            a.len() == 512
+        }),
+    )(input)
+}
+
+fn parse(input: &str) -> IResult<'_, (Vec<bool>, Grid<bool>)> {
+    separated_pair(parse_algorithm, blank_line, grid(pixel))(input)
+}
+
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
 
-    use super::{Data, DAY};
+    use super::Data;
+
+    const INPUT: &str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 35);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 3351);
     }
+
+    #[test]
+    fn invalid() {
+        let err = Data::parse(&INPUT.replace("\n##..#", "\n##.x#")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 5, column 1: expected row of the same width"
+        );
+    }
 }
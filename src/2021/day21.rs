@@ -1,8 +1,17 @@
-use std::{collections::VecDeque, path::PathBuf, vec};
+use std::collections::VecDeque;
 
-use crate::AdventOfCode;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{digit1, line_ending},
+    combinator::verify,
+    error::context,
+    sequence::{delimited, preceded, separated_pair},
+};
 
-const DAY: &str = "day21";
+use crate::{
+    parse::{finish, int, IResult},
+    AdventOfCode, Result,
+};
 
 struct FairDice {
     last: u64,
@@ -42,41 +51,28 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        (a, b)
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let mut lines = input.lines();
-        let a = lines
-            .next()
-            .unwrap()
-            .split_ascii_whitespace()
-            .last()
-            .unwrap()
-            .parse()
-            .unwrap();
-        let b = lines
-            .next()
-            .unwrap()
-            .split_ascii_whitespace()
-            .last()
-            .unwrap()
-            .parse()
-            .unwrap();
-        self.input = (a, b);
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = finish(input, parse(input))?;
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
@@ -116,11 +112,11 @@ impl Data {
         ));
 
         let mut wins = (0, 0);
-        let times = vec![1, 3, 6, 7, 6, 3, 1];
+        let times = [1, 3, 6, 7, 6, 3, 1];
 
         while let Some((g, num)) = games.pop_front() {
             (3..=9).for_each(|roll| {
-                let mut g = g.clone();
+                let mut g = g;
                 g.step(roll);
 
                 let num = num * times[roll as usize - 3];
@@ -142,30 +138,37 @@ impl Data {
     }
 }
 
+fn parse(input: &str) -> IResult<'_, (u8, u8)> {
+    let player = || {
+        preceded(
+            delimited(tag("Player "), digit1, tag(" starting position: ")),
+            context(
+                "position from 1 to 10",
+                verify(int, |p| (1..=10).contains(p)),
+            ),
+        )
+    };
+    separated_pair(player(), line_ending, player())(input)
+}
+
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
 
-    use super::{Data, DAY};
+    const INPUT: &str = "Player 1 starting position: 4
+Player 2 starting position: 8";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 739785);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 444356092776315);
     }
 }
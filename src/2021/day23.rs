@@ -1,505 +1,616 @@
-use std::{
-    collections::{BinaryHeap, HashSet},
-    fmt,
-    ops::Div,
-    path::PathBuf,
-    slice::Iter,
-};
-
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::one_of,
-    combinator::map_res,
-    sequence::{preceded, tuple},
-    Finish,
-};
-
-use crate::AdventOfCode;
-
-const DAY: &str = "day23";
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Hallway(usize);
-impl TryFrom<Position> for Hallway {
-    type Error = ();
-    fn try_from(value: Position) -> Result<Self, Self::Error> {
-        if value.1 == 0 && ![2, 4, 6, 8].contains(&value.0) {
-            Ok(Hallway(value.0))
-        } else {
-            Err(())
-        }
-    }
-}
+use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Room(usize, usize);
-impl TryFrom<Position> for Room {
-    type Error = ();
-    fn try_from(value: Position) -> Result<Self, Self::Error> {
-        if value.1 > 0 && [2, 4, 6, 8].contains(&value.0) {
-            Ok(Room(value.0.div(2) - 1, value.1 - 1))
-        } else {
-            Err(())
-        }
-    }
-}
+use crate::{parse::ParseError, search::astar, AdventOfCode, Error, Result};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Position(usize, usize);
+/// Rooms are limited so that the energy fits, it grows tenfold from one type to the next.
+const MAX_ROOMS: usize = 10;
 
-impl From<Hallway> for Position {
-    fn from(hw: Hallway) -> Self {
-        assert!(![2, 4, 6, 8].contains(&hw.0));
-        Position(hw.0, 0)
-    }
-}
+/// The two folded lines of the diagram, inserted between the rows of the input for part 2.
+const FOLDED: [&str; 2] = ["DCBA", "DBAC"];
 
-impl From<Room> for Position {
-    fn from(r: Room) -> Self {
-        Position((r.0 + 1) * 2, r.1 + 1)
+/// Amphipod type by index, `A` is 0 and belongs in the leftmost room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Amphipod(u8);
+
+impl Amphipod {
+    fn from_char(c: char) -> Option<Self> {
+        c.is_ascii_uppercase().then(|| Amphipod(c as u8 - b'A'))
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Amphipod {
-    A,
-    B,
-    C,
-    D,
-}
+    fn room(self) -> usize {
+        self.0 as usize
+    }
 
-impl Default for Amphipod {
-    fn default() -> Self {
-        Amphipod::A
+    fn energy(self) -> u64 {
+        10u64.pow(self.0 as u32)
     }
-}
 
-impl TryFrom<char> for Amphipod {
-    type Error = ();
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            'A' => Ok(Amphipod::A),
-            'B' => Ok(Amphipod::B),
-            'C' => Ok(Amphipod::C),
-            'D' => Ok(Amphipod::D),
-            _ => Err(()),
-        }
+    fn letter(self) -> char {
+        (b'A' + self.0) as char
     }
 }
 
-impl Amphipod {
-    fn cost(&self) -> isize {
-        match &self {
-            Amphipod::A => 1,
-            Amphipod::B => 10,
-            Amphipod::C => 100,
-            Amphipod::D => 1000,
-        }
-    }
+/// A square of the burrow, rooms are numbered from the left and their squares from the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Square {
+    Hallway(usize),
+    Room(usize, usize),
+}
 
-    // room type index (0..=3)
-    fn target_room(&self) -> usize {
-        match &self {
-            Amphipod::A => 0,
-            Amphipod::B => 1,
-            Amphipod::C => 2,
-            Amphipod::D => 3,
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Square::Hallway(x) => write!(f, "hallway {x}"),
+            Square::Room(room, depth) => write!(f, "room {room} depth {depth}"),
         }
     }
-
-    pub fn iterator() -> Iter<'static, Amphipod> {
-        use self::Amphipod::*;
-        static AMPHIPOD: [Amphipod; 4] = [A, B, C, D];
-        AMPHIPOD.iter()
-    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct GameState {
-    rooms: [[Option<Amphipod>; 4]; 4],
-    hallway: [Option<Amphipod>; 11],
-    amphibods: Vec<(Position, Amphipod)>,
+/// An amphipod moving from one square to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub amphipod: char,
+    pub from: Square,
+    pub to: Square,
+    pub energy: u64,
 }
 
-impl fmt::Display for GameState {
+impl fmt::Display for Move {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // #############
-        // #...........#
-        // ###A#C#B#A###
-        //   #D#D#B#C#
-        //   #########
-        writeln!(f, "#############")?;
-        write!(f, "#")?;
-        for hw in self.hallway {
-            match hw {
-                None => write!(f, ".")?,
-                Some(a) => write!(f, "{:?}", a)?,
-            }
-        }
-        writeln!(f, "#")?;
+        write!(
+            f,
+            "{} from {} to {} for {}",
+            self.amphipod, self.from, self.to, self.energy
+        )
+    }
+}
 
-        for i in 0..4 {
-            if i == 0 {
-                write!(f, "###")?;
-            } else {
-                write!(f, "  #")?;
-            }
+/// Who is where: the hallway squares from the left, then each room from the top.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct State(Vec<Option<Amphipod>>);
+
+/// The shape of the burrow, a hallway with rooms of the same depth below some of its squares.
+#[derive(Debug, Clone)]
+struct Burrow {
+    hallway: usize,
+    /// Hallway squares above each room, where amphipods can't stop.
+    entrances: Vec<usize>,
+    depth: usize,
+    /// Column of the first hallway square in the diagram.
+    left: usize,
+}
 
-            for room in self.rooms {
-                match room[i] {
-                    None => write!(f, " ")?,
-                    Some(a) => write!(f, "{:?}", a)?,
-                }
-                write!(f, "#")?;
-            }
+impl Burrow {
+    /// Parses a diagram like the puzzle's, with any number of rooms of any depth.
+    fn parse(input: &str) -> Result<(Self, State)> {
+        let lines: Vec<Vec<char>> = input
+            .lines()
+            .map(|l| l.trim_end().chars().collect())
+            .collect();
+        // lines and columns from 0
+        let error =
+            |l: usize, col: usize, message| Error::from(ParseError::new(l + 1, col + 1, message));
+        let wall = |l: usize| lines[l].iter().all(|&c| c == '#' || c == ' ');
+        let open = |l: usize, col: usize| lines[l].get(col).is_some_and(|&c| c != '#' && c != ' ');
+        let cell = |l: usize, col: usize| match lines[l].get(col) {
+            Some('.') => Ok(None),
+            Some(&c) if Amphipod::from_char(c).is_some() => Ok(Amphipod::from_char(c)),
+            _ => Err(error(l, col, "expected an amphipod or `.`")),
+        };
 
-            if i == 0 {
-                writeln!(f, "##")?;
-            } else {
-                writeln!(f, "")?;
-            }
+        if lines.len() < 4 || !wall(0) {
+            return Err(error(0, 0, "expected the wall above the hallway"));
         }
-        writeln!(f, "  #########")
-    }
-}
+        let (Some(left), Some(right)) = (
+            (0..lines[1].len()).find(|&col| open(1, col)),
+            (0..lines[1].len()).rfind(|&col| open(1, col)),
+        ) else {
+            return Err(error(1, 0, "expected the hallway"));
+        };
+        let mut cells = (left..=right)
+            .map(|col| cell(1, col))
+            .collect::<Result<Vec<_>>>()?;
 
-impl GameState {
-    fn try_move(&self, source: &Position, destination: &Position) -> Option<(isize, GameState)> {
-        // println!("try move {:?} {:?}", source, destination);
+        let entrances: Vec<usize> = (0..cells.len()).filter(|x| open(2, left + x)).collect();
+        if entrances.is_empty() {
+            return Err(error(2, 0, "expected rooms below the hallway"));
+        }
+        if entrances.len() > MAX_ROOMS {
+            return Err(Error::Parse(format!(
+                "{} rooms are more than {MAX_ROOMS}",
+                entrances.len()
+            )));
+        }
+        // amphipods never stop there, and the hallway scans go around them
+        if let Some(&x) = entrances.iter().find(|&&x| cells[x].is_some()) {
+            return Err(error(1, left + x, "amphipod on a room entrance"));
+        }
+        let depth = (2..lines.len()).take_while(|&l| !wall(l)).count();
+        if 2 + depth == lines.len() {
+            return Err(error(
+                lines.len() - 1,
+                0,
+                "expected the wall below the rooms",
+            ));
+        }
 
-        // horizontal movement
-        let way = if source.0 < destination.0 {
-            (source.0 + 1)..(destination.0 + 1) // skip own position, include destination
-        } else {
-            destination.0..source.0 // include destination, skipp own position
-        };
-        for step in way {
-            if self.hallway[step].is_some() {
-                // println!("can't move, hallway {} is occupied", step);
-                return None;
+        for &x in &entrances {
+            for d in 0..depth {
+                let (line, col) = (2 + d, left + x);
+                let amphipod = cell(line, col)?;
+                if amphipod.is_some_and(|a| a.room() >= entrances.len()) {
+                    return Err(error(line, col, "no room for this type"));
+                }
+                if d > 0 && amphipod.is_none() && cells.last().unwrap().is_some() {
+                    return Err(error(line, col, "gap below an amphipod"));
+                }
+                cells.push(amphipod);
             }
         }
 
-        // vertical movement
-        // This check is already done before calling `try_move`
-        // if source.1 > 1 {
-        //     // `> 0` also includes very first position
-        //     let room = Room::try_from(source.to_owned()).unwrap();
-        //     for step in 0..room.1 {
-        //         if self.rooms[room.0][step].is_some() {
-        //             // println!("can't move, source room {:?} is occupied", room);
-        //             return None;
-        //         }
-        //     }
-        // }
-
-        // not necessary, since we only move into the lowest *possible* position
-        // if destination.1 > 1 {
-        //     // `> 0` also includes very first position
-        //     let room = Room::try_from(destination.to_owned()).unwrap();
-        //     for step in 0..room.1 {
-        //         if self.rooms[room.0][step].is_some() {
-        //             // println!("can't move, destination room {:?} is occupied", room);
-        //             return None;
-        //         }
-        //     }
-        // }
-
-        let mut new_state = self.clone();
-        let occ = new_state.free(source);
-        new_state.occupy(destination, occ);
-
-        // Manhattan distance.
-        let dist_x = ((source.0 as isize) - (destination.0 as isize)).abs();
-        let dist_y = ((source.1 as isize) - (destination.1 as isize)).abs();
-        let cost = occ.cost() * (dist_x + dist_y);
-
-        Some((cost, new_state))
-    }
-
-    fn free(&mut self, pos: &Position) -> Amphipod {
-        let occ2 = self
-            .amphibods
-            .iter()
-            .find(|(p, _)| p == pos)
-            .map(|(_, a)| a)
-            .unwrap()
-            .to_owned();
-        self.amphibods.retain(|(p, _)| p != pos);
-
-        if let Ok(hw) = Hallway::try_from(pos.to_owned()) {
-            if let Some(occ) = self.hallway[hw.0] {
-                self.hallway[hw.0] = None;
-                assert_eq!(occ, occ2);
-                return occ;
-            }
-        } else if let Ok(room) = Room::try_from(pos.to_owned()) {
-            if let Some(occ) = self.rooms[room.0][room.1] {
-                self.rooms[room.0][room.1] = None;
-                assert_eq!(occ, occ2);
-                return occ;
+        for room in 0..entrances.len() {
+            let amphipod = Amphipod(room as u8);
+            let count = cells.iter().filter(|&&c| c == Some(amphipod)).count();
+            if count != depth {
+                return Err(Error::Parse(format!(
+                    "{count} amphipods {} for a room of {depth}",
+                    amphipod.letter()
+                )));
             }
         }
-        unreachable!();
-    }
 
-    fn occupy(&mut self, pos: &Position, occ: Amphipod) {
-        self.amphibods.push((pos.to_owned(), occ));
-
-        if let Ok(hw) = Hallway::try_from(pos.to_owned()) {
-            assert!(self.hallway[hw.0].is_none());
-            self.hallway[hw.0] = Some(occ);
-        } else if let Ok(room) = Room::try_from(pos.to_owned()) {
-            assert!(self.rooms[room.0][room.1].is_none());
-            self.rooms[room.0][room.1] = Some(occ);
-        }
+        let burrow = Burrow {
+            hallway: right - left + 1,
+            entrances,
+            depth,
+            left,
+        };
+        Ok((burrow, State(cells)))
     }
 
-    fn win(&self, size: usize) -> bool {
-        // println!("win?\n{}", self);
-
-        // hallway empty?
-        if self.hallway.iter().any(|occ| occ.is_some()) {
-            return false;
+    /// Adds the `rows` at the top of the rooms, below their first square.
+    fn unfold(&mut self, state: &State, rows: &[&str]) -> Result<State> {
+        if rows.iter().any(|row| row.len() != self.entrances.len()) {
+            return Err(Error::Parse(format!(
+                "the folded lines are for {} rooms",
+                rows[0].len()
+            )));
         }
 
-        for amp in Amphipod::iterator() {
-            if !self.room_all_same(&Room(amp.target_room(), 0), size, amp) {
-                return false;
+        let mut cells = state.0[..self.hallway].to_vec();
+        for room in 0..self.entrances.len() {
+            let squares = self.room(state, room);
+            cells.push(squares[0]);
+            for row in rows {
+                cells.push(row.chars().nth(room).and_then(Amphipod::from_char));
             }
+            cells.extend(&squares[1..]);
         }
-        true
+        self.depth += rows.len();
+        Ok(State(cells))
     }
 
-    fn room_all_same(&self, room: &Room, size: usize, test: &Amphipod) -> bool {
-        for i in 0..size {
-            if let Some(occ) = &self.rooms[room.0][i] {
-                if test != occ {
-                    return false;
-                }
-            }
-        }
-        true
+    fn rooms(&self) -> usize {
+        self.entrances.len()
     }
-}
 
-#[derive(Debug, Default)]
-pub struct Data {
-    input: [[Option<Amphipod>; 4]; 4],
-}
-
-impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt", true);
-        let a = self.puzzle1();
-
-        self.load(base_dir, String::from(DAY) + ".txt", false);
-        let b = self.puzzle2();
-
-        (a, b)
+    fn room<'a>(&self, state: &'a State, room: usize) -> &'a [Option<Amphipod>] {
+        let start = self.hallway + room * self.depth;
+        &state.0[start..start + self.depth]
     }
-}
 
-impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String, skip_middle: bool) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let mut lines = input.lines();
-        assert_eq!(lines.next().unwrap(), "#############");
-        assert_eq!(lines.next().unwrap(), "#...........#");
-        let (a1, b1, c1, d1) = parse(lines.next().unwrap())
-            .finish()
-            .map(|(_, x)| x)
-            .unwrap();
-        let (a2, b2, c2, d2) = parse(lines.next().unwrap())
-            .finish()
-            .map(|(_, x)| x)
-            .unwrap();
-
-        if skip_middle {
-            self.input = [
-                [Some(a1), Some(a2), None, None],
-                [Some(b1), Some(b2), None, None],
-                [Some(c1), Some(c2), None, None],
-                [Some(d1), Some(d2), None, None],
-            ];
-        } else {
-            // #D#C#B#A#
-            // #D#B#A#C#
-            self.input = [
-                [Some(a1), Some(Amphipod::D), Some(Amphipod::D), Some(a2)],
-                [Some(b1), Some(Amphipod::C), Some(Amphipod::B), Some(b2)],
-                [Some(c1), Some(Amphipod::B), Some(Amphipod::A), Some(c2)],
-                [Some(d1), Some(Amphipod::A), Some(Amphipod::C), Some(d2)],
-            ];
+    fn square(&self, i: usize) -> Square {
+        match i.checked_sub(self.hallway) {
+            None => Square::Hallway(i),
+            Some(i) => Square::Room(i / self.depth, i % self.depth),
         }
     }
 
-    fn puzzle1(&mut self) -> u64 {
-        self.start(2)
+    fn index(&self, square: Square) -> usize {
+        match square {
+            Square::Hallway(x) => x,
+            Square::Room(room, depth) => self.hallway + room * self.depth + depth,
+        }
     }
 
-    fn puzzle2(&mut self) -> u64 {
-        // no idea why my results are off by a few hundrets
-        self.start(4) - 200
+    /// Steps between two squares, going through the hallway.
+    fn distance(&self, a: Square, b: Square) -> usize {
+        let (ax, ay) = self.coords(a);
+        let (bx, by) = self.coords(b);
+        ax.abs_diff(bx) + ay + by
     }
 
-    fn start(&self, room_size: usize) -> u64 {
-        let mut occupants = vec![];
-        for room in 0..4 {
-            for pos in 0..4 {
-                if let Some(occ) = self.input[room][pos] {
-                    occupants.push((Room(room, pos).into(), occ));
-                }
-            }
+    /// Hallway square above the square, and steps to get up there.
+    fn coords(&self, square: Square) -> (usize, usize) {
+        match square {
+            Square::Hallway(x) => (x, 0),
+            Square::Room(room, depth) => (self.entrances[room], depth + 1),
         }
+    }
 
-        let start = GameState {
-            hallway: [None; 11],
-            rooms: self.input.to_owned(),
-            amphibods: occupants,
+    /// Whether the hallway is free from `from` to `to`, ignoring `from` itself.
+    fn clear(&self, state: &State, from: usize, to: usize) -> bool {
+        let squares = if from < to {
+            from + 1..to + 1
+        } else {
+            to..from
         };
+        state.0[squares].iter().all(Option::is_none)
+    }
 
-        amphipod_party(start, room_size)
+    /// Depth from which the room only holds its own amphipods.
+    fn settled(&self, state: &State, room: usize) -> usize {
+        let squares = self.room(state, room);
+        let own = squares
+            .iter()
+            .rev()
+            .take_while(|&&c| c == Some(Amphipod(room as u8)))
+            .count();
+        self.depth - own
     }
-}
 
-fn amphipod_party(start: GameState, room_size: usize) -> u64 {
-    let mut queue = BinaryHeap::new();
-    let mut visited = HashSet::new();
+    /// Free squares at the top of the room.
+    fn free(&self, state: &State, room: usize) -> usize {
+        self.room(state, room)
+            .iter()
+            .take_while(|c| c.is_none())
+            .count()
+    }
 
-    // println!("starting with\n{}", start);
+    /// Where an amphipod can enter the room, if it only holds its own amphipods.
+    fn entering(&self, state: &State, room: usize) -> Option<Square> {
+        let settled = self.settled(state, room);
+        let free = self.free(state, room);
+        (settled == free && free > 0).then(|| Square::Room(room, free - 1))
+    }
 
-    queue.push((0isize, start.to_owned()));
-    visited.insert(start);
+    /// The amphipod that has to leave the room first, if any.
+    fn leaving(&self, state: &State, room: usize) -> Option<Square> {
+        let settled = self.settled(state, room);
+        let free = self.free(state, room);
+        (free < settled).then_some(Square::Room(room, free))
+    }
 
-    while let Some((cost, state)) = queue.pop() {
-        // dbg!(queue.len());
+    /// All states reachable with a single move, with the energy it takes.
+    fn moves(&self, state: &State) -> Vec<(State, u64)> {
+        let mut next = vec![];
+        let mut push = |from: Square, to: Square| {
+            let mut cells = state.0.clone();
+            let amphipod = cells[self.index(from)].take().unwrap();
+            cells[self.index(to)] = Some(amphipod);
+            let energy = amphipod.energy() * self.distance(from, to) as u64;
+            next.push((State(cells), energy));
+        };
 
-        if state.win(room_size) {
-            return -cost as u64;
+        // into their room, from the hallway or straight from another room
+        let leaving = (0..self.rooms()).filter_map(|room| self.leaving(state, room));
+        let hallway = (0..self.hallway)
+            .filter(|&x| state.0[x].is_some())
+            .map(Square::Hallway);
+        for from in hallway.chain(leaving) {
+            let amphipod = state.0[self.index(from)].unwrap();
+            let (x, _) = self.coords(from);
+            let target = amphipod.room();
+            // a room being left can't be entered, so nobody moves within their own room
+            if let Some(to) = self.entering(state, target) {
+                if self.clear(state, x, self.entrances[target]) {
+                    push(from, to);
+                }
+            }
         }
 
-        for (pos, amphipod) in &state.amphibods {
-            // println!("{:?} @ {:?}", amphipod, pos);
-            if let Ok(room) = Room::try_from(pos.to_owned()) {
-                // println!("{:?} in room", amphipod);
-
-                // Can't go through another amphipod.
-                // Do this check here to bail out early! (This check is NOT included in `try_move`)
-                if room.1 >= 1 && state.rooms[room.0][0..room.1].iter().any(|o| o.is_some()) {
-                    // println!("can't move out of room, somebody is infront of us");
-                    continue;
+        // out of the rooms, stopping anywhere in the hallway but in front of a room
+        for room in 0..self.rooms() {
+            let Some(from) = self.leaving(state, room) else {
+                continue;
+            };
+            let entrance = self.entrances[room];
+            let left = (0..entrance).rev().take_while(|&x| state.0[x].is_none());
+            let right = (entrance + 1..self.hallway).take_while(|&x| state.0[x].is_none());
+            for x in left.chain(right) {
+                if !self.entrances.contains(&x) {
+                    push(from, Square::Hallway(x));
                 }
+            }
+        }
 
-                if room.0 == amphipod.target_room() {
-                    // already in correct room
+        next
+    }
 
-                    if state.room_all_same(&room, room_size, amphipod) {
-                        // println!("can't stay in room with other variant");
+    /// Energy still needed at least, moving the amphipods as if they could go through each
+    /// other.
+    fn lower_bound(&self, state: &State) -> u64 {
+        let mut entering = vec![0; self.rooms()];
+        let mut energy = 0;
+        for (i, amphipod) in state.0.iter().enumerate() {
+            let Some(amphipod) = amphipod else {
+                continue;
+            };
+            let target = amphipod.room();
+            let steps = match self.square(i) {
+                Square::Room(room, depth) if room == target => {
+                    if depth >= self.settled(state, room) {
                         continue;
                     }
+                    // out of the way and back
+                    depth + 1 + 2
                 }
-
-                // move to hallway
-                for dest_x in [0, 1, 3, 5, 7, 9, 10] {
-                    if let Some((new_cost, new_state)) =
-                        state.try_move(&pos, &Hallway(dest_x).into())
-                    {
-                        if visited.contains(&new_state) {
-                            // println!("skipping known state");
-                            continue;
-                        }
-                        // println!("moving from {:?} to {:?}", pos, &hw);
-                        queue.push((cost - new_cost, new_state.to_owned()));
-                        visited.insert(new_state);
-                    }
+                square => {
+                    let (x, up) = self.coords(square);
+                    up + x.abs_diff(self.entrances[target])
                 }
-            } else if let Ok(_) = Hallway::try_from(pos.to_owned()) {
-                // println!("{:?} in hallway", amphipod);
+            };
+            energy += amphipod.energy() * steps as u64;
+            entering[target] += 1;
+        }
+
+        // the amphipods entering a room fill it from its bottom free square
+        let down: u64 = entering
+            .iter()
+            .enumerate()
+            .map(|(room, &n)| Amphipod(room as u8).energy() * n * (n + 1) / 2)
+            .sum();
+        energy + down
+    }
 
-                // move into room
-                let room_x = amphipod.target_room();
+    fn organised(&self, state: &State) -> bool {
+        (0..self.rooms()).all(|room| self.settled(state, room) == 0)
+    }
 
-                if !state.room_all_same(&Room(room_x, 0), room_size, amphipod) {
-                    // println!("can't move into room with other variant");
-                    continue;
+    /// The moves organising the amphipods with the least energy.
+    fn organise(&self, start: &State) -> Option<(u64, Vec<Move>)> {
+        let path = astar(
+            start.to_owned(),
+            |state| self.moves(state),
+            |state| self.lower_bound(state),
+            |state| self.organised(state),
+        )?;
+
+        let moves = path
+            .states
+            .windows(2)
+            .map(|pair| {
+                let (before, after) = (&pair[0].0, &pair[1].0);
+                let from = (0..before.len())
+                    .find(|&i| before[i].is_some() && after[i].is_none())
+                    .unwrap();
+                let to = (0..before.len())
+                    .find(|&i| before[i].is_none() && after[i].is_some())
+                    .unwrap();
+                let amphipod = before[from].unwrap();
+                let (from, to) = (self.square(from), self.square(to));
+                Move {
+                    amphipod: amphipod.letter(),
+                    from,
+                    to,
+                    energy: amphipod.energy() * self.distance(from, to) as u64,
                 }
+            })
+            .collect();
+        Some((path.cost, moves))
+    }
 
-                let occupants = state.rooms[room_x]
+    /// The state as a diagram like the puzzle's.
+    fn diagram(&self, state: &State) -> String {
+        let width = self.left + self.hallway + 1;
+        let first = self.left + self.entrances[0];
+        let last = self.left + self.entrances[self.rooms() - 1];
+        let letter = |c: Option<Amphipod>| c.map_or('.', Amphipod::letter);
+
+        let mut lines = vec!["#".repeat(width)];
+        let hallway: String = state.0[..self.hallway].iter().map(|&c| letter(c)).collect();
+        lines.push(format!("{}{hallway}#", "#".repeat(self.left)));
+        for depth in 0..=self.depth {
+            let line = (0..width).map(|col| {
+                let room = self
+                    .entrances
                     .iter()
-                    .filter(|room| room.is_some())
-                    .count();
-                if occupants == room_size {
-                    // println!("room full");
-                    continue;
-                }
-                let room_y = room_size - occupants - 1;
-
-                if let Some((new_cost, new_state)) =
-                    state.try_move(&pos, &Room(room_x, room_y).into())
-                {
-                    if visited.contains(&new_state) {
-                        // println!("skipping known state");
-                        continue;
+                    .position(|&x| self.left + x == col)
+                    .filter(|_| depth < self.depth);
+                match room {
+                    Some(room) => letter(self.room(state, room)[depth]),
+                    None if depth == 0 || (first.saturating_sub(1)..=last + 1).contains(&col) => {
+                        '#'
                     }
-                    // println!("moving from {:?} to {:?}", pos, &room);
-                    queue.push((cost - new_cost, new_state.to_owned()));
-                    visited.insert(new_state);
+                    None => ' ',
                 }
-            } else {
-                // println!("{:?} {:?}", pos, amphipod);
-                unreachable!("Amphipod got lost...");
-            }
+            });
+            lines.push(line.collect::<String>().trim_end().to_owned());
         }
+        lines.join("\n")
     }
-    unreachable!()
 }
 
-fn parse_amphibod(line: &str) -> nom::IResult<&str, Amphipod> {
-    map_res(one_of("ABCD"), |c| c.try_into())(line)
+#[derive(Debug)]
+pub struct Data {
+    burrow: Burrow,
+    start: State,
 }
 
-fn parse_room(line: &str) -> nom::IResult<&str, Amphipod> {
-    preceded(tag("#"), parse_amphibod)(line)
+impl AdventOfCode for Data {
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self> {
+        let (burrow, start) = Burrow::parse(input)?;
+        Ok(Data { burrow, start })
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        self.puzzle1().ok_or(Error::NoSolution)
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        self.unfold()?;
+        self.puzzle1().ok_or(Error::NoSolution)
+    }
 }
 
-fn parse(line: &str) -> nom::IResult<&str, (Amphipod, Amphipod, Amphipod, Amphipod)> {
-    tuple((
-        preceded(alt((tag("###"), tag("  #"))), parse_amphibod),
-        parse_room,
-        parse_room,
-        parse_room,
-    ))(line)
+impl Data {
+    /// Inserts the two folded lines of the diagram between the first two rows of the input.
+    fn unfold(&mut self) -> Result<()> {
+        self.start = self.burrow.unfold(&self.start, &FOLDED)?;
+        Ok(())
+    }
+
+    /// The least energy needed to organise the amphipods, with the moves to get there.
+    pub fn organise(&self) -> Option<(u64, Vec<Move>)> {
+        self.burrow.organise(&self.start)
+    }
+
+    fn puzzle1(&mut self) -> Option<u64> {
+        tracing::debug!("burrow:\n{}", self.burrow.diagram(&self.start));
+        let (energy, moves) = self.organise()?;
+        for m in &moves {
+            tracing::debug!("{m}");
+        }
+        Some(energy)
+    }
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
-
-    use super::{Data, DAY};
+mod tests {
+    use crate::{search::dijkstra, AdventOfCode};
+
+    use super::{Burrow, Data, Move, Square};
+
+    const INPUT: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    /// Checks the moves against the energy, and that they organise the burrow.
+    fn replay(data: &Data, energy: u64, moves: &[Move]) {
+        assert_eq!(moves.iter().map(|m| m.energy).sum::<u64>(), energy);
+
+        let mut state = data.start.clone();
+        for m in moves {
+            let amphipod = state.0[data.burrow.index(m.from)].take().unwrap();
+            assert_eq!(amphipod.letter(), m.amphipod);
+            let to = &mut state.0[data.burrow.index(m.to)];
+            assert!(to.is_none());
+            *to = Some(amphipod);
+        }
+        assert!(data.burrow.organised(&state));
+    }
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt", true);
-        assert_eq!(data.puzzle1(), 12521);
+        let mut data = Data::parse(INPUT).unwrap();
+        assert_eq!(data.puzzle1(), Some(12521));
+
+        let (energy, moves) = data.organise().unwrap();
+        replay(&data, energy, &moves);
+        // the A in the first room is already in place
+        assert!(moves.iter().all(|m| m.from != Square::Room(0, 1)));
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt", false);
-        assert_eq!(data.puzzle2(), 44169);
+        let mut data = Data::parse(INPUT).unwrap();
+        assert_eq!(data.part2().unwrap(), 44169);
+
+        let (energy, moves) = data.organise().unwrap();
+        replay(&data, energy, &moves);
+    }
+
+    #[test]
+    fn diagram() {
+        let data = Data::parse(INPUT).unwrap();
+        assert_eq!(data.burrow.diagram(&data.start), INPUT);
+
+        let mut data = Data::parse(INPUT).unwrap();
+        data.unfold().unwrap();
+        assert_eq!(
+            data.burrow.diagram(&data.start),
+            "#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########"
+        );
+    }
+
+    #[test]
+    fn any_burrow() {
+        for input in [
+            // two deep rooms
+            "#######
+#.....#
+##A#B##
+ #B#A#
+ #A#B#
+ #####",
+            // adjacent rooms, only reachable from one another
+            "######
+#....#
+##BA##
+ ####",
+            // a long hallway, already holding amphipods
+            "###########
+#C.......B#
+####A#.#.##
+   #B#A#C#
+   #######",
+            // five rooms
+            "#############
+#...........#
+###E#D#C#B#A#
+  #A#B#C#D#E#
+  ###########",
+        ] {
+            let data = Data::parse(input).unwrap();
+            assert_eq!(data.burrow.diagram(&data.start), input);
+
+            let (energy, moves) = data.organise().unwrap();
+            replay(&data, energy, &moves);
+
+            // the lower bound keeps the search exact
+            let burrow = &data.burrow;
+            let path = dijkstra(
+                data.start.clone(),
+                |state| burrow.moves(state),
+                |state| burrow.organised(state),
+            );
+            assert_eq!(path.unwrap().cost, energy, "{input}");
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for (input, error) in [
+            (
+                "#####\n#...#\n###a#\n  ###",
+                "invalid input at line 3, column 4: expected an amphipod or `.`",
+            ),
+            (
+                "#####\n#...#\n##A##\n #B#\n ###",
+                "invalid input at line 4, column 3: no room for this type",
+            ),
+            (
+                "#####\n#A..#\n##A##\n #.#\n ###",
+                "invalid input at line 4, column 3: gap below an amphipod",
+            ),
+            (
+                "#####\n#A..#\n##A##\n #A#\n ###",
+                "invalid input: 3 amphipods A for a room of 2",
+            ),
+            (
+                "#####\n#.A.#\n##.##\n #A#\n ###",
+                "invalid input at line 2, column 3: amphipod on a room entrance",
+            ),
+            (
+                "#####\n#...#\n##A##\n #A#",
+                "invalid input at line 4, column 1: expected the wall below the rooms",
+            ),
+        ] {
+            assert_eq!(Data::parse(input).unwrap_err().to_string(), error);
+        }
+
+        let (mut burrow, state) = Burrow::parse("#####\n#...#\n##A##\n ###").unwrap();
+        assert!(burrow.unfold(&state, &super::FOLDED).is_err());
     }
 }
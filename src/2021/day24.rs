@@ -1,7 +1,7 @@
 use std::{
-    collections::HashMap,
+    collections::VecDeque,
     fmt::{self, Display},
-    path::PathBuf,
+    rc::Rc,
 };
 
 use nom::{
@@ -10,12 +10,13 @@ use nom::{
     character::complete::{i64, one_of, space1},
     combinator::{map, map_res},
     sequence::{preceded, separated_pair},
-    Finish,
 };
 
-use crate::AdventOfCode;
-
-const DAY: &str = "day24";
+use crate::{
+    parse::lines,
+    vm::{self, Flow, Registers, Stop, Vm},
+    AdventOfCode, Error, Result,
+};
 
 #[derive(Debug, Clone, Copy)]
 
@@ -27,7 +28,18 @@ enum Register {
     Literal(i64),
 }
 
-impl Register {}
+impl Register {
+    /// Index of the register in `regs`, `None` for a literal.
+    fn index(&self) -> Option<usize> {
+        match self {
+            Register::W => Some(0),
+            Register::X => Some(1),
+            Register::Y => Some(2),
+            Register::Z => Some(3),
+            Register::Literal(_) => None,
+        }
+    }
+}
 
 impl TryFrom<char> for Register {
     type Error = ();
@@ -67,159 +79,493 @@ impl OpCode {
     }
 }
 
-struct State {
-    regs: [i64; 4],
+impl vm::Instruction<4> for OpCode {
+    fn execute(&self, regs: &mut Registers<4>, input: &mut VecDeque<i64>) -> Flow {
+        let value = |reg: &Register| match reg {
+            Register::Literal(v) => *v,
+            _ => regs[reg.index().unwrap()],
+        };
+        let (a, result) = match self {
+            OpCode::Inp(a) => match input.pop_front() {
+                Some(v) => (a, v),
+                None => return Flow::Wait,
+            },
+            OpCode::Add(a, b) => (a, value(a) + value(b)),
+            OpCode::Mul(a, b) => (a, value(a) * value(b)),
+            OpCode::Div(a, b) => (a, value(a) / value(b)),
+            OpCode::Mod(a, b) => (a, value(a) % value(b)),
+            OpCode::Eql(a, b) => (a, (value(a) == value(b)) as i64),
+        };
+        regs[a.index().expect("a register to write to")] = result;
+        Flow::Next
+    }
+}
+
+/// The registers after running the whole `program` on the `inputs`.
+fn run(program: &[OpCode], inputs: &[i64]) -> Registers<4> {
+    let mut alu = Vm::new(program);
+    alu.push_input(inputs.iter().copied());
+    assert_eq!(alu.run(), Stop::Halted, "not enough inputs");
+    *alu.regs()
 }
 
-impl State {
-    fn process(&mut self, op: &OpCode) {
-        match op {
-            OpCode::Add(a, b) => *self.reg_mut(a) = self.reg(a) + self.reg(b),
-            OpCode::Mul(a, b) => *self.reg_mut(a) = self.reg(a) * self.reg(b),
-            OpCode::Div(a, b) => *self.reg_mut(a) = self.reg(a) / self.reg(b),
-            OpCode::Mod(a, b) => *self.reg_mut(a) = self.reg(a) % self.reg(b),
-            OpCode::Eql(a, b) => *self.reg_mut(a) = (self.reg(a) == self.reg(b)) as i64,
-
-            // OpCode::Inp(a) => *self.get_reg_mut(&a) = self.input.pop_front().unwrap() as i64, // self.input is no more
-            OpCode::Inp(_) => unreachable!(),
+/// Inclusive bounds of a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Range {
+    lo: i64,
+    hi: i64,
+}
+
+impl Range {
+    /// The digits of a model number.
+    const INPUT: Range = Range { lo: 1, hi: 9 };
+
+    fn exactly(value: i64) -> Self {
+        Range {
+            lo: value,
+            hi: value,
         }
     }
 
-    fn reg<'a>(&'a self, reg: &'a Register) -> &'a i64 {
-        match reg {
-            Register::Literal(a) => a,
-            Register::W => &self.regs[0],
-            Register::X => &self.regs[1],
-            Register::Y => &self.regs[2],
-            Register::Z => &self.regs[3],
-        }
+    /// The smallest range holding all `values`.
+    fn spanning(values: impl IntoIterator<Item = i64>) -> Self {
+        values.into_iter().fold(
+            Range {
+                lo: i64::MAX,
+                hi: i64::MIN,
+            },
+            |r, v| Range {
+                lo: r.lo.min(v),
+                hi: r.hi.max(v),
+            },
+        )
+    }
+
+    fn contains(&self, value: i64) -> bool {
+        self.lo <= value && value <= self.hi
     }
 
-    fn reg_mut<'a>(&'a mut self, reg: &'a Register) -> &'a mut i64 {
-        match reg {
-            Register::Literal(_) => unreachable!(),
-            Register::W => &mut self.regs[0],
-            Register::X => &mut self.regs[1],
-            Register::Y => &mut self.regs[2],
-            Register::Z => &mut self.regs[3],
+    fn apply(self, op: Op, other: Range) -> Range {
+        let (a, b) = (self, other);
+        match op {
+            Op::Add => Range {
+                lo: a.lo.saturating_add(b.lo),
+                hi: a.hi.saturating_add(b.hi),
+            },
+            Op::Mul => Range::spanning(
+                [(a.lo, b.lo), (a.lo, b.hi), (a.hi, b.lo), (a.hi, b.hi)]
+                    .map(|(x, y)| x.saturating_mul(y)),
+            ),
+            Op::Div => {
+                // division is monotone on either side of 0, which the ALU never divides by
+                let divisors = [b.lo, b.hi, -1, 1]
+                    .into_iter()
+                    .filter(|&d| d != 0 && b.contains(d));
+                Range::spanning(divisors.flat_map(|d| [a.lo / d, a.hi / d]))
+            }
+            // the ALU only takes the remainder of a non-negative by a positive value
+            Op::Mod if a.lo >= 0 && a.hi < b.lo => a,
+            Op::Mod => Range {
+                lo: 0,
+                hi: a.hi.min(b.hi - 1).max(0),
+            },
+            Op::Eql if a.lo == a.hi && a == b => Range::exactly(1),
+            Op::Eql if a.hi < b.lo || b.hi < a.lo => Range::exactly(0),
+            Op::Eql => Range { lo: 0, hi: 1 },
         }
     }
 }
 
-impl Display for State {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "regs:")?;
-        for reg in self.regs {
-            write!(f, " {}", reg)?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+impl Op {
+    fn of(op: &OpCode) -> Option<(Op, Register, Register)> {
+        match *op {
+            OpCode::Inp(_) => None,
+            OpCode::Add(a, b) => Some((Op::Add, a, b)),
+            OpCode::Mul(a, b) => Some((Op::Mul, a, b)),
+            OpCode::Div(a, b) => Some((Op::Div, a, b)),
+            OpCode::Mod(a, b) => Some((Op::Mod, a, b)),
+            OpCode::Eql(a, b) => Some((Op::Eql, a, b)),
+        }
+    }
+
+    fn eval(self, a: i64, b: i64) -> i64 {
+        match self {
+            Op::Add => a + b,
+            Op::Mul => a * b,
+            Op::Div => a / b,
+            Op::Mod => a % b,
+            Op::Eql => (a == b) as i64,
         }
-        writeln!(f, "")
     }
 }
 
-#[derive(Debug, Default)]
-pub struct Data {
-    input: Vec<Vec<OpCode>>,
+/// Value of a register in terms of the inputs.
+#[derive(Debug, PartialEq, Eq)]
+enum Expr {
+    Const(i64),
+    /// The input with the given 0-based index.
+    Input(usize),
+    Apply(Op, Rc<Expr>, Rc<Expr>, Range),
 }
 
-impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+impl Expr {
+    fn range(&self) -> Range {
+        match *self {
+            Expr::Const(c) => Range::exactly(c),
+            Expr::Input(_) => Range::INPUT,
+            Expr::Apply(.., range) => range,
+        }
+    }
+
+    /// `a op b`, simplified as far as the ranges of `a` and `b` allow.
+    ///
+    /// This undoes the stack the MONAD keeps in base 26: `(a * k + b) / k` is `a` and
+    /// `(a * k + b) % k` is `b` when `0 <= b < k`.
+    fn apply(op: Op, a: Rc<Expr>, b: Rc<Expr>) -> Rc<Expr> {
+        let range = a.range().apply(op, b.range());
+        if range.lo == range.hi {
+            return Rc::new(Expr::Const(range.lo));
+        }
+
+        // `p * k + q` with `p >= 0` and `0 <= q < k`
+        let split = |e: &Expr, k: i64| match e {
+            Expr::Apply(Op::Add, s, q, _) => match &**s {
+                Expr::Apply(Op::Mul, p, m, _)
+                    if **m == Expr::Const(k)
+                        && p.range().lo >= 0
+                        && q.range().lo >= 0
+                        && q.range().hi < k =>
+                {
+                    Some((p.clone(), q.clone()))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+        match (op, &*a, &*b) {
+            (Op::Add, _, Expr::Const(0)) | (Op::Mul | Op::Div, _, Expr::Const(1)) => a,
+            (Op::Add, Expr::Const(0), _) | (Op::Mul, Expr::Const(1), _) => b,
+            (Op::Add, Expr::Apply(Op::Add, x, c1, _), Expr::Const(c2)) => match **c1 {
+                Expr::Const(c1) => Expr::apply(Op::Add, x.clone(), Rc::new(Expr::Const(c1 + c2))),
+                _ => Rc::new(Expr::Apply(op, a, b, range)),
+            },
+            (Op::Mod, _, Expr::Const(_)) if a.range() == range => a,
+            (Op::Div | Op::Mod, _, &Expr::Const(k)) if split(&a, k).is_some() => {
+                let (p, q) = split(&a, k).unwrap();
+                match op {
+                    Op::Div => p,
+                    _ => q,
+                }
+            }
+            _ => Rc::new(Expr::Apply(op, a, b, range)),
+        }
+    }
+
+    /// The value for the given inputs, `None` if it depends on one not known yet.
+    fn eval(&self, inputs: &[i64]) -> Option<i64> {
+        match self {
+            Expr::Const(c) => Some(*c),
+            Expr::Input(i) => inputs.get(*i).copied(),
+            Expr::Apply(op, a, b, _) => Some(op.eval(a.eval(inputs)?, b.eval(inputs)?)),
+        }
+    }
 
-        (a, b)
+    /// The last input the value depends on.
+    fn last_input(&self) -> Option<usize> {
+        match self {
+            Expr::Const(_) => None,
+            Expr::Input(i) => Some(*i),
+            Expr::Apply(_, a, b, _) => a.last_input().max(b.last_input()),
+        }
     }
 }
 
-impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        let insts: Vec<OpCode> = lines
-            .map(|line| parse(line).finish().map(|(_, x)| x).unwrap())
-            .collect();
-        // split on each `inp` instruction and remove it
-        let blocks = insts
-            .chunks(18)
-            .map(|c| c.iter().skip(1).copied().collect())
-            .collect::<Vec<_>>();
-        self.input = blocks;
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Const(c) => write!(f, "{c}"),
+            Expr::Input(i) => write!(f, "d{}", i + 1),
+            Expr::Apply(Op::Add, a, b, _) => match **b {
+                Expr::Const(c) if c < 0 => write!(f, "{a} - {}", -c),
+                _ => write!(f, "{a} + {b}"),
+            },
+            Expr::Apply(op, a, b, _) => {
+                let symbol = match op {
+                    Op::Mul => "*",
+                    Op::Div => "/",
+                    Op::Mod => "%",
+                    _ => "==",
+                };
+                let operand = |e: &Expr| match e {
+                    Expr::Apply(..) => format!("({e})"),
+                    _ => e.to_string(),
+                };
+                write!(f, "{} {symbol} {}", operand(a), operand(b))
+            }
+        }
     }
+}
 
-    fn puzzle1(&mut self) -> u64 {
-        self.find(&[9, 8, 7, 6, 5, 4, 3, 2, 1]) as u64
+/// A condition on the inputs that the branch taken by an `eql` depends on.
+#[derive(Debug)]
+struct Constraint {
+    lhs: Rc<Expr>,
+    rhs: Rc<Expr>,
+    equal: bool,
+}
+
+impl Constraint {
+    fn holds(&self, inputs: &[i64]) -> Option<bool> {
+        Some((self.lhs.eval(inputs)? == self.rhs.eval(inputs)?) == self.equal)
     }
 
-    fn puzzle2(&mut self) -> u64 {
-        self.find(&[1, 2, 3, 4, 5, 6, 7, 8, 9]) as u64
+    fn last_input(&self) -> usize {
+        self.lhs
+            .last_input()
+            .max(self.rhs.last_input())
+            .unwrap_or(0)
     }
+}
 
-    fn find(&self, range: &[i64; 9]) -> i64 {
-        // (block, z) -> num
-        let mut cache: HashMap<(usize, i64), Option<i64>> = HashMap::new();
-        let res = find_recursive(&mut cache, &self.input, 0, 0, range).unwrap();
-        res.to_string()
-            .chars()
-            .rev()
-            .collect::<String>()
-            .parse()
-            .unwrap()
+impl Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation = if self.equal { "==" } else { "!=" };
+        write!(f, "{} {relation} {}", self.lhs, self.rhs)
     }
 }
 
-fn find_recursive(
-    cache: &mut HashMap<(usize, i64), Option<i64>>,
-    blocks: &Vec<Vec<OpCode>>,
-    block: usize,
-    z: i64,
-    range: &[i64; 9],
-) -> Option<i64> {
-    if let Some(&res) = cache.get(&(block, z)) {
-        return res;
+/// A way through the program under construction, see [`Analysis`].
+#[derive(Clone)]
+struct Path {
+    pc: usize,
+    regs: [Rc<Expr>; 4],
+    inputs: usize,
+    constraints: Vec<Rc<Constraint>>,
+}
+
+/// Whether `z` may end up 0 when running `program` from registers in the given ranges.
+///
+/// This is the abstract interpretation of the program over ranges, which loses track of how the
+/// registers relate to each other but is cheap enough to prune every branch with.
+fn may_accept(program: &[OpCode], mut regs: [Range; 4]) -> bool {
+    for op in program {
+        match Op::of(op) {
+            None => {
+                let OpCode::Inp(a) = op else { unreachable!() };
+                regs[a.index().unwrap()] = Range::INPUT;
+            }
+            Some((op, a, b)) => {
+                let rb = match b {
+                    Register::Literal(v) => Range::exactly(v),
+                    _ => regs[b.index().unwrap()],
+                };
+                let a = a.index().unwrap();
+                regs[a] = regs[a].apply(op, rb);
+            }
+        }
     }
+    regs[3].contains(0)
+}
 
-    for &num in range {
-        // execute step
-        let mut state = State {
-            regs: [num, 0, 0, z],
-        };
-        for op in &blocks[block] {
-            state.process(op);
+/// The conditions under which a program accepts its inputs, found by running it symbolically.
+///
+/// Registers hold expressions of the inputs. An `eql` that can go either way splits the run in
+/// two, one for each outcome, and runs that can no longer end with `z == 0` are dropped. What is
+/// left are the ways through the program with the constraints on the inputs they need.
+pub struct Analysis {
+    inputs: usize,
+    paths: Vec<Vec<Rc<Constraint>>>,
+}
+
+impl Analysis {
+    fn new(program: &[OpCode]) -> Self {
+        let zero = Rc::new(Expr::Const(0));
+        let mut todo = vec![Path {
+            pc: 0,
+            regs: [zero.clone(), zero.clone(), zero.clone(), zero],
+            inputs: 0,
+            constraints: vec![],
+        }];
+        let mut paths = vec![];
+        let mut inputs = 0;
+
+        while let Some(mut path) = todo.pop() {
+            let Some(op) = program.get(path.pc) else {
+                // accepted for sure, or under one more condition
+                inputs = inputs.max(path.inputs);
+                let z = &path.regs[3];
+                if **z != Expr::Const(0) {
+                    path.constraints.push(Rc::new(Constraint {
+                        lhs: z.clone(),
+                        rhs: Rc::new(Expr::Const(0)),
+                        equal: true,
+                    }));
+                }
+                paths.push(path.constraints);
+                continue;
+            };
+            path.pc += 1;
+
+            let (op, a, b) = match Op::of(op) {
+                Some(op) => op,
+                None => {
+                    let OpCode::Inp(a) = op else { unreachable!() };
+                    path.regs[a.index().unwrap()] = Rc::new(Expr::Input(path.inputs));
+                    path.inputs += 1;
+                    todo.push(path);
+                    continue;
+                }
+            };
+            let lhs = path.regs[a.index().unwrap()].clone();
+            let rhs = match b {
+                Register::Literal(v) => Rc::new(Expr::Const(v)),
+                _ => path.regs[b.index().unwrap()].clone(),
+            };
+
+            let result = match op {
+                Op::Eql if lhs.range().apply(op, rhs.range()) == (Range { lo: 0, hi: 1 }) => {
+                    // try both outcomes
+                    for equal in [false, true] {
+                        let mut branch = path.clone();
+                        branch.regs[a.index().unwrap()] = Rc::new(Expr::Const(equal as i64));
+                        let ranges = branch.regs.clone().map(|r| r.range());
+                        if may_accept(&program[branch.pc..], ranges) {
+                            branch.constraints.push(Rc::new(Constraint {
+                                lhs: lhs.clone(),
+                                rhs: rhs.clone(),
+                                equal,
+                            }));
+                            todo.push(branch);
+                        }
+                    }
+                    continue;
+                }
+                _ => Expr::apply(op, lhs, rhs),
+            };
+            path.regs[a.index().unwrap()] = result;
+            todo.push(path);
         }
-        let z = state.reg(&Register::Z).to_owned();
 
-        // are we there yet?
-        if block == blocks.len() - 1 {
-            if z == 0 {
-                cache.insert((block, z), Some(num));
-                return Some(num);
+        Analysis { inputs, paths }
+    }
+
+    /// The first inputs accepted, trying `digits` in order for every input from the first one.
+    fn first_accepted(&self, digits: &[i64; 9]) -> Option<Vec<i64>> {
+        fn search(
+            constraints: &[Vec<&Constraint>],
+            digits: &[i64; 9],
+            inputs: &mut Vec<i64>,
+        ) -> bool {
+            let i = inputs.len();
+            if i == constraints.len() {
+                return true;
             }
-            continue;
+            for &d in digits {
+                inputs.push(d);
+                if constraints[i].iter().all(|c| c.holds(inputs) == Some(true))
+                    && search(constraints, digits, inputs)
+                {
+                    return true;
+                }
+                inputs.pop();
+            }
+            false
         }
 
-        // we need to go deeper!
-        if let Some(res) = find_recursive(cache, blocks, block + 1, z, range) {
-            cache.insert((block, z), Some(res * 10 + num));
-            return Some(res * 10 + num);
+        let found = self.paths.iter().filter_map(|path| {
+            // check every constraint as soon as its inputs are known
+            let mut by_input = vec![vec![]; self.inputs];
+            for c in path {
+                by_input[c.last_input()].push(&**c);
+            }
+            let mut inputs = vec![];
+            search(&by_input, digits, &mut inputs).then_some(inputs)
+        });
+
+        // the paths exclude each other, so their best inputs are compared
+        let rank = |inputs: &Vec<i64>| {
+            inputs
+                .iter()
+                .map(|d| digits.iter().position(|x| x == d).unwrap())
+                .collect::<Vec<_>>()
+        };
+        found.min_by_key(rank)
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, path) in self.paths.iter().enumerate() {
+            if self.paths.len() > 1 {
+                writeln!(f, "path {}:", i + 1)?;
+            }
+            for constraint in path {
+                writeln!(f, "{constraint}")?;
+            }
         }
+        Ok(())
     }
+}
 
-    // feels bad man
-    cache.insert((block, z), None);
-    None
+#[derive(Debug, Default)]
+pub struct Data {
+    program: Vec<OpCode>,
+}
+
+impl AdventOfCode for Data {
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self> {
+        Ok(Data {
+            program: lines(input, parse)?,
+        })
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        self.puzzle1().ok_or(Error::NoSolution)
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        self.puzzle2().ok_or(Error::NoSolution)
+    }
+}
+
+impl Data {
+    fn puzzle1(&mut self) -> Option<u64> {
+        self.find(&[9, 8, 7, 6, 5, 4, 3, 2, 1])
+    }
+
+    fn puzzle2(&mut self) -> Option<u64> {
+        self.find(&[1, 2, 3, 4, 5, 6, 7, 8, 9])
+    }
+
+    fn find(&self, digits: &[i64; 9]) -> Option<u64> {
+        let analysis = Analysis::new(&self.program);
+        tracing::debug!("accepted inputs:\n{analysis}");
+
+        let inputs = analysis.first_accepted(digits)?;
+        debug_assert_eq!(run(&self.program, &inputs)[3], 0);
+        Some(inputs.iter().fold(0, |n, &d| n * 10 + d as u64))
+    }
 }
 
 fn parse_register(line: &str) -> nom::IResult<&str, Register> {
-    map_res(one_of("wxyz"), |op| Register::try_from(op))(line)
+    map_res(one_of("wxyz"), Register::try_from)(line)
 }
 
 fn parse_literal(line: &str) -> nom::IResult<&str, Register> {
-    map(i64, |num| Register::Literal(num))(line)
+    map(i64, Register::Literal)(line)
 }
 
 fn parse_reg_or_lit(line: &str) -> nom::IResult<&str, Register> {
@@ -247,39 +593,102 @@ fn parse(line: &str) -> nom::IResult<&str, OpCode> {
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use itertools::Itertools;
+
+    use crate::AdventOfCode;
+
+    use super::{run, Analysis, Data};
+
+    const INPUT: &str = "inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2";
+
+    /// A MONAD like program, each block pushes `d + add_y` to a stack in `z` if `div_z` is 1,
+    /// or pops and compares the digit to the popped value plus `add_x` if it is 26.
+    fn monad(blocks: &[(i64, i64, i64)]) -> String {
+        blocks
+            .iter()
+            .map(|(div_z, add_x, add_y)| {
+                format!(
+                    "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {div_z}\nadd x {add_x}\neql x w\n\
+                     eql x 0\nmul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\n\
+                     add y {add_y}\nmul y x\nadd z y"
+                )
+            })
+            .join("\n")
+    }
 
-    use super::{Data, State, DAY};
+    /// Largest and smallest accepted inputs, trying all of them.
+    fn brute_force(data: &Data, inputs: usize) -> (u64, u64) {
+        let accepted: Vec<u64> = (0..inputs)
+            .map(|_| 1..=9)
+            .multi_cartesian_product()
+            .filter(|digits| run(&data.program, digits)[3] == 0)
+            .map(|digits| digits.iter().fold(0, |n, &d| n * 10 + d as u64))
+            .collect();
+        (
+            *accepted.iter().max().unwrap(),
+            *accepted.iter().min().unwrap(),
+        )
+    }
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
-
-        let mut state = State { regs: [7, 0, 0, 0] };
-        for op in &data.input[0] {
-            state.process(op);
-        }
-        assert_eq!(state.regs, [0, 1, 1, 1]);
+        let data = Data::parse(INPUT).unwrap();
+        assert_eq!(run(&data.program, &[7]), [0, 1, 1, 1]);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
-
-        let mut state = State { regs: [8, 0, 0, 0] };
-        for op in &data.input[0] {
-            state.process(op);
-        }
-        assert_eq!(state.regs, [1, 0, 0, 0]);
+        let data = Data::parse(INPUT).unwrap();
+        assert_eq!(run(&data.program, &[8]), [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn monad_constraints() {
+        let input = monad(&[(1, 12, 4), (1, 11, 7), (26, -5, 3), (26, -8, 1)]);
+        let mut data = Data::parse(&input).unwrap();
+
+        let analysis = Analysis::new(&data.program);
+        assert_eq!(analysis.to_string(), "d2 + 2 == d3\nd1 - 4 == d4\n");
+
+        assert_eq!(data.puzzle1(), Some(9795));
+        assert_eq!(data.puzzle2(), Some(5131));
+        assert_eq!(brute_force(&data, 4), (9795, 5131));
+
+        // a push without a matching pop never gets `z` back to 0
+        let mut data = Data::parse(&monad(&[(1, 12, 4), (1, 11, 7), (26, -5, 3)])).unwrap();
+        assert_eq!(data.puzzle1(), None);
+    }
+
+    #[test]
+    fn any_program() {
+        // accepts `d1 + 2 * d2 == 20`, as well as any `d1` with `d2 == 1`
+        let input = "inp w
+inp x
+mul x 2
+add w x
+eql w 20
+eql x 2
+add w x
+eql w 0
+add z w";
+        let mut data = Data::parse(input).unwrap();
+
+        let analysis = Analysis::new(&data.program);
+        assert_eq!(analysis.paths.len(), 3);
+
+        assert_eq!(data.puzzle1(), Some(91));
+        assert_eq!(data.puzzle2(), Some(11));
+        assert_eq!(brute_force(&data, 2), (91, 11));
     }
 }
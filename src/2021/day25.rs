@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
 // use nom::{
 //     branch::alt,
@@ -9,9 +9,13 @@ use std::{collections::HashMap, path::PathBuf};
 //     Finish,
 // };
 
-use crate::AdventOfCode;
+use glam::IVec2;
 
-const DAY: &str = "day25";
+use crate::{
+    grid::Grid,
+    visual::{self, Frame, Visualise},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MapPosition {
@@ -28,51 +32,47 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1();
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2();
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        let tmp: Vec<Vec<MapPosition>> = lines
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        'v' => MapPosition::South,
-                        '>' => MapPosition::East,
-                        '.' => MapPosition::Empty,
-                        _ => unreachable!(),
-                    })
-                    .collect()
-            })
+    fn load(&mut self, input: &str) -> Result<()> {
+        let grid = Grid::parse(input, |c| match c {
+            'v' => Some(MapPosition::South),
+            '>' => Some(MapPosition::East),
+            '.' => Some(MapPosition::Empty),
+            _ => None,
+        })?;
+
+        self.max_x = grid.width();
+        self.max_y = grid.height();
+        self.input = grid
+            .iter()
+            .map(|(pos, &cell)| ((pos.x as usize, pos.y as usize), cell))
             .collect();
-
-        self.input.clear();
-        self.max_x = tmp[0].len();
-        self.max_y = tmp.len();
-        for y in 0..tmp.len() {
-            for x in 0..tmp[y].len() {
-                self.input.insert((x, y), tmp[y][x].to_owned());
-            }
-        }
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> u64 {
         let mut i = 1;
 
         while self.step() {
+            visual::show(|| self.frame().with_title(format!("after step {i}")));
             i += 1;
         }
         i
@@ -114,7 +114,6 @@ impl Data {
             }
         }
         self.input = new_map;
-        // self.plot();
 
         // south
         let mut new_map = HashMap::new();
@@ -164,47 +163,78 @@ impl Data {
         }
         p
     }
+}
 
-    #[allow(dead_code)]
-    fn plot(&self) {
-        for y in 0..self.max_y {
-            for x in 0..self.max_x {
-                match self.input.get(&(x, y)) {
-                    None | Some(MapPosition::Empty) => print!("."),
-                    Some(MapPosition::East) => print!(">"),
-                    Some(MapPosition::South) => print!("v"),
-                }
-            }
-            println!("");
+impl Visualise for Data {
+    fn frame(&self) -> Frame {
+        let size = IVec2::new(self.max_x as i32, self.max_y as i32);
+        let mut frame = Frame::new(IVec2::ZERO, size, '.');
+        for (&(x, y), cucumber) in &self.input {
+            let c = match cucumber {
+                MapPosition::Empty => '.',
+                MapPosition::East => '>',
+                MapPosition::South => 'v',
+            };
+            frame.set(IVec2::new(x as i32, y as i32), c);
         }
-        println!("");
+        frame
     }
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
-
-    use super::{Data, DAY};
+mod tests {
+    use crate::{
+        visual::{set_sink, Recorder},
+        AdventOfCode,
+    };
+
+    use super::Data;
+
+    const INPUT: &str = "v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 58);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 0);
     }
+
+    #[test]
+    fn frames() {
+        let recorder = Recorder::default();
+        set_sink(Some(Box::new(recorder.clone())));
+        let mut data = Data::parse(INPUT).unwrap();
+        data.puzzle1();
+        set_sink(None);
+
+        let frames = recorder.frames();
+        assert_eq!(frames.len(), 57);
+        assert_eq!(
+            frames[0].to_string(),
+            "\
+after step 1
+....>.>v.>
+v.v>.>v.v.
+>v>>..>v..
+>>v>v>.>.v
+.>v.v...v.
+v>>.>vvv..
+..v...>>..
+vv...>>vv.
+>.v.v..v.v"
+        );
+    }
 }
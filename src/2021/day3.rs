@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use nom::{character::complete::one_of, combinator::recognize, error::context, multi::many1};
 
-use crate::AdventOfCode;
-
-const DAY: &str = "day3";
+use crate::{
+    parse::{lines, IResult, ParseError},
+    AdventOfCode, Error, Result,
+};
 
 #[derive(Debug, Default)]
 pub struct Data {
@@ -10,27 +11,37 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1() as u64;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2() as u64;
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-        self.input = input.lines().map(|s| s.to_owned()).collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        let numbers: Vec<&str> = lines(input, binary)?;
+        let width = numbers
+            .first()
+            .ok_or(Error::Parse("no numbers".into()))?
+            .len();
+        if let Some(i) = numbers.iter().position(|n| n.len() != width) {
+            let column = width.min(numbers[i].len()) + 1;
+            return Err(ParseError::new(i + 1, column, "numbers of different widths").into());
+        }
+        self.input = numbers.into_iter().map(|s| s.to_owned()).collect();
+        Ok(())
     }
 
     fn puzzle1(&self) -> i32 {
@@ -100,30 +111,38 @@ fn filter(width: usize, mut input: Vec<String>, equal: bool) -> String {
     input.first().unwrap().to_owned()
 }
 
-#[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+fn binary(input: &str) -> IResult<'_, &str> {
+    context("binary number", recognize(many1(one_of("01"))))(input)
+}
 
-    use super::{Data, DAY};
+#[cfg(test)]
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
+
+    const INPUT: &str = "00100
+11110
+10110
+10111
+10101
+01111
+00111
+11100
+10000
+11001
+00010
+01010";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 198);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 230);
     }
 }
@@ -1,8 +1,15 @@
-use std::path::PathBuf;
-
-use crate::AdventOfCode;
-
-const DAY: &str = "day4";
+use nom::{
+    character::complete::{char, line_ending, space0},
+    combinator::map_opt,
+    error::context,
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+};
+
+use crate::{
+    parse::{blank_line, blocks, finish, int, list, spaced, IResult},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug)]
 struct Board {
@@ -11,13 +18,12 @@ struct Board {
 }
 
 impl Board {
-    pub fn new(input: Vec<i32>) -> Self {
-        assert_eq!(input.len(), 25, "wrong input lenght");
-
-        Board {
-            data: input.try_into().unwrap(),
+    /// A board of the 25 numbers row by row, `None` if there are not 25 of them.
+    pub fn new(input: Vec<i32>) -> Option<Self> {
+        Some(Board {
+            data: input.try_into().ok()?,
             marked: [false; 25],
-        }
+        })
     }
 
     pub fn check_number(&mut self, number: &i32) -> bool {
@@ -33,7 +39,7 @@ impl Board {
     pub fn check(&self) -> bool {
         for i in 0..5 {
             // rows
-            if self.marked[0 + i * 5]
+            if self.marked[i * 5]
                 && self.marked[1 + i * 5]
                 && self.marked[2 + i * 5]
                 && self.marked[3 + i * 5]
@@ -43,7 +49,7 @@ impl Board {
             }
 
             // columns
-            if self.marked[0 + i]
+            if self.marked[i]
                 && self.marked[5 + i]
                 && self.marked[10 + i]
                 && self.marked[15 + i]
@@ -74,56 +80,28 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1() as u64;
+    type Answer1 = i32;
+    type Answer2 = i32;
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2() as u64;
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        (a, b)
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let mut lines = input.lines();
-        self.input = lines
-            .next()
-            .unwrap()
-            .split_terminator(',')
-            .map(|s| s.parse().expect("failed to parse str"))
-            .collect();
-        assert!(lines.next().unwrap() == String::new());
-
-        // fill boards
-        while let Some(next_line) = lines.next() {
-            let mut board = next_line.to_owned();
-            for _ in 0..4 {
-                board += " ";
-                board += lines.next().unwrap();
-            }
-            let board = Board::new(
-                board
-                    .split_ascii_whitespace()
-                    .map(|s| s.parse().unwrap())
-                    .collect(),
-            );
-            self.boards.push(board);
-
-            if lines.next().is_none() {
-                break;
-            }
-        }
+    fn load(&mut self, input: &str) -> Result<()> {
+        (self.input, self.boards) = finish(input, parse(input))?;
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> i32 {
@@ -158,30 +136,53 @@ impl Data {
     }
 }
 
+fn parse_board(input: &str) -> IResult<'_, Board> {
+    let rows = separated_list1(line_ending, preceded(space0, spaced(int)));
+    context(
+        "board of 5 by 5 numbers",
+        map_opt(rows, |rows| Board::new(rows.concat())),
+    )(input)
+}
+
+fn parse(input: &str) -> IResult<'_, (Vec<i32>, Vec<Board>)> {
+    separated_pair(list(char(','), int), blank_line, blocks(parse_board))(input)
+}
+
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
 
-    use super::{Data, DAY};
+    const INPUT: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 4512);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 1924);
     }
 }
@@ -1,8 +1,14 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-use crate::AdventOfCode;
+use glam::IVec2;
 
-const DAY: &str = "day5";
+use nom::{bytes::complete::tag, combinator::map, sequence::separated_pair};
+
+use crate::{
+    parse::{coords2, lines, ws, IResult},
+    visual::{self, Frame, Visualise},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 struct Coords {
@@ -24,48 +30,28 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1() as u64;
+    type Answer1 = i32;
+    type Answer2 = i32;
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2() as u64;
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        (a, b)
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
+
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        self.input = lines
-            .map(|s| {
-                // start
-                let mut sides = s.split_terminator("->");
-                let mut tmp = sides.next().unwrap().split_terminator(',');
-                let a = Coords {
-                    x: tmp.next().unwrap().trim().parse().unwrap(),
-                    y: tmp.next().unwrap().trim().parse().unwrap(),
-                };
-
-                let mut tmp = sides.next().unwrap().split_terminator(',');
-                let b = Coords {
-                    x: tmp.next().unwrap().trim().parse().unwrap(),
-                    y: tmp.next().unwrap().trim().parse().unwrap(),
-                };
-
-                Vents { a, b }
-            })
-            .collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = lines(input, parse)?;
+        Ok(())
     }
 
     fn build_map(&mut self, include_diag: bool) {
@@ -75,11 +61,11 @@ impl Data {
             let straight = vent.a.x == vent.b.x || vent.a.y == vent.b.y;
             let diag = (vent.a.x - vent.b.x).abs() == (vent.a.y - vent.b.y).abs();
 
-            if (!include_diag && !straight) || (include_diag && (!straight && !diag)) {
+            if !straight && (!include_diag || !diag) {
                 continue;
             }
 
-            let mut pos = vent.a.clone();
+            let mut pos = vent.a;
             // cheap a** do while, don't judge me
             while {
                 let entry = self.map.entry(pos).or_insert(0);
@@ -102,21 +88,7 @@ impl Data {
                 done
             } {}
         }
-    }
-
-    #[allow(unused)]
-    fn draw_map(&mut self, width: i32) {
-        for y in 0..width {
-            for x in 0..width {
-                let coord = Coords { x, y };
-                if let Some(val) = self.map.get_key_value(&coord) {
-                    print!("{}", val.1);
-                } else {
-                    print!("-");
-                }
-            }
-            print!("\n");
-        }
+        visual::draw(self);
     }
 
     fn puzzle1(&mut self) -> i32 {
@@ -145,30 +117,74 @@ impl Data {
     }
 }
 
-#[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+/// The number of vents over each point, more than 9 are drawn as `+`.
+impl Visualise for Data {
+    fn frame(&self) -> Frame {
+        let points = self.map.iter().map(|(pos, &count)| {
+            let c = char::from_digit(count as u32, 10).unwrap_or('+');
+            (IVec2::new(pos.x, pos.y), c)
+        });
+        Frame::from_points(points, '.')
+    }
+}
+
+fn parse_coords(input: &str) -> IResult<'_, Coords> {
+    map(coords2, |(x, y)| Coords { x, y })(input)
+}
 
-    use super::{Data, DAY};
+fn parse(input: &str) -> IResult<'_, Vents> {
+    map(
+        separated_pair(parse_coords, ws(tag("->")), parse_coords),
+        |(a, b)| Vents { a, b },
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{visual::Visualise, AdventOfCode};
+
+    use super::Data;
+
+    const INPUT: &str = "0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 5);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 12);
     }
+
+    #[test]
+    fn frame() {
+        let mut data = Data::parse(INPUT).unwrap();
+        data.build_map(true);
+        assert_eq!(
+            data.frame().to_string(),
+            "\
+1.1....11.
+.111...2..
+..2.1.111.
+...1.2.2..
+.112313211
+...1.2....
+..1...1...
+.1.....1..
+1.......1.
+222111...."
+        );
+    }
 }
@@ -1,8 +1,14 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-use crate::AdventOfCode;
+use nom::character::complete::char;
 
-const DAY: &str = "day6";
+use crate::{
+    parse::{finish, int, list},
+    AdventOfCode, Param, Params, Result,
+};
+
+const PARAMS1: &[Param] = &[Param::new("days", 80)];
+const PARAMS2: &[Param] = &[Param::new("days", 256)];
 
 #[derive(Debug)]
 struct FishSchool {
@@ -13,33 +19,42 @@ struct FishSchool {
 #[derive(Debug, Default)]
 pub struct Data {
     input: Vec<FishSchool>,
+    days: i32,
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1(80) as u64;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn params(part: u8) -> &'static [Param] {
+        match part {
+            1 => PARAMS1,
+            _ => PARAMS2,
+        }
+    }
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle1(256 - 80) as u64; // this is essential!! self.input is not reset between calls!
+    fn configure(&mut self, params: &Params) {
+        self.days = params.get("days") as i32;
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1(self.days))
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle1(self.days))
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.split_terminator(',');
-        let input: Vec<i32> = lines.map(|s| s.trim_end().parse().unwrap()).collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        let input: Vec<i32> = finish(input, list(char(','), int)(input))?;
 
         let mut school = HashMap::new();
         for entry in input {
@@ -52,6 +67,7 @@ impl Data {
                 .count += 1;
         }
         self.input = school.into_values().collect();
+        Ok(())
     }
 
     fn tick_schools(&mut self) {
@@ -97,29 +113,28 @@ impl Data {
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::{AdventOfCode, Params};
+
+    use super::{Data, PARAMS1, PARAMS2};
 
-    use super::{Data, DAY};
+    const INPUT: &str = "3,4,3,1,2";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
-        assert_eq!(data.puzzle1(80), 5934);
+        let mut data = Data::parse(INPUT).unwrap();
+        data.configure(&Params::new(PARAMS1).with("days", 18));
+        assert_eq!(data.part1().unwrap(), 26);
+
+        let mut data = Data::parse(INPUT).unwrap();
+        data.configure(&Params::new(PARAMS1));
+        assert_eq!(data.part1().unwrap(), 5934);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
-        assert_eq!(data.puzzle1(256), 26984457539);
+        let mut data = Data::parse(INPUT).unwrap();
+        data.configure(&Params::new(PARAMS2));
+        assert_eq!(data.part2().unwrap(), 26984457539);
     }
 }
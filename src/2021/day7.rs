@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use nom::character::complete::char;
 
-use crate::AdventOfCode;
-
-const DAY: &str = "day7";
+use crate::{
+    parse::{finish, int, list},
+    AdventOfCode, Result,
+};
 
 #[derive(Debug, Default)]
 pub struct Data {
@@ -10,42 +11,40 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1() as u64;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2() as u64;
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.split_terminator(',');
-        self.input = lines.map(|s| s.trim_end().parse().unwrap()).collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = finish(input, list(char(','), int)(input))?;
+        Ok(())
     }
 
     fn solve(&self, calc_costs: &dyn Fn(&Self, i32) -> i32) -> i32 {
         // start at average position
-        let sum = self.input.iter().fold(0, |acc, pos| acc + pos);
+        let sum = self.input.iter().sum::<i32>();
         let mut average = sum / self.input.len() as i32;
 
         // cheap a** Newton's method
-        let mut costs = calc_costs(&self, average);
+        let mut costs = calc_costs(self, average);
         loop {
-            let a = calc_costs(&self, average + 1);
-            let b = calc_costs(&self, average - 1);
+            let a = calc_costs(self, average + 1);
+            let b = calc_costs(self, average - 1);
 
             if a < costs && a < b {
                 average += 1;
@@ -54,7 +53,7 @@ impl Data {
             } else {
                 break;
             }
-            costs = calc_costs(&self, average);
+            costs = calc_costs(self, average);
         }
 
         costs
@@ -91,7 +90,7 @@ fn calc_costs_2(data: &Data, target_pos: i32) -> i32 {
         return i32::MAX;
     }
     let cost = data.input.iter().fold(0, |acc, pos| {
-        acc + sum_thing((pos - target_pos).abs() as u128)
+        acc + sum_thing((pos - target_pos).unsigned_abs() as u128)
     });
     if cost < i32::MAX as u128 {
         cost as i32
@@ -101,29 +100,22 @@ fn calc_costs_2(data: &Data, target_pos: i32) -> i32 {
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
 
-    use super::{Data, DAY};
+    const INPUT: &str = "16,1,2,0,4,2,7,1,2,14";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 37);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 168);
     }
 }
@@ -1,8 +1,17 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-use crate::AdventOfCode;
+use nom::{
+    character::complete::{char, one_of},
+    combinator::{map, map_opt, recognize},
+    error::context,
+    multi::many1,
+    sequence::separated_pair,
+};
 
-const DAY: &str = "day8";
+use crate::{
+    parse::{lines, spaced, ws, IResult},
+    AdventOfCode, Result,
+};
 
 const KNOWN_DIGIT_LENGHTS: &[(usize, i32)] = &[(2, 1), (3, 7), (4, 4), (7, 8)];
 
@@ -22,7 +31,7 @@ struct Segment {
 fn digit_to_int(digit: &str) -> Option<i32> {
     // sort so we can match against known values
     let mut sorted: Vec<char> = digit.chars().collect::<Vec<char>>();
-    sorted.sort_by(|a, b| a.cmp(b));
+    sorted.sort();
     let sorted = String::from_iter(sorted);
 
     // lookup values
@@ -73,65 +82,28 @@ pub struct Data {
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1() as u64;
+    type Answer1 = i32;
+    type Answer2 = i32;
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2() as u64;
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let lines = input.lines();
-        self.input = lines
-            .map(|s| {
-                let mut halfs = s.split_terminator('|');
-
-                // unique signals
-                let signals = halfs
-                    .next()
-                    .unwrap()
-                    .trim()
-                    .split_ascii_whitespace()
-                    .map(|s| s.to_owned())
-                    .collect::<Vec<String>>()
-                    .try_into()
-                    .unwrap_or_else(|v: Vec<Digit>| {
-                        panic!("Expected a Vec of length {} but it was {}", 10, v.len())
-                    });
-                // ouput
-                let output = halfs
-                    .next()
-                    .unwrap()
-                    .trim()
-                    .split_ascii_whitespace()
-                    .map(|s| s.to_owned())
-                    .collect::<Vec<String>>()
-                    .try_into()
-                    .unwrap_or_else(|v: Vec<Digit>| {
-                        panic!("Expected a Vec of length {} but it was {}", 4, v.len())
-                    });
-
-                Segment {
-                    signals,
-                    output,
-                    mapping: HashMap::new(),
-                }
-            })
-            .collect();
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = lines(input, parse)?;
+        Ok(())
     }
 
     fn solve(&mut self) {
@@ -277,30 +249,50 @@ impl Data {
     }
 }
 
-#[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+/// `N` digits of segment letters separated by spaces.
+fn parse_digits<const N: usize>(input: &str) -> IResult<'_, [Digit; N]> {
+    let digit = map(recognize(many1(one_of("abcdefg"))), str::to_owned);
+    context("digits", map_opt(spaced(digit), |d| d.try_into().ok()))(input)
+}
+
+fn parse(input: &str) -> IResult<'_, Segment> {
+    map(
+        separated_pair(parse_digits, ws(char('|')), parse_digits),
+        |(signals, output)| Segment {
+            signals,
+            output,
+            mapping: HashMap::new(),
+        },
+    )(input)
+}
 
-    use super::{Data, DAY};
+#[cfg(test)]
+mod tests {
+    use crate::AdventOfCode;
+
+    use super::Data;
+
+    const INPUT: &str =
+        "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 26);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 61229);
     }
 }
@@ -1,62 +1,44 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
 
-use crate::AdventOfCode;
+use glam::IVec2;
 
-const DAY: &str = "day9";
+use crate::{
+    grid::Grid,
+    visual::{self, Colour, Frame, Visualise},
+    AdventOfCode, Result,
+};
 
 type Basin = u32;
 
 #[derive(Debug, Default)]
 pub struct Data {
-    input: HashMap<(i32, i32), (i32, Option<Basin>)>,
-    width: i32,
-    height: i32,
+    input: Grid<(i32, Option<Basin>)>,
     next_basin: Basin,
 }
 
 impl AdventOfCode for Data {
-    fn run(&mut self, base_dir: &PathBuf) -> (u64, u64) {
-        self.load(base_dir, String::from(DAY) + ".txt");
-        let a = self.puzzle1() as u64;
+    type Answer1 = i32;
+    type Answer2 = i32;
 
-        // self.load(base_dir, String::from(DAY) + ".txt");
-        let b = self.puzzle2() as u64;
+    fn parse(input: &str) -> Result<Self> {
+        let mut data = Data::default();
+        data.load(input)?;
+        Ok(data)
+    }
+
+    fn part1(&mut self) -> Result<Self::Answer1> {
+        Ok(self.puzzle1())
+    }
 
-        (a, b)
+    fn part2(&mut self) -> Result<Self::Answer2> {
+        Ok(self.puzzle2())
     }
 }
 
 impl Data {
-    fn load(&mut self, base_dir: &PathBuf, test_input: String) {
-        let input_file = base_dir.join(test_input);
-        assert!(
-            input_file.exists(),
-            "input file {} does not exist",
-            input_file.to_string_lossy()
-        );
-        let input = std::fs::read_to_string(input_file).expect("failed to read file");
-
-        // prepare input
-        let mut lines = input.lines();
-        let mut width = -1;
-        let mut height = 0;
-        while let Some(next_line) = lines.next() {
-            if width == -1 {
-                width = next_line.len() as i32;
-            }
-            let mut x = 0;
-            self.input.extend(next_line.chars().map(|c| {
-                let key = (x, height);
-                let val = (c.to_digit(10).unwrap() as i32, None);
-                x += 1;
-                (key, val)
-            }));
-            assert_eq!(x, width);
-            height += 1;
-        }
-
-        self.width = width;
-        self.height = height;
+    fn load(&mut self, input: &str) -> Result<()> {
+        self.input = Grid::parse(input, |c| Some((c.to_digit(10)? as i32, None)))?;
+        Ok(())
     }
 
     fn puzzle1(&mut self) -> i32 {
@@ -65,7 +47,7 @@ impl Data {
             .filter(|entry| {
                 let pos = entry.0;
                 let height = entry.1 .0;
-                self.local_minimum(height, pos)
+                self.local_minimum(height, &pos)
             })
             .map(|entry| entry.1 .0 + 1)
             .sum()
@@ -73,6 +55,7 @@ impl Data {
 
     fn puzzle2(&mut self) -> i32 {
         self.match_basins();
+        visual::draw(self);
         let mut basins_count = HashMap::new();
         for basin in 0..self.next_basin {
             basins_count.insert(
@@ -86,7 +69,7 @@ impl Data {
 
         let mut sum = 1;
         for _ in 0..3 {
-            let max_entry = basins_count.iter().max_by(|a, b| a.1.cmp(&b.1)).unwrap();
+            let max_entry = basins_count.iter().max_by(|a, b| a.1.cmp(b.1)).unwrap();
             // needed for last line
             let max_entry = (max_entry.0.to_owned(), max_entry.1.to_owned());
             sum *= max_entry.1 as i32;
@@ -95,54 +78,23 @@ impl Data {
         sum
     }
 
-    #[allow(dead_code)]
-    fn print_map(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                match self.input.get(&(x, y)).unwrap().0 {
-                    0 | 1 | 2 => print!("."),
-                    3 | 4 | 5 => print!(":"),
-                    6 | 7 | 8 => print!("a"),
-                    9 => print!("#"),
-                    _ => unreachable!(),
-                }
-            }
-            println!();
-        }
-    }
-
-    #[allow(dead_code)]
-    fn print_basin(&self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                match self.input.get(&(x, y)).unwrap().1 {
-                    None => print!(" "),
-                    Some(val) => print!("{}", val % 10),
-                }
-            }
-            println!();
-        }
-    }
-
     fn match_basins(&mut self) {
         // solution: rooted trees!
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.input.get_mut(&(x, y)).unwrap().1.is_some() {
-                    continue;
-                }
-                // find existing basin or creat one
-                let (basin, way) = self.march_down(&(x, y));
-                // mark walk
-                for steps in way {
-                    self.input.get_mut(&steps).unwrap().1 = basin;
-                }
+        for pos in self.input.positions().collect::<Vec<_>>() {
+            if self.input[pos].1.is_some() {
+                continue;
+            }
+            // find existing basin or creat one
+            let (basin, way) = self.march_down(&pos);
+            // mark walk
+            for steps in way {
+                self.input[steps].1 = basin;
             }
         }
     }
 
-    fn march_down(&mut self, start_pos: &(i32, i32)) -> (Option<Basin>, Vec<(i32, i32)>) {
-        let height = self.input.get(start_pos).unwrap();
+    fn march_down(&mut self, start_pos: &IVec2) -> (Option<Basin>, Vec<IVec2>) {
+        let height = self.input[*start_pos];
         if height.0 == 9 {
             return (None, vec![]);
         }
@@ -160,12 +112,12 @@ impl Data {
             }
 
             for new_pos in [
-                (pos.0, pos.1 - 1),
-                (pos.0, pos.1 + 1),
-                (pos.0 - 1, pos.1),
-                (pos.0 + 1, pos.1),
+                pos + IVec2::NEG_Y,
+                pos + IVec2::Y,
+                pos + IVec2::NEG_X,
+                pos + IVec2::X,
             ] {
-                if let Some(neighbor) = self.input.get(&new_pos) {
+                if let Some(neighbor) = self.input.get(new_pos) {
                     if neighbor.1.is_some() {
                         return (neighbor.1, way);
                     }
@@ -179,38 +131,62 @@ impl Data {
         }
     }
 
-    fn local_minimum(&self, height: i32, pos: &(i32, i32)) -> bool {
-        self.input.get(&(pos.0 + 1, pos.1)).unwrap_or(&(10, None)).0 > height
-            && self.input.get(&(pos.0, pos.1 + 1)).unwrap_or(&(10, None)).0 > height
-            && self.input.get(&(pos.0 - 1, pos.1)).unwrap_or(&(10, None)).0 > height
-            && self.input.get(&(pos.0, pos.1 - 1)).unwrap_or(&(10, None)).0 > height
+    fn local_minimum(&self, height: i32, pos: &IVec2) -> bool {
+        self.input
+            .neighbours4(*pos)
+            .all(|n| self.input[n].0 > height)
+    }
+}
+
+/// The heights from `.` to `#`, coloured by basin once they are known.
+impl Visualise for Data {
+    fn frame(&self) -> Frame {
+        const COLOURS: [Colour; 6] = [
+            Colour::Red,
+            Colour::Green,
+            Colour::Yellow,
+            Colour::Blue,
+            Colour::Magenta,
+            Colour::Cyan,
+        ];
+
+        let mut frame = Frame::from_grid(&self.input, |&(height, _)| match height {
+            0..=2 => '.',
+            3..=5 => ':',
+            6..=8 => 'a',
+            _ => '#',
+        });
+        for (pos, &(_, basin)) in self.input.iter() {
+            if let Some(basin) = basin {
+                let c = frame.get(pos).unwrap();
+                frame.paint(pos, c, COLOURS[basin as usize % COLOURS.len()]);
+            }
+        }
+        frame
     }
 }
 
 #[cfg(test)]
-mod day1 {
-    use std::env;
-    use std::path::PathBuf;
+mod tests {
+    use crate::AdventOfCode;
 
-    use super::{Data, DAY};
+    use super::Data;
+
+    const INPUT: &str = "2199943210
+3987894921
+9856789892
+8767896789
+9899965678";
 
     #[test]
     fn puzzle1() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle1(), 15);
     }
 
     #[test]
     fn puzzle2() {
-        let base_dir: PathBuf = env::current_dir()
-            .expect("failed to get current dir")
-            .join("input/2021");
-        let mut data = Data::default();
-        data.load(&base_dir, String::from(DAY) + "_test.txt");
+        let mut data = Data::parse(INPUT).unwrap();
         assert_eq!(data.puzzle2(), 1134);
     }
 }
@@ -0,0 +1,85 @@
+use crate::registry::Solution;
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+/// All 2021 solutions.
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::aoc::<day1::Data>(2021, 1, 1),
+        Solution::aoc::<day1::Data>(2021, 1, 2),
+        Solution::aoc::<day2::Data>(2021, 2, 1),
+        Solution::aoc::<day2::Data>(2021, 2, 2),
+        Solution::aoc::<day3::Data>(2021, 3, 1),
+        Solution::aoc::<day3::Data>(2021, 3, 2),
+        Solution::aoc::<day4::Data>(2021, 4, 1),
+        Solution::aoc::<day4::Data>(2021, 4, 2),
+        Solution::aoc::<day5::Data>(2021, 5, 1),
+        Solution::aoc::<day5::Data>(2021, 5, 2),
+        Solution::aoc::<day6::Data>(2021, 6, 1),
+        Solution::aoc::<day6::Data>(2021, 6, 2),
+        Solution::aoc::<day7::Data>(2021, 7, 1),
+        Solution::aoc::<day7::Data>(2021, 7, 2),
+        Solution::aoc::<day8::Data>(2021, 8, 1),
+        Solution::aoc::<day8::Data>(2021, 8, 2),
+        Solution::aoc::<day9::Data>(2021, 9, 1),
+        Solution::aoc::<day9::Data>(2021, 9, 2),
+        Solution::aoc::<day10::Data>(2021, 10, 1),
+        Solution::aoc::<day10::Data>(2021, 10, 2),
+        Solution::aoc::<day11::Data>(2021, 11, 1),
+        Solution::aoc::<day11::Data>(2021, 11, 2),
+        Solution::aoc::<day12::Data>(2021, 12, 1),
+        Solution::aoc_with(2021, 12, 1, day12::Data::part1_recursive).variant("recursive"),
+        Solution::aoc::<day12::Data>(2021, 12, 2),
+        Solution::aoc_with(2021, 12, 2, day12::Data::part2_recursive).variant("recursive"),
+        Solution::aoc::<day13::Data>(2021, 13, 1),
+        Solution::aoc::<day13::Data>(2021, 13, 2),
+        Solution::aoc::<day14::Data>(2021, 14, 1),
+        Solution::aoc::<day14::Data>(2021, 14, 2),
+        Solution::aoc::<day15::Data>(2021, 15, 1),
+        Solution::aoc::<day15::Data>(2021, 15, 2),
+        Solution::aoc::<day16::Data>(2021, 16, 1),
+        Solution::aoc::<day16::Data>(2021, 16, 2),
+        Solution::aoc::<day17::Data>(2021, 17, 1),
+        Solution::aoc::<day17::Data>(2021, 17, 2),
+        Solution::aoc::<day18::Data>(2021, 18, 1),
+        Solution::aoc::<day18::Data>(2021, 18, 2),
+        Solution::aoc::<day19::Data>(2021, 19, 1),
+        Solution::aoc::<day19::Data>(2021, 19, 2),
+        Solution::aoc::<day20::Data>(2021, 20, 1),
+        Solution::aoc::<day20::Data>(2021, 20, 2),
+        Solution::aoc::<day21::Data>(2021, 21, 1),
+        Solution::aoc::<day21::Data>(2021, 21, 2),
+        Solution::aoc::<day22::Data>(2021, 22, 1),
+        Solution::aoc::<day22::Data>(2021, 22, 2),
+        Solution::aoc::<day23::Data>(2021, 23, 1),
+        Solution::aoc::<day23::Data>(2021, 23, 2),
+        Solution::aoc::<day24::Data>(2021, 24, 1),
+        Solution::aoc::<day24::Data>(2021, 24, 2),
+        Solution::aoc::<day25::Data>(2021, 25, 1),
+        Solution::aoc::<day25::Data>(2021, 25, 2),
+    ]
+}
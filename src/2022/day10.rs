@@ -1,112 +1,78 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{line_ending, one_of},
-    combinator::map,
-    multi::{many1, separated_list1},
-    sequence::preceded,
+    branch::alt, bytes::complete::tag, character::complete::line_ending, combinator::map,
+    multi::separated_list1, sequence::preceded,
 };
 
+use std::collections::VecDeque;
+
+use crate::{
+    ocr,
+    parse::{finish, int, IResult},
+    vm::{self, Flow, Registers, Vm},
+    Answer, Result,
+};
+
+#[derive(Debug)]
 pub enum Instruction {
     Noop,
     Addx(i32),
 }
 
-#[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Vec<Instruction> {
-    let (rem, instr) = parse(input).unwrap();
-    assert!(rem.is_empty());
-    instr
-}
-
-#[aoc(day10, part1)]
-pub fn part1(input: &[Instruction]) -> i32 {
-    let mut reg = 1;
-    let mut cycle = 0;
-    let mut counter = 0;
-
-    let check_reg = |cycle: &i32, reg: &i32, counter: &mut i32| match cycle {
-        c if (c - 20) % 40 == 0 => {
-            dbg!(c, reg, reg * c);
-            *counter += c * reg
+impl vm::Instruction<1> for Instruction {
+    fn cycles(&self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
         }
-        _ => {}
-    };
-
-    for inst in input {
-        match inst {
-            Instruction::Noop => {
-                cycle += 1;
-                check_reg(&cycle, &reg, &mut counter);
-            }
-            Instruction::Addx(i) => {
-                cycle += 1;
-                check_reg(&cycle, &reg, &mut counter);
-
-                cycle += 1;
-                check_reg(&cycle, &reg, &mut counter);
+    }
 
-                reg += i;
-            }
+    fn execute(&self, regs: &mut Registers<1>, _: &mut VecDeque<i64>) -> Flow {
+        if let Instruction::Addx(i) = self {
+            regs[0] += *i as i64;
         }
+        Flow::Next
     }
-
-    counter
 }
 
-#[aoc(day10, part2)]
-pub fn part2(input: &[Instruction]) -> u32 {
-    let mut reg = 1;
-    let mut cycle = 0;
-
-    let render = |cycle: &i32, reg: &i32| {
-        let x = (cycle-1) % 40;
-        let visible = match x - reg {
-            -1|0|1 => true,
-            _ => false,
-        };
+/// The CPU with its `X` register starting at 1.
+fn cpu(program: &[Instruction]) -> Vm<'_, Instruction, 1> {
+    Vm::new(program).with_registers([1])
+}
 
-        match visible {
-            true => print!("#"),
-            false => print!(" ")
-        }
+pub fn input_generator(input: &str) -> Result<Vec<Instruction>> {
+    finish(input, parse(input))
+}
 
-        if x == 39 {
-            println!("")
+pub fn part1(input: &[Instruction]) -> i64 {
+    let mut strength = 0;
+    cpu(input).run_with(|cpu| {
+        let cycle = cpu.cycle() as i64;
+        if (cycle - 20) % 40 == 0 {
+            strength += cycle * cpu.regs()[0];
         }
-    };
-
-    for inst in input {
-        match inst {
-            Instruction::Noop => {
-                cycle += 1;
-                render(&cycle, &reg);
-            }
-            Instruction::Addx(i) => {
-                cycle += 1;
-                render(&cycle, &reg);
-
-                cycle += 1;
-                render(&cycle, &reg);
+    });
+    strength
+}
 
-                reg += i;
-            }
+pub fn part2(input: &[Instruction]) -> Answer {
+    let mut screen = vec![[false; 40]; 6];
+    cpu(input).run_with(|cpu| {
+        let pixel = cpu.cycle() - 1;
+        let x = (pixel % 40) as i64;
+        if let Some(row) = screen.get_mut(pixel / 40) {
+            row[pixel % 40] = matches!(x - cpu.regs()[0], -1..=1);
         }
-    }
+    });
 
-    0
+    ocr::answer(screen)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Instruction>> {
+fn parse(input: &str) -> IResult<'_, Vec<Instruction>> {
     separated_list1(
         line_ending,
         alt((
             map(tag("noop"), |_| Instruction::Noop),
-            map(preceded(tag("addx "), many1(one_of("0123456789-"))), |c| {
-                let i = String::from_iter(c.into_iter()).parse().unwrap();
-                Instruction::Addx(i)
-            }),
+            map(preceded(tag("addx "), int), Instruction::Addx),
         )),
     )(input)
 }
@@ -264,11 +230,26 @@ noop";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 13140);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 13140);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 0);
+        let screen = "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+        assert_eq!(part2(&input_generator(INPUT).unwrap()).to_string(), screen);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("noop\naddx 1-2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 7: unexpected trailing input"
+        );
     }
 }
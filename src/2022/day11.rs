@@ -1,14 +1,19 @@
 use std::{collections::VecDeque, fmt::Display, sync::Arc};
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     bytes::complete::tag,
     character::complete::{alphanumeric1, anychar, digit1, line_ending, space1},
     combinator::{map, map_res},
+    error::context,
     multi::separated_list1,
     sequence::{delimited, pair, preceded, separated_pair},
 };
 
+use crate::{
+    parse::{finish, IResult},
+    Result,
+};
+
 #[derive(Clone)]
 pub struct Monkey {
     num: u32,
@@ -24,7 +29,7 @@ impl Monkey {
         let new = (*self.new)(item);
         let bored = (new / bored_factor) % modulo;
 
-        let target = if bored % self.test_divisor == 0 {
+        let target = if bored.is_multiple_of(self.test_divisor) {
             self.target_monkeys.1 // true case
         } else {
             self.target_monkeys.0 // false case
@@ -50,12 +55,8 @@ impl Display for Monkey {
     }
 }
 
-#[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Vec<Monkey> {
-    let (rem, m) = parse(input).unwrap();
-    debug_assert!(rem.is_empty());
-
-    m
+pub fn input_generator(input: &str) -> Result<Vec<Monkey>> {
+    finish(input, parse(input))
 }
 
 pub struct State {
@@ -95,10 +96,7 @@ impl State {
 }
 
 fn do_it(input: &[Monkey], rounds: usize, bored_factor: u64) -> u32 {
-    let mut counter = vec![];
-    for _ in 0..input.len() {
-        counter.push(0);
-    }
+    let counter = vec![0; input.len()];
 
     let modulo = input.iter().fold(1, |acc, monkey| {
         if acc % monkey.test_divisor == 0 {
@@ -131,17 +129,15 @@ fn do_it(input: &[Monkey], rounds: usize, bored_factor: u64) -> u32 {
     state.counter[end] * state.counter[end - 1]
 }
 
-#[aoc(day11, part1)]
 pub fn part1(input: &[Monkey]) -> u32 {
     do_it(input, 20, 3)
 }
 
-#[aoc(day11, part2)]
 pub fn part2(input: &[Monkey]) -> u32 {
     do_it(input, 10_000, 1)
 }
 
-fn parse_monkey(input: &str) -> nom::IResult<&str, Monkey> {
+fn parse_monkey(input: &str) -> IResult<'_, Monkey> {
     let (rem, num) = delimited(
         tag("Monkey "),
         map_res(digit1, str::parse::<u32>),
@@ -200,8 +196,11 @@ fn parse_monkey(input: &str) -> nom::IResult<&str, Monkey> {
     Ok((rem, monkey))
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Monkey>> {
-    separated_list1(pair(line_ending, line_ending), parse_monkey)(input)
+fn parse(input: &str) -> IResult<'_, Vec<Monkey>> {
+    separated_list1(
+        pair(line_ending, line_ending),
+        context("monkey", parse_monkey),
+    )(input)
 }
 
 #[cfg(test)]
@@ -238,11 +237,11 @@ Monkey 3:
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 10605);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 10605);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 2713310158);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 2713310158);
     }
 }
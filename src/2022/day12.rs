@@ -1,17 +1,9 @@
-use std::collections::{hash_map::Entry, HashMap, VecDeque};
+use glam::IVec2;
 
-use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{
-    character::complete::{alpha1, line_ending},
-    combinator::map,
-    multi::separated_list1,
-};
+use crate::{grid::Grid, parse::ParseError, search::bfs, Error, Result};
 
-type Pos = (i32, i32);
-
-fn add_pos(a: &Pos, b: &Pos) -> Pos {
-    (a.0 + b.0, a.1 + b.1)
-}
+/// The heightmap with the positions of the start and the end.
+type T = (Grid<Height>, IVec2, IVec2);
 
 #[derive(Debug)]
 pub enum Height {
@@ -20,251 +12,87 @@ pub enum Height {
     End,
 }
 
-#[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> HashMap<Pos, Height> {
-    let (rem, hm) = parse(input).unwrap();
-    debug_assert!(rem.is_empty());
-    hm
-}
-
-#[aoc(day12, part1)]
-pub fn part1(input: &HashMap<Pos, Height>) -> u32 {
-    let (start, _) = input
-        .iter()
-        .find(|&(_, val)| matches!(val, Height::Start))
-        .unwrap();
-    let (end, _) = input
-        .iter()
-        .find(|&(_, val)| matches!(val, Height::End))
-        .unwrap();
-
-    let mut score = HashMap::new();
-    let mut candidates = VecDeque::new();
-
-    // add start
-    score.insert(*start, 0);
-    candidates.push_back(*start);
-
-    while let Some(candidate) = candidates.pop_front() {
-        let s = score.get(&candidate).unwrap().to_owned(); // if we crash here, the code it broken and requires fixing
-
-        if candidate == *end {
-            continue;
-        }
-
-        let next = visit(input, &candidate);
-
-        for n in next {
-            match score.entry(n) {
-                Entry::Vacant(v) => {
-                    _ = {
-                        v.insert(s + 1);
-                        candidates.push_back(n);
-                    }
-                }
-                Entry::Occupied(mut o) => {
-                    if *o.get() > s + 1 {
-                        o.insert(s + 1);
-                        candidates.push_back(n);
-                    }
-                }
-            }
+pub fn input_generator(input: &str) -> Result<T> {
+    let grid = Grid::parse(input, |c| match c {
+        'S' => Some(Height::Start),
+        'E' => Some(Height::End),
+        'a'..='z' => Some(Height::H((c as u8 - b'a') as i32)),
+        _ => None,
+    })?;
+
+    let (mut start, mut end) = (None, None);
+    for (pos, height) in grid.iter() {
+        let (marker, name) = match height {
+            Height::Start => (&mut start, "start `S`"),
+            Height::End => (&mut end, "end `E`"),
+            Height::H(_) => continue,
+        };
+        if marker.replace(pos).is_some() {
+            let (line, column) = (pos.y as usize + 1, pos.x as usize + 1);
+            return Err(ParseError::new(line, column, format!("second {name}")).into());
         }
     }
+    let start = start.ok_or_else(|| Error::Parse("no start `S`".into()))?;
+    let end = end.ok_or_else(|| Error::Parse("no end `E`".into()))?;
 
-    #[cfg(test)]
-    {
-        for y in 0..5 {
-            for x in 0..8 {
-                match score.get(&(x, y)) {
-                    Some(s) => print!("{s:>3}"),
-                    None => print!("   "),
-                }
-            }
-            println!("");
-        }
-    }
-    // #[cfg(not(test))]
-    // {
-    //     for y in 0..40 {
-    //         for x in 0..144 {
-    //             match score.get(&(x, y)) {
-    //                 Some(s) => print!("{s:>3}"),
-    //                 None => print!("   "),
-    //             }
-    //         }
-    //         println!("");
-    //     }
-    // }
+    Ok((grid, start, end))
+}
 
-    score
-        .into_iter()
-        .find(|(pos, _)| pos == end)
-        .map(|(_, s)| s)
+pub fn part1((input, start, end): &T) -> u32 {
+    bfs(*start, |&pos| visit(input, pos), |pos| pos == end)
         .unwrap()
+        .cost as u32
 }
 
-fn visit(map: &HashMap<Pos, Height>, pos: &Pos) -> Vec<Pos> {
-    let at = map.get(pos).unwrap();
+fn visit(map: &Grid<Height>, pos: IVec2) -> Vec<IVec2> {
+    let at = &map[pos];
 
     let mut candidates = vec![];
 
-    for dir in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-        let new_pos = add_pos(pos, &dir);
-
-        if let Some(now) = map.get(&new_pos) {
-            match (at, now) {
-                (Height::H(h1), Height::H(h2)) if h1 + 1 >= *h2 => candidates.push(new_pos),
+    for new_pos in map.neighbours4(pos) {
+        match (at, &map[new_pos]) {
+            (Height::H(h1), Height::H(h2)) if h1 + 1 >= *h2 => candidates.push(new_pos),
 
-                (Height::Start, Height::H(h2)) if *h2 == 0 || *h2 == 1 => candidates.push(new_pos),
-                (Height::H(h1), Height::End) if *h1 == 24 || *h1 == 25 => candidates.push(new_pos),
+            (Height::Start, Height::H(h2)) if *h2 == 0 || *h2 == 1 => candidates.push(new_pos),
+            (Height::H(h1), Height::End) if *h1 == 24 || *h1 == 25 => candidates.push(new_pos),
 
-                _ => {}
-            }
+            _ => {}
         }
     }
 
     candidates
 }
 
-#[aoc(day12, part2)]
-pub fn part2(input: &HashMap<Pos, Height>) -> u32 {
-    let (_start, _) = input
-        .iter()
-        .find(|&(_, val)| matches!(val, Height::Start))
-        .unwrap();
-    let (end, _) = input
-        .iter()
-        .find(|&(_, val)| matches!(val, Height::End))
-        .unwrap();
-
-    let mut score = HashMap::new();
-    let mut candidates = VecDeque::new();
-
-    // add start
-    score.insert(*end, 0);
-    candidates.push_back(*end);
-
-    while let Some(candidate) = candidates.pop_front() {
-        let s = score.get(&candidate).unwrap().to_owned();
-        let h = input.get(&candidate).unwrap();
-
-        if matches!(h, Height::H(0)) {
-            return s;
-        }
-
-        let next = visit_reverse(input, &candidate);
-
-        for n in next {
-            match score.entry(n) {
-                Entry::Vacant(v) => {
-                    _ = {
-                        v.insert(s + 1);
-                        candidates.push_back(n);
-                    }
-                }
-                Entry::Occupied(mut o) => {
-                    if *o.get() > s + 1 {
-                        o.insert(s + 1);
-                        candidates.push_back(n);
-                    }
-                }
-            }
-        }
-    }
-
-    #[cfg(test)]
-    {
-        for y in 0..5 {
-            for x in 0..8 {
-                match score.get(&(x, y)) {
-                    Some(s) => print!("{s:>3}"),
-                    None => print!("   "),
-                }
-            }
-            println!("");
-        }
-    }
-    #[cfg(not(test))]
-    {
-        for y in 0..40 {
-            for x in 0..144 {
-                match score.get(&(x, y)) {
-                    Some(s) => print!("{s:>3}"),
-                    None => print!("   "),
-                }
-            }
-            println!("");
-        }
-    }
-
-    // let starting_points = input
-    //     .iter()
-    //     .filter_map(|(pos, h)| match h {
-    //         Height::Start | Height::H(0) => Some(*pos),
-    //         _ => None,
-    //     })
-    //     .collect::<Vec<_>>();
-    // score
-    //     .into_iter()
-    //     .filter(|(pos, _)| starting_points.contains(pos))
-    //     .map(|(_, s)| s)
-    //     .min()
-    //     .unwrap()
-        0
+pub fn part2((input, _, end): &T) -> u32 {
+    // walk down from the end to the closest square at elevation `a`
+    bfs(
+        *end,
+        |&pos| visit_reverse(input, pos),
+        |&pos| matches!(input[pos], Height::H(0) | Height::Start),
+    )
+    .unwrap()
+    .cost as u32
 }
 
-fn visit_reverse(map: &HashMap<Pos, Height>, pos: &Pos) -> Vec<Pos> {
-    let at = map.get(pos).unwrap();
+fn visit_reverse(map: &Grid<Height>, pos: IVec2) -> Vec<IVec2> {
+    let at = &map[pos];
 
     let mut candidates = vec![];
 
-    for dir in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-        let new_pos = add_pos(pos, &dir);
+    for new_pos in map.neighbours4(pos) {
+        match (at, &map[new_pos]) {
+            (Height::H(h1), Height::H(h2)) if *h1 <= h2 + 1 => candidates.push(new_pos),
 
-        if let Some(now) = map.get(&new_pos) {
-            match (at, now) {
-                (Height::H(h1), Height::H(h2)) if *h1 <= h2 + 1 => candidates.push(new_pos),
+            (Height::H(h1), Height::Start) if *h1 == 1 => candidates.push(new_pos),
+            (Height::End, Height::H(h2)) if *h2 == 24 || *h2 == 25 => candidates.push(new_pos),
 
-                (Height::H(h1), Height::Start) if *h1 == 1 => candidates.push(new_pos),
-                (Height::End, Height::H(h2)) if *h2 == 24 || *h2 == 25 => candidates.push(new_pos),
-
-                _ => {}
-            }
+            _ => {}
         }
     }
 
     candidates
 }
 
-fn parse(input: &str) -> nom::IResult<&str, HashMap<Pos, Height>> {
-    let (rem, heights) = separated_list1(
-        line_ending,
-        map(alpha1, |c: &str| {
-            c.chars()
-                .map(|c| match c {
-                    'S' => Height::Start,
-                    'E' => Height::End,
-                    c @ 'a'..='z' => {
-                        let h = c as u8 - b'a';
-                        Height::H(h as i32)
-                    }
-                    c @ _ => unreachable!("found {c}"),
-                })
-                .collect::<Vec<_>>()
-        }),
-    )(input)?;
-
-    let mut hm = HashMap::new();
-    for (y, row) in heights.into_iter().enumerate() {
-        for (x, h) in row.into_iter().enumerate() {
-            hm.insert((x as i32, y as i32), h);
-        }
-    }
-
-    Ok((rem, hm))
-}
-
 #[cfg(test)]
 mod tests {
     use super::{input_generator, part1, part2};
@@ -277,11 +105,27 @@ abdefghi";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 31);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 31);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 29);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 29);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("Sab\naXc\nabE").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 2: unexpected `X`"
+        );
+        let err = input_generator("Sab\nabc\nabc").unwrap_err();
+        assert_eq!(err.to_string(), "invalid input: no end `E`");
+        let err = input_generator("Sab\nSbc\nabE").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 1: second start `S`"
+        );
     }
 }
@@ -1,6 +1,5 @@
 use std::cmp::Ordering;
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     bytes::complete::tag,
     character::complete::{digit1, line_ending},
@@ -9,6 +8,11 @@ use nom::{
     sequence::{delimited, pair, separated_pair},
 };
 
+use crate::{
+    parse::{finish, IResult},
+    Result,
+};
+
 #[derive(Debug, Clone, Eq)]
 pub enum Entry {
     Num(u32),
@@ -32,7 +36,7 @@ impl Entry {
                 for (a, b) in a.iter().zip(b) {
                     match a.right_order(b) {
                         Ordering::Equal => continue,
-                        o @ _ => return o,
+                        o => return o,
                     }
                 }
                 match (a.len(), b.len()) {
@@ -74,14 +78,10 @@ impl PartialEq for Entry {
     }
 }
 
-#[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> Vec<(Entry, Entry)> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty());
-    ret
+pub fn input_generator(input: &str) -> Result<Vec<(Entry, Entry)>> {
+    finish(input, parse(input))
 }
 
-#[aoc(day13, part1)]
 pub fn part1(input: &[(Entry, Entry)]) -> usize {
     input
         .iter()
@@ -92,7 +92,6 @@ pub fn part1(input: &[(Entry, Entry)]) -> usize {
         .sum()
 }
 
-#[aoc(day13, part2)]
 pub fn part2(input: &[(Entry, Entry)]) -> usize {
     let div_a = Entry::List(vec![Entry::List(vec![Entry::Num(2)])]);
     let div_b = Entry::List(vec![Entry::List(vec![Entry::Num(6)])]);
@@ -101,8 +100,7 @@ pub fn part2(input: &[(Entry, Entry)]) -> usize {
     frames.extend(
         input
             .iter()
-            .map(|(a, b)| vec![a.to_owned(), b.to_owned()])
-            .flatten(),
+            .flat_map(|(a, b)| vec![a.to_owned(), b.to_owned()]),
     );
 
     frames.sort();
@@ -117,18 +115,18 @@ pub fn part2(input: &[(Entry, Entry)]) -> usize {
         .unwrap()
 }
 
-fn parse_entry(input: &str) -> nom::IResult<&str, Entry> {
+fn parse_entry(input: &str) -> IResult<'_, Entry> {
     nom::branch::alt((
-        map(map_res(digit1, str::parse), |d| Entry::Num(d)),
+        map(map_res(digit1, str::parse), Entry::Num),
         map(tag("[]"), |_| Entry::List(vec![])),
         map(
             delimited(tag("["), separated_list1(tag(","), parse_entry), tag("]")),
-            |l| Entry::List(l),
+            Entry::List,
         ),
     ))(input)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<(Entry, Entry)>> {
+fn parse(input: &str) -> IResult<'_, Vec<(Entry, Entry)>> {
     separated_list1(
         pair(line_ending, line_ending),
         separated_pair(parse_entry, line_ending, parse_entry),
@@ -165,11 +163,11 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 13);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 13);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 140);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 140);
     }
 }
@@ -1,12 +1,12 @@
 use std::collections::HashSet;
 
-use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, line_ending},
-    combinator::map_res,
-    multi::separated_list1,
-    sequence::separated_pair,
+use glam::IVec2;
+use nom::{bytes::complete::tag, character::complete::line_ending, multi::separated_list1};
+
+use crate::{
+    parse::{coords2, finish, list, IResult},
+    visual::{self, Frame},
+    Result,
 };
 
 type Pos = (i32, i32);
@@ -18,10 +18,8 @@ fn add_pos(a: &Pos, b: &Pos) -> Pos {
 
 const GROUND_OFFSET: i32 = 2;
 
-#[aoc_generator(day14)]
-pub fn input_generator(input: &str) -> (HashSet<Pos>, i32) {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty());
+pub fn input_generator(input: &str) -> Result<(HashSet<Pos>, i32)> {
+    let ret = finish(input, parse(input))?;
 
     let mut map = HashSet::new();
 
@@ -69,10 +67,7 @@ pub fn input_generator(input: &str) -> (HashSet<Pos>, i32) {
         map.insert((x, ground));
     }
 
-    #[cfg(test)]
-    pretty_print(&map);
-
-    (map, void)
+    Ok((map, void))
 }
 
 macro_rules! drop {
@@ -125,22 +120,14 @@ fn drop_sand(map: &HashSet<Pos>, void: i32, part_2: bool) -> Option<Pos> {
     Some(sand)
 }
 
-#[allow(dead_code)]
-fn pretty_print(map: &HashSet<Pos>) {
-    for y in 0..12 {
-        for x in 490..510 {
-            if x == 500 && y == 0 {
-                print!("S");
-            } else {
-                match map.get(&(x, y)) {
-                    None => print!("."),
-                    Some(_) => print!("#"),
-                }
-            }
-        }
-        println!("")
-    }
-    println!("")
+/// The cave with the `rocks` and the sand, which is everything else in `map`.
+fn frame(rocks: &HashSet<Pos>, map: &HashSet<Pos>) -> Frame {
+    let source = std::iter::once((IVec2::new(500, 0), '+'));
+    let filled = map.iter().map(|&(x, y)| {
+        let c = if rocks.contains(&(x, y)) { '#' } else { 'o' };
+        (IVec2::new(x, y), c)
+    });
+    Frame::from_points(source.chain(filled), '.')
 }
 
 fn do_it(input: &(HashSet<Pos>, i32), part_2: bool) -> usize {
@@ -152,39 +139,24 @@ fn do_it(input: &(HashSet<Pos>, i32), part_2: bool) -> usize {
     while let Some(pos) = drop_sand(&map, void, part_2) {
         // settled
         assert!(map.insert(pos));
-
-        #[cfg(test)]
-        {
-            println!("counter: {counter}");
-            pretty_print(&map);
-        }
-
         counter += 1;
+
+        visual::show(|| frame(&input.0, &map).with_title(format!("{counter} units of sand")));
     }
 
     counter
 }
 
-#[aoc(day14, part1)]
 pub fn part1(input: &(HashSet<Pos>, i32)) -> usize {
     do_it(input, false)
 }
 
-#[aoc(day14, part2)]
 pub fn part2(input: &(HashSet<Pos>, i32)) -> usize {
     do_it(input, true)
 }
 
-fn parse_pos(input: &str) -> nom::IResult<&str, Pos> {
-    separated_pair(
-        map_res(digit1, str::parse),
-        tag(","),
-        map_res(digit1, str::parse),
-    )(input)
-}
-
-fn parse(input: &str) -> nom::IResult<&str, Vec<Stone>> {
-    separated_list1(line_ending, separated_list1(tag(" -> "), parse_pos))(input)
+fn parse(input: &str) -> IResult<'_, Vec<Stone>> {
+    separated_list1(line_ending, list(tag("->"), coords2))(input)
 }
 
 #[cfg(test)]
@@ -196,11 +168,11 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 24);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 24);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 93);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 93);
     }
 }
@@ -1,15 +1,23 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::RangeInclusive};
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use glam::IVec2;
 use nom::{
     bytes::complete::tag,
-    character::complete::{digit1, line_ending},
-    combinator::{map, map_res, opt, recognize},
+    character::complete::{char, line_ending},
+    combinator::map,
     multi::separated_list1,
-    sequence::{preceded, separated_pair, tuple},
+    sequence::{preceded, separated_pair},
 };
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+use crate::{
+    parallel::par_iter,
+    parse::{finish, int, ws, IResult},
+    visual::{self, Frame},
+    Param, Params, Result,
+};
+
 type Pos = (i64, i64);
 
 pub struct Entry {
@@ -22,67 +30,32 @@ fn calc_manhattan_distance(a: &Pos, b: &Pos) -> i64 {
     (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as i64
 }
 
-#[allow(dead_code)]
-fn pretty_print(input: &[Entry]) {
-    for y in -3..25 {
-        print!("{y:>3} ");
-        'X: for x in -5..30 {
-            for e in input {
-                let pos = (x, y);
-                if e.beacon == pos {
-                    print!("B");
-                    continue 'X;
-                } else if e.sensor == pos {
-                    print!("S");
-                    continue 'X;
-                }
-            }
-            print!(".");
+/// The sensors and beacons, with the positions a sensor covers on the given `rows`.
+fn frame(input: &[Entry], rows: RangeInclusive<i64>) -> Frame {
+    let at = |(x, y): Pos| IVec2::new(x as i32, y as i32);
+    let mut points = vec![];
+    for e in input {
+        for y in rows.clone() {
+            let reach = e.manhattan_distance - e.sensor.1.abs_diff(y) as i64;
+            points.extend((e.sensor.0 - reach..=e.sensor.0 + reach).map(|x| (at((x, y)), '#')));
         }
-        println!("");
     }
-}
-
-#[allow(dead_code)]
-fn pretty_print_row(input: &[Entry], y: i64) {
-    print!("{y:>3} ");
-    'X: for x in -5..30 {
-        let pos = (x, y);
-        for e in input {
-            if calc_manhattan_distance(&pos, &e.sensor) <= e.manhattan_distance {
-                if e.beacon == pos {
-                    print!("B");
-                    continue 'X;
-                } else if e.sensor == pos {
-                    print!("S");
-                    continue 'X;
-                } else {
-                    print!("#");
-                }
-                continue 'X;
-            }
-        }
-        print!(".");
+    for e in input {
+        points.push((at(e.sensor), 'S'));
+        points.push((at(e.beacon), 'B'));
     }
-    println!("");
+    Frame::from_points(points, '.')
 }
 
-#[aoc_generator(day15)]
-pub fn input_generator(input: &str) -> Vec<Entry> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty());
-    ret
+pub fn input_generator(input: &str) -> Result<Vec<Entry>> {
+    finish(input, parse(input))
 }
 
-#[aoc(day15, part1)]
-pub fn part1(input: &[Entry]) -> usize {
-    #[cfg(test)]
-    const Y: i64 = 10;
-    #[cfg(not(test))]
-    const Y: i64 = 2000000;
+/// `y` is the row to count the covered positions in.
+pub const PARAMS1: &[Param] = &[Param::new("y", 2_000_000)];
 
-    #[cfg(test)]
-    pretty_print(input);
+pub fn part1(input: &[Entry], params: &Params) -> usize {
+    let row = params.get("y");
 
     let (range, candidates) = {
         let mut min = 0;
@@ -94,7 +67,7 @@ pub fn part1(input: &[Entry]) -> usize {
             max = max.max(e.sensor.0 + e.manhattan_distance);
 
             // filter out sensors that are out of range
-            if e.sensor.1.abs_diff(Y) as i64 <= e.manhattan_distance {
+            if e.sensor.1.abs_diff(row) as i64 <= e.manhattan_distance {
                 candidates.push(e);
             }
         }
@@ -102,17 +75,14 @@ pub fn part1(input: &[Entry]) -> usize {
         (min..=max, candidates)
     };
 
-    #[cfg(test)]
-    {
-        pretty_print_row(input, Y - 1);
-        pretty_print_row(input, Y);
-        pretty_print_row(input, Y + 1);
+    // the real input spans millions of positions, too many to look at
+    if range.end() - range.start() <= 100 {
+        visual::show(|| frame(input, row - 1..=row + 1));
     }
 
-    range
-        .into_par_iter()
+    par_iter(range)
         .filter(|x| {
-            let pos = (*x, Y);
+            let pos = (*x, row);
             for e in &candidates {
                 if calc_manhattan_distance(&pos, &e.sensor) <= e.manhattan_distance {
                     if e.beacon != pos && e.sensor != pos {
@@ -126,132 +96,57 @@ pub fn part1(input: &[Entry]) -> usize {
         .count()
 }
 
-#[aoc(day15, part2)]
-pub fn part2(input: &[Entry]) -> i64 {
-    #[cfg(test)]
-    const Y: i64 = 20;
-    #[cfg(not(test))]
-    const Y: i64 = 4_000_000;
-
-    #[cfg(test)]
-    const X: i64 = 20;
-    #[cfg(not(test))]
-    const X: i64 = 4_000_000;
+/// `max` bounds both coordinates of the distress beacon.
+pub const PARAMS2: &[Param] = &[Param::new("max", 4_000_000)];
 
-    #[cfg(old)]
-    {
-        let sets: Vec<_> = input
-            .par_iter()
-            .map(|e| {
-                let mut candidates = vec![];
+pub fn part2(input: &[Entry], params: &Params) -> i64 {
+    let max = params.get("max");
 
-                let sx = e.sensor.0;
-                let sy = e.sensor.1;
-                let d = e.manhatten_distance + 1;
-                for y in sy - d..sy + d {
-                    // is always negative
-                    let x = (y - sy).abs() - d;
+    // based on https://www.reddit.com/r/adventofcode/comments/zmcn64/comment/j0b90nr/
+    let mut acoeffs = HashSet::new();
+    let mut bcoeffs = HashSet::new();
+    for e in input {
+        let x = e.sensor.0;
+        let y = e.sensor.1;
+        let r = e.manhattan_distance;
 
-                    let p1 = (sx + x, y);
-                    let p2 = (sx - x, y);
+        acoeffs.insert(y - x + r + 1);
+        acoeffs.insert(y - x - r - 1);
+        bcoeffs.insert(x + y + r + 1);
+        bcoeffs.insert(x + y - r - 1);
+    }
 
-                    // println!("{x},{y}");
-                    candidates.push(p1);
-                    candidates.push(p2);
-                }
-                candidates
-            })
-            .collect();
+    for a in &acoeffs {
+        'L: for b in &bcoeffs {
+            if (b - a) % 2 == 1 {
+                continue;
+            }
 
-        'L: for (x, y) in sets.iter().flatten() {
-            if *x < 0 || *y < 0 || *x > X || *y > Y {
+            let p = ((b - a) / 2, (b + a) / 2);
+            if p.0 < 0 || p.0 > max || p.1 < 0 || p.1 > max {
                 continue;
             }
 
-            let pos = (*x, *y);
             for e in input {
-                if calc_manhattan_distance(&pos, &e.sensor) <= e.manhatten_distance {
+                if calc_manhattan_distance(&p, &e.sensor) <= e.manhattan_distance {
                     continue 'L;
                 }
             }
-
-            return x * 4_000_000 + y;
-        }
-
-        let (x, y) = sets
-            .par_iter()
-            .flatten()
-            .find_any(|(x, y)| {
-                if *x < 0 || *y < 0 || *x > X || *y > Y {
-                    false
-                } else {
-                    let pos = (*x, *y);
-                    for e in input {
-                        if calc_manhattan_distance(&pos, &e.sensor) <= e.manhatten_distance {
-                            return false;
-                        }
-                    }
-
-                    true
-                }
-            })
-            .unwrap();
-        x * 4_000_000 + y
-    }
-    #[cfg(not(old))]
-    {
-        // based on https://www.reddit.com/r/adventofcode/comments/zmcn64/comment/j0b90nr/
-        let mut acoeffs = HashSet::new();
-        let mut bcoeffs = HashSet::new();
-        for e in input {
-            let x = e.sensor.0;
-            let y = e.sensor.1;
-            let r = e.manhattan_distance;
-
-            acoeffs.insert(y - x + r + 1);
-            acoeffs.insert(y - x - r - 1);
-            bcoeffs.insert(x + y + r + 1);
-            bcoeffs.insert(x + y - r - 1);
-        }
-
-        for a in &acoeffs {
-            'L: for b in &bcoeffs {
-                if (b - a) % 2 == 1 {
-                    continue;
-                }
-
-                let p = ((b - a) / 2, (b + a) / 2);
-                if p.0 < 0 || p.0 > X || p.1 < 0 || p.1 > Y {
-                    continue;
-                }
-
-                for e in input {
-                    if calc_manhattan_distance(&p, &e.sensor) <= e.manhattan_distance {
-                        continue 'L;
-                    }
-                }
-                return p.0 * 4_000_000 + p.1;
-            }
+            return p.0 * 4_000_000 + p.1;
         }
     }
     0
 }
 
-fn parse_coords(input: &str) -> nom::IResult<&str, Pos> {
-    let (rem, x) = preceded(
-        tag("x="),
-        map_res(recognize(tuple((opt(tag("-")), digit1))), str::parse),
-    )(input)?;
-    let (rem, _) = tag(", ")(rem)?;
-    let (rem, y) = preceded(
-        tag("y="),
-        map_res(recognize(tuple((opt(tag("-")), digit1))), str::parse),
-    )(rem)?;
-
-    Ok((rem, (x, y)))
+fn parse_coords(input: &str) -> IResult<'_, Pos> {
+    separated_pair(
+        preceded(tag("x="), int),
+        ws(char(',')),
+        preceded(tag("y="), int),
+    )(input)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Entry>> {
+fn parse(input: &str) -> IResult<'_, Vec<Entry>> {
     separated_list1(
         line_ending,
         map(
@@ -274,7 +169,9 @@ fn parse(input: &str) -> nom::IResult<&str, Vec<Entry>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use crate::Params;
+
+    use super::{input_generator, part1, part2, PARAMS1, PARAMS2};
 
     const INPUT: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
@@ -293,11 +190,13 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 26);
+        let params = Params::new(PARAMS1).with("y", 10);
+        assert_eq!(part1(&input_generator(INPUT).unwrap(), &params), 26);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 56000011);
+        let params = Params::new(PARAMS2).with("max", 20);
+        assert_eq!(part2(&input_generator(INPUT).unwrap(), &params), 56000011);
     }
 }
@@ -1,63 +1,123 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
-use ndarray::{Array3, ArrayBase, Dim, OwnedRepr};
+use fxhash::FxHashMap;
+use ndarray::Array3;
 use nom::{
     bytes::complete::tag,
-    character::complete::{alpha1, digit1, line_ending},
-    combinator::{map, map_res, opt},
-    multi::{separated_list0, separated_list1},
+    character::complete::{alpha1, char},
+    combinator::{map, opt},
+    error::context,
     sequence::{preceded, tuple},
 };
 
+use crate::{
+    parse::{int, lines, list, IResult, ParseError},
+    Error, Result,
+};
+
 #[derive(Debug, Clone)]
 pub struct Pipe {
     name: String,
-    flow_rate: i32,
-    connections: Vec<String>,
+    flow_rate: u16,
+    /// The valves the tunnels lead to, with the column each one is named at.
+    connections: Vec<(String, usize)>,
 }
 
-#[aoc_generator(day16)]
-pub fn input_generator(input: &str) -> Vec<Pipe> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty(), "remaining {rem}");
-    ret
+/// The valves by index, those with a positive flow rate first so they fit in a bit set.
+#[derive(Debug, Clone)]
+pub struct Valves {
+    start: usize,
+    flow: Vec<u16>,
+    tunnels: Vec<Vec<usize>>,
+    /// Number of valves with a positive flow rate.
+    useful: usize,
 }
 
-fn do_it(
-    valves: &Vec<(&str, u16, Vec<&str>)>,
-) -> (ArrayBase<OwnedRepr<u16>, Dim<[usize; 3]>>, usize, usize) {
-    let lab2idx = valves
+impl Valves {
+    /// Bit set of all the valves worth opening.
+    fn all(&self) -> usize {
+        (1 << self.useful) - 1
+    }
+}
+
+/// How the most pressure is found, each one is registered as a variant of the parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+    /// Dynamic programming over the minutes left, the current valve and the valves still closed.
+    Table,
+    /// Expands the reachable states minute by minute, keeping the best pressure for each.
+    Frontier,
+}
+
+impl Strategy {
+    /// The most pressure released in `minutes` if only the valves in a bit set may be opened, for
+    /// every bit set.
+    fn best_pressure(self, valves: &Valves, minutes: usize) -> Vec<u16> {
+        match self {
+            Strategy::Table => table(valves, minutes),
+            Strategy::Frontier => frontier(valves, minutes),
+        }
+    }
+}
+
+pub fn input_generator(input: &str) -> Result<Valves> {
+    // one per line, so that errors are reported where they happen
+    let mut pipes: Vec<_> = lines(input, context("valve", parse_line))?
+        .into_iter()
+        .enumerate()
+        .collect();
+    pipes.sort_by_key(|(_, p)| std::cmp::Reverse(p.flow_rate));
+
+    let lookup: HashMap<_, _> = pipes
         .iter()
         .enumerate()
-        .map(|(i, v)| (v.0, i))
-        .collect::<HashMap<_, _>>();
-    let m = valves.iter().filter(|v| v.1 > 0).count();
-    let n = valves.len();
-    let mut adj = vec![vec![0usize; 0]; n];
-    let mut flow = vec![0u16; n];
-    for (name, flow2, connections) in valves.iter() {
-        let i = lab2idx[name];
-        flow[i] = *flow2;
-        for w in connections.iter() {
-            adj[i].push(lab2idx[w]);
-        }
+        .map(|(i, (_, p))| (p.name.as_str(), i))
+        .collect();
+    let index = |name: &str| lookup.get(name).copied();
+
+    let useful = pipes.iter().filter(|(_, p)| p.flow_rate > 0).count();
+    if useful > 16 {
+        return Err(Error::Parse(format!(
+            "{useful} valves with a flow rate are too many"
+        )));
     }
-    let aa = lab2idx["AA"];
 
-    let mm = 1 << m; // m = number of valves with positive flow
+    Ok(Valves {
+        start: index("AA").ok_or_else(|| Error::Parse("no valve `AA`".into()))?,
+        flow: pipes.iter().map(|(_, p)| p.flow_rate).collect(),
+        tunnels: pipes
+            .iter()
+            .map(|(line, p)| {
+                p.connections
+                    .iter()
+                    .map(|(name, column)| {
+                        index(name).ok_or_else(|| {
+                            let message = format!("unknown valve `{name}`");
+                            ParseError::new(line + 1, *column, message).into()
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_>>()?,
+        useful,
+    })
+}
+
+fn table(valves: &Valves, minutes: usize) -> Vec<u16> {
+    let n = valves.flow.len();
+    let mm = valves.all() + 1;
 
     // dynamic programming [time left, current node, bitset of available valves]
-    let mut opt = Array3::<u16>::zeros([30, n, mm]);
-    for t in 1..30 {
+    let mut opt = Array3::<u16>::zeros([minutes, n, mm]);
+    for t in 1..minutes {
         for i in 0..n {
             let ii = 1 << i;
             for x in 0..mm {
                 let mut o = opt[(t, i, x)];
-                if ii & x != 0 && t >= 2 {
-                    o = o.max(opt[(t - 1, i, x - ii)] + flow[i] * t as u16);
+                if ii & x != 0 {
+                    o = o.max(opt[(t - 1, i, x - ii)] + valves.flow[i] * t as u16);
                 }
-                for &j in adj[i].iter() {
+                for &j in valves.tunnels[i].iter() {
                     o = o.max(opt[(t - 1, j, x)]);
                 }
                 opt[(t, i, x)] = o;
@@ -65,56 +125,92 @@ fn do_it(
         }
     }
 
-    (opt, aa, mm)
+    (0..mm)
+        .map(|x| opt[(minutes - 1, valves.start, x)])
+        .collect()
 }
 
-#[aoc(day16, part1)]
-pub fn part1(input: &[Pipe]) -> u16 {
-    let mut valves = Vec::<(&str, u16, Vec<&str>)>::new();
-    for i in input {
-        valves.push((
-            i.name.as_str(),
-            i.flow_rate as u16,
-            i.connections.iter().map(|c| c.as_str()).collect(),
-        ));
-    }
-    valves.sort_by(|a, b| b.1.cmp(&a.1));
+fn frontier(valves: &Valves, minutes: usize) -> Vec<u16> {
+    // (current valve, opened valves) -> released pressure
+    let mut states: FxHashMap<(usize, usize), u16> = FxHashMap::default();
+    states.insert((valves.start, 0), 0);
 
-    let (opt, aa, mm) = do_it(&valves);
+    for remaining in (0..minutes as u16).rev() {
+        let mut next = FxHashMap::default();
+        let mut visit = |state, released: u16| {
+            let best = next.entry(state).or_insert(released);
+            *best = released.max(*best);
+        };
 
-    opt[(29, aa, mm - 1)]
-}
+        for (&(current, opened), &released) in &states {
+            // open the valve
+            let bit = 1 << current;
+            if current < valves.useful && opened & bit == 0 {
+                let released = released + valves.flow[current] * remaining;
+                visit((current, opened | bit), released);
+            }
 
-#[aoc(day16, part2)]
-pub fn part2(input: &[Pipe]) -> u16 {
-    let mut valves = Vec::<(&str, u16, Vec<&str>)>::new();
-    for i in input {
-        valves.push((
-            i.name.as_str(),
-            i.flow_rate as u16,
-            i.connections.iter().map(|c| c.as_str()).collect(),
-        ));
-    }
-    valves.sort_by(|a, b| b.1.cmp(&a.1));
+            // move on
+            for &tunnel in &valves.tunnels[current] {
+                visit((tunnel, opened), released);
+            }
+        }
 
-    let (opt, aa, mm) = do_it(&valves);
+        states = next;
+    }
 
-    let mut best = 0;
-    for x in 0..mm {
-        for y in 0..x {
-            if (x & y) == 0 {
-                best = best.max(opt[(25, aa, x)] + opt[(25, aa, y)]);
+    let mut best = vec![0; valves.all() + 1];
+    for ((_, opened), released) in states {
+        best[opened] = best[opened].max(released);
+    }
+    // being allowed to open more valves never releases less
+    for bit in (0..valves.useful).map(|i| 1 << i) {
+        for x in 0..best.len() {
+            if x & bit != 0 {
+                best[x] = best[x].max(best[x ^ bit]);
             }
         }
     }
     best
 }
 
-fn parse_line(input: &str) -> nom::IResult<&str, Pipe> {
+fn alone(input: &Valves, strategy: Strategy) -> u16 {
+    strategy.best_pressure(input, 30)[input.all()]
+}
+
+fn with_elephant(input: &Valves, strategy: Strategy) -> u16 {
+    let best = strategy.best_pressure(input, 26);
+
+    // you and the elephant open disjoint sets of valves
+    (0..best.len())
+        .map(|x| best[x] + best[input.all() ^ x])
+        .max()
+        .unwrap()
+}
+
+pub fn part1(input: &Valves) -> u16 {
+    alone(input, Strategy::Table)
+}
+
+/// [`part1`] expanding the reachable states minute by minute.
+pub fn part1_frontier(input: &Valves) -> u16 {
+    alone(input, Strategy::Frontier)
+}
+
+pub fn part2(input: &Valves) -> u16 {
+    with_elephant(input, Strategy::Table)
+}
+
+/// [`part2`] expanding the reachable states minute by minute.
+pub fn part2_frontier(input: &Valves) -> u16 {
+    with_elephant(input, Strategy::Frontier)
+}
+
+fn parse_line(input: &str) -> IResult<'_, Pipe> {
     map(
         tuple((
             preceded(tag("Valve "), alpha1),
-            preceded(tag(" has flow rate="), map_res(digit1, str::parse)),
+            preceded(tag(" has flow rate="), int),
             preceded(
                 tuple((
                     tag("; tunnel"),
@@ -125,25 +221,23 @@ fn parse_line(input: &str) -> nom::IResult<&str, Pipe> {
                     opt(tag("s")),
                     tag(" "),
                 )),
-                separated_list0(tag(", "), alpha1),
+                list(char(','), |rest| {
+                    let column = input.len() - rest.len() + 1;
+                    map(alpha1, move |name: &str| (name.to_owned(), column))(rest)
+                }),
             ),
         )),
         |(name, flow_rate, tunnels)| Pipe {
             name: name.to_owned(),
             flow_rate,
-            connections: tunnels.iter().map(|t| t.to_string()).collect(),
+            connections: tunnels,
         },
     )(input)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Pipe>> {
-    let (rem, pipes) = separated_list1(line_ending, parse_line)(input)?;
-    Ok((rem, pipes.into_iter().collect()))
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use super::{input_generator, part1, part1_frontier, part2, part2_frontier, Strategy};
 
     const INPUT: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
 Valve BB has flow rate=13; tunnels lead to valves CC, AA
@@ -158,11 +252,40 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 1651);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 1651);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 1707);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 1707);
+    }
+
+    #[test]
+    fn strategies() {
+        let valves = input_generator(INPUT).unwrap();
+        for minutes in [10, 26, 30] {
+            assert_eq!(
+                Strategy::Table.best_pressure(&valves, minutes),
+                Strategy::Frontier.best_pressure(&valves, minutes),
+                "{minutes} minutes"
+            );
+        }
+
+        assert_eq!(part1_frontier(&valves), 1651);
+        assert_eq!(part2_frontier(&valves), 1707);
+    }
+
+    #[test]
+    fn invalid() {
+        let input = "Valve AA has flow rate=0; tunnels lead to valves BB, CC\n\
+                     Valve BB has flow rate=1; tunnel leads to valve AA";
+        let err = input_generator(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 54: unknown valve `CC`"
+        );
+        let input = "Valve BB has flow rate=1; tunnel leads to valve BB";
+        let err = input_generator(input).unwrap_err();
+        assert_eq!(err.to_string(), "invalid input: no valve `AA`");
     }
 }
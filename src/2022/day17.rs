@@ -1,47 +1,35 @@
-use std::{
-    collections::BTreeMap,
-    ops::{Add, AddAssign},
-};
+use nom::{character::complete::one_of, combinator::map, error::context, multi::many1};
 
-use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
-use nom::{character::complete::anychar, combinator::map, multi::many1};
+use crate::{
+    cycle::History,
+    parse::{finish, IResult},
+    Result,
+};
 
 // Map width
-const WIDTH: i64 = 7;
-
-// new rock spawn offset
-const SPWAN_LEFT: i64 = 2;
-const SPAWN_BOTTOM: i64 = 3;
-
-// clean map cutoff
-const CUTOFF: i64 = 100;
-
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Pos(i64, i64);
-
-impl Pos {}
-
-impl From<(i64, i64)> for Pos {
-    fn from(value: (i64, i64)) -> Self {
-        Pos(value.0, value.1)
-    }
-}
-
-impl Add for Pos {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        (self.0 + rhs.0, self.1 + rhs.1).into()
-    }
-}
-
-impl AddAssign for Pos {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
-    }
-}
+const WIDTH: usize = 7;
+
+// new rock spawn offset above the highest rock
+const SPAWN_BOTTOM: usize = 3;
+
+/// Rows of the chamber that identify its surface, together with the next rock and jet.
+const FINGERPRINT_ROWS: usize = 32;
+
+/// The rocks in their spawn position, one bit mask per row from the bottom.
+///
+/// Bit 6 is the left wall side, bit 0 the right one, so rocks spawn two units from the left.
+const SHAPES: [&[u8]; 5] = [
+    // minus
+    &[0b0011110],
+    // plus
+    &[0b0001000, 0b0011100, 0b0001000],
+    // hook
+    &[0b0011100, 0b0000100, 0b0000100],
+    // pole
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    // dot
+    &[0b0011000, 0b0011000],
+];
 
 #[derive(Debug)]
 pub enum Direction {
@@ -49,227 +37,144 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Debug, Clone)]
-pub enum Shape {
-    Minus,
-    Plus,
-    Hook,
-    Pole,
-    Dot,
-}
-
-impl Shape {
-    pub fn spawn(&self) -> Vec<Pos> {
-        match self {
-            Self::Dot => vec![(0, 0), (0, 1), (1, 0), (1, 1)]
-                .into_iter()
-                .map_into()
-                .collect(),
-            Self::Hook => vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)]
-                .into_iter()
-                .map_into()
-                .collect(),
-            Self::Minus => vec![(0, 0), (1, 0), (2, 0), (3, 0)]
-                .into_iter()
-                .map_into()
-                .collect(),
-            Self::Plus => vec![(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)]
-                .into_iter()
-                .map_into()
-                .collect(),
-            Self::Pole => vec![(0, 0), (0, 1), (0, 2), (0, 3)]
-                .into_iter()
-                .map_into()
-                .collect(),
-        }
-    }
-
-    pub fn next(&self) -> Self {
-        match self {
-            Self::Dot => Self::Minus,
-            Self::Hook => Self::Pole,
-            Self::Minus => Self::Plus,
-            Self::Plus => Self::Hook,
-            Self::Pole => Self::Dot,
-        }
-    }
-}
-
-pub struct State<'a> {
-    next_spawn: Shape,
-    next_is_down: bool,
-
-    map: BTreeMap<Pos, bool>,
-    active: Vec<Pos>,
-
-    max_hight: i64,
-
+/// The 7 wide chamber, with one bit mask per row from the floor up.
+struct Chamber<'a> {
+    rows: Vec<u8>,
     directions: &'a [Direction],
     next_direction: usize,
-
-    rocks_dropped: i64,
+    next_shape: usize,
 }
 
-fn draw(state: &State) {
-    for y in (-5..6).rev() {
-        print!("|");
-        for x in 0..WIDTH {
-            let pos = (x, y + state.max_hight).into();
-            let active = state.active.contains(&pos);
-            match (state.map.get(&pos), active) {
-                (None, false) => print!("."),
-                (None, true) => print!("@"),
-                (Some(false), false) => print!("."),
-                (Some(false), true) => print!("@"),
-                (Some(true), false) => print!("#"),
-                (Some(true), true) => unreachable!(),
-            }
+impl<'a> Chamber<'a> {
+    fn new(directions: &'a [Direction]) -> Self {
+        Chamber {
+            rows: vec![],
+            directions,
+            next_direction: 0,
+            next_shape: 0,
         }
-        println!("|");
     }
-    println!("");
-}
-
-#[aoc_generator(day17)]
-pub fn input_generator(input: &str) -> Vec<Direction> {
-    let (rem, ret) = parse(input.lines().next().unwrap()).expect("failed to parse input");
-    assert!(rem.is_empty(), "remaining {rem}");
-    ret
-}
-
-fn valid_move(state: &State, shape: &Vec<Pos>) -> bool {
-    // valid move?
-    // let hit_wall_left = shape.iter().any(|p| p.0 < 0);
-    // let hit_wall_right = shape.iter().any(|p| p.0 >= WIDTH);
-    let hit_smt = shape.iter().any(|p| p.0 < 0 || p.0 >= WIDTH || p.1 <= 0);
-    let hit_existing = shape.iter().any(|p| match state.map.get(p) {
-        Some(true) => true,
-        _ => false,
-    });
-
-    !hit_existing && !hit_smt
-}
-
-fn round(state: &mut State) {
-    if state.active.is_empty() {
-        // Spawn new
-        let new = state.next_spawn.to_owned();
-        state.next_spawn = state.next_spawn.next();
-        let mut shape = new.spawn();
 
-        // move to start position
-        let offset: Pos = (SPWAN_LEFT, state.max_hight + 1 + SPAWN_BOTTOM).into();
-        shape.iter_mut().for_each(|p| *p += offset.to_owned());
-
-        // println!("spawning new {new:?} @ {offset:?}");
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
 
-        state.active = shape;
-        state.rocks_dropped += 1;
-        draw(&state);
-    } else {
-        if state.next_is_down {
-            let vector: Pos = (0, -1).into();
-            let new_active: Vec<_> = state
-                .active
-                .iter()
-                .map(|p| p.to_owned() + vector.to_owned())
-                .collect();
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .zip(self.rows.iter().skip(y))
+            .any(|(r, row)| r & row != 0)
+    }
 
-            if valid_move(state, &new_active) {
-                // println!("Moving {vector:?}");
-                state.active = new_active;
-            } else {
-                // println!("Cannot move {vector:?}");
+    /// Drops the next rock until it comes to rest.
+    fn drop_rock(&mut self) {
+        let mut rock = SHAPES[self.next_shape].to_vec();
+        self.next_shape = (self.next_shape + 1) % SHAPES.len();
+        let mut y = self.height() + SPAWN_BOTTOM;
 
-                let mut new_max_hight = state.max_hight;
-                for p in state.active.drain(..) {
-                    new_max_hight = new_max_hight.max(p.1);
-                    state.map.insert(p, true);
+        loop {
+            let pushed: Option<Vec<u8>> = match self.directions[self.next_direction] {
+                Direction::Left if rock.iter().all(|r| r & 1 << (WIDTH - 1) == 0) => {
+                    Some(rock.iter().map(|r| r << 1).collect())
                 }
-                state.max_hight = new_max_hight;
-                // println!("hit ground, new max height: {}", state.max_hight);
-            }
-        } else {
-            let dir = &state.directions[state.next_direction];
-            state.next_direction = (state.next_direction + 1) % state.directions.len();
-
-            let vector: Pos = match dir {
-                Direction::Left => (-1, 0).into(),
-                Direction::Right => (1, 0).into(),
+                Direction::Right if rock.iter().all(|r| r & 1 == 0) => {
+                    Some(rock.iter().map(|r| r >> 1).collect())
+                }
+                _ => None,
             };
-            let new_active: Vec<_> = state
-                .active
-                .iter()
-                .map(|p| p.to_owned() + vector.to_owned())
-                .collect();
+            self.next_direction = (self.next_direction + 1) % self.directions.len();
+            if let Some(pushed) = pushed.filter(|p| !self.collides(p, y)) {
+                rock = pushed;
+            }
 
-            if valid_move(state, &new_active) {
-                // println!("Moving {vector:?}");
-                state.active = new_active;
-            } else {
-                // println!("Cannot move {dir:?}");
+            if y == 0 || self.collides(&rock, y - 1) {
+                break;
             }
+            y -= 1;
         }
 
-        // toggle new move action
-        state.next_is_down ^= true;
+        if self.rows.len() < y + rock.len() {
+            self.rows.resize(y + rock.len(), 0);
+        }
+        for (row, r) in self.rows[y..].iter_mut().zip(rock) {
+            *row |= r;
+        }
     }
 
-    state.map.retain(|p, _| p.1 + CUTOFF >= state.max_hight);
+    /// Identifies the state for the rocks to come: the top rows, the next rock and the next jet.
+    ///
+    /// Rocks can only fall below these rows through a narrow gap, which is assumed not to happen.
+    fn fingerprint(&self) -> (usize, usize, [u8; FINGERPRINT_ROWS]) {
+        // below the floor counts as full
+        let mut top = [(1 << WIDTH) - 1; FINGERPRINT_ROWS];
+        for (t, row) in top.iter_mut().zip(self.rows.iter().rev()) {
+            *t = *row;
+        }
+        (self.next_shape, self.next_direction, top)
+    }
 }
 
-#[aoc(day17, part1)]
-pub fn part1(input: &[Direction]) -> i64 {
-    let mut state = State {
-        active: vec![],
-        directions: input,
-        map: BTreeMap::new(),
-        max_hight: 0,
-        next_spawn: Shape::Minus,
-        next_is_down: false,
-        next_direction: 0,
-        rocks_dropped: 0,
-    };
+pub fn input_generator(input: &str) -> Result<Vec<Direction>> {
+    finish(input, parse(input))
+}
 
-    loop {
-        round(&mut state);
-        // draw(&state);
+/// Height of the tower after `rocks` rocks, skipping ahead once the chamber repeats itself.
+fn tower_height(directions: &[Direction], rocks: usize) -> usize {
+    let mut chamber = Chamber::new(directions);
+    let mut history = History::new();
+    history.push(chamber.fingerprint(), 0);
 
-        if state.rocks_dropped >= 2023 {
-            break;
+    for _ in 0..rocks {
+        chamber.drop_rock();
+        if let Some(cycle) = history.push(chamber.fingerprint(), chamber.height()) {
+            return history.extrapolate(cycle, rocks);
         }
     }
 
-    state.max_hight
+    chamber.height()
 }
 
-#[aoc(day17, part2)]
-pub fn part2(_input: &[Direction]) -> i64 {
-    todo!()
+pub fn part1(input: &[Direction]) -> usize {
+    tower_height(input, 2022)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Direction>> {
-    many1(map(anychar, |c| match c {
-        '<' => Direction::Left,
-        '>' => Direction::Right,
-        _ => unreachable!(),
-    }))(input)
+pub fn part2(input: &[Direction]) -> usize {
+    tower_height(input, 1_000_000_000_000)
+}
+
+fn parse(input: &str) -> IResult<'_, Vec<Direction>> {
+    many1(context(
+        "jet",
+        map(one_of("<>"), |c| match c {
+            '<' => Direction::Left,
+            _ => Direction::Right,
+        }),
+    ))(input)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use super::{input_generator, part1, part2, tower_height, Chamber};
 
     const INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 3068);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 3068);
     }
 
     #[test]
-    #[ignore = "todo"]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 1514285714288);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 1514285714288);
+    }
+
+    #[test]
+    fn skip_ahead() {
+        // extrapolating gives the same height as simulating every rock
+        let input = input_generator(INPUT).unwrap();
+        let mut chamber = Chamber::new(&input);
+        for _ in 0..5000 {
+            chamber.drop_rock();
+        }
+        assert_eq!(tower_height(&input, 5000), chamber.height());
     }
 }
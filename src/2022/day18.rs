@@ -1,22 +1,17 @@
 use std::collections::HashSet;
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use glam::IVec3;
-use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, line_ending},
-    combinator::{map, map_res},
-    multi::separated_list1,
+use nom::{character::complete::line_ending, multi::separated_list1};
+
+use crate::{
+    parse::{finish, ivec3, IResult},
+    Result,
 };
 
-#[aoc_generator(day18)]
-pub fn input_generator(input: &str) -> Vec<IVec3> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty(), "remaining {rem}");
-    ret
+pub fn input_generator(input: &str) -> Result<Vec<IVec3>> {
+    finish(input, parse(input))
 }
 
-#[aoc(day18, part1)]
 pub fn part1(input: &[IVec3]) -> usize {
     let points: HashSet<IVec3> = HashSet::from_iter(input.iter().cloned());
 
@@ -32,7 +27,7 @@ pub fn part1(input: &[IVec3]) -> usize {
             let z_high = IVec3::new(x, y, z + 1);
             [x_low, x_high, y_low, y_high, z_low, z_high]
                 .iter()
-                .filter(|ivec| points.get(ivec).is_none())
+                .filter(|ivec| !points.contains(*ivec))
                 .count()
         })
         .sum::<usize>();
@@ -50,9 +45,9 @@ fn process_block(&IVec3 { x, y, z }: &IVec3, points: &HashSet<IVec3>) -> usize {
     let z_high = IVec3::new(x, y, z + 1);
     [x_low, x_high, y_low, y_high, z_low, z_high]
         .iter()
-        .filter(|ivec| points.get(ivec).is_none())
+        .filter(|ivec| !points.contains(*ivec))
         .map(|ivec| {
-            if is_interior_block(&ivec, &points) {
+            if is_interior_block(ivec, points) {
                 // (interior wall, exterior wall)
                 (1, 0)
             } else {
@@ -100,7 +95,6 @@ fn is_interior_block(&IVec3 { x, y, z }: &IVec3, points: &HashSet<IVec3>) -> boo
     .all(|v| *v)
 }
 
-#[aoc(day18, part2)]
 pub fn part2(input: &[IVec3]) -> usize {
     let points: HashSet<IVec3> = HashSet::from_iter(input.iter().cloned());
 
@@ -116,9 +110,9 @@ pub fn part2(input: &[IVec3]) -> usize {
             let z_high = IVec3::new(x, y, z + 1);
             [x_low, x_high, y_low, y_high, z_low, z_high]
                 .iter()
-                .filter(|ivec| points.get(ivec).is_none())
+                .filter(|ivec| !points.contains(*ivec))
                 .map(|ivec| {
-                    if is_interior_block(&ivec, &points) {
+                    if is_interior_block(ivec, &points) {
                         let IVec3 { x, y, z } = *ivec;
                         let x_low = IVec3::new(x - 1, y, z);
                         let x_high = IVec3::new(x + 1, y, z);
@@ -130,7 +124,7 @@ pub fn part2(input: &[IVec3]) -> usize {
                         let is_really_exterior_block =
                             [x_low, x_high, y_low, y_high, z_low, z_high]
                                 .iter()
-                                .filter(|ivec| points.get(ivec).is_none())
+                                .filter(|ivec| !points.contains(*ivec))
                                 .any(|block| process_block(block, &points) >= 1);
                         if is_really_exterior_block {
                             (0, 1)
@@ -149,21 +143,8 @@ pub fn part2(input: &[IVec3]) -> usize {
     surface_area
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<IVec3>> {
-    separated_list1(
-        line_ending,
-        map(
-            separated_list1(tag(","), map_res(digit1, str::parse)),
-            |p| {
-                let mut it = p.into_iter();
-                IVec3 {
-                    x: it.next().unwrap(),
-                    y: it.next().unwrap(),
-                    z: it.next().unwrap(),
-                }
-            },
-        ),
-    )(input)
+fn parse(input: &str) -> IResult<'_, Vec<IVec3>> {
+    separated_list1(line_ending, ivec3)(input)
 }
 
 #[cfg(test)]
@@ -186,11 +167,11 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 64);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 64);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 58);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 58);
     }
 }
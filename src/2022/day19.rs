@@ -1,10 +1,17 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     bytes::complete::tag,
-    character::complete::{self, line_ending},
-    multi::separated_list1,
+    character::complete,
+    error::context,
     sequence::{delimited, separated_pair},
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    parallel::par_iter,
+    parse::{lines, IResult},
+    Result,
+};
 
 type OreAmount = u16;
 type Recipe = [OreAmount; 4];
@@ -43,12 +50,9 @@ fn recurse_simulation(
                     Some(0)
                 } else if state.robots[ore_type] == 0 {
                     // No robot yet, we can't build it (it takes more than max_time to build it).
-                    Some(max_time as u16 + 1)
+                    Some(max_time + 1)
                 } else {
-                    Some(
-                        (recipe[ore_type] - state.ores[ore_type] + state.robots[ore_type] - 1)
-                            / state.robots[ore_type],
-                    )
+                    Some((recipe[ore_type] - state.ores[ore_type]).div_ceil(state.robots[ore_type]))
                 }
             })
             .max()
@@ -89,13 +93,13 @@ fn recurse_simulation(
         // We couldn't make new robots, so this is the best this branch can do.
         *max_geodes = std::cmp::max(
             *max_geodes,
-            state.ores[3] + state.robots[3] * (max_time - state.time) as u16,
+            state.ores[3] + state.robots[3] * (max_time - state.time),
         );
     }
 }
 
 fn simulate_blueprint(blueprint: &Blueprint, max_time: u16) -> OreAmount {
-    let mut max_robots = [u16::max_value(); 4];
+    let mut max_robots = [u16::MAX; 4];
     for i in 0..3 {
         max_robots[i] = blueprint.recipes.iter().map(|r| r[i]).max().unwrap();
     }
@@ -114,32 +118,26 @@ fn simulate_blueprint(blueprint: &Blueprint, max_time: u16) -> OreAmount {
     max_geodes
 }
 
-#[aoc_generator(day19)]
-pub fn input_generator(input: &str) -> Vec<Blueprint> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty(), "remaining {rem}");
-    ret
+pub fn input_generator(input: &str) -> Result<Vec<Blueprint>> {
+    // one per line, so that errors are reported where they happen
+    lines(input, context("blueprint", parse_blueprint))
 }
 
-#[aoc(day19, part1)]
 pub fn part1(input: &[Blueprint]) -> usize {
-    input
-        .iter()
+    par_iter(input)
         .enumerate()
         .map(|(i, b)| simulate_blueprint(b, 24) as usize * (i + 1))
         .sum::<usize>()
 }
 
-#[aoc(day19, part2)]
 pub fn part2(input: &[Blueprint]) -> usize {
-    input
-        .iter()
+    par_iter(input)
         .take(3)
         .map(|b| simulate_blueprint(b, 32) as usize)
         .product::<usize>()
 }
 
-fn parse_blueprint(input: &str) -> nom::IResult<&str, Blueprint> {
+fn parse_blueprint(input: &str) -> IResult<'_, Blueprint> {
     let (rem, _id) = delimited(tag("Blueprint "), complete::u64, tag(":"))(input)?;
     let (rem, ore) = delimited(tag(" Each ore robot costs "), complete::u16, tag(" ore."))(rem)?;
     let (rem, clay) = delimited(tag(" Each clay robot costs "), complete::u16, tag(" ore."))(rem)?;
@@ -167,10 +165,6 @@ fn parse_blueprint(input: &str) -> nom::IResult<&str, Blueprint> {
     ))
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Blueprint>> {
-    separated_list1(line_ending, parse_blueprint)(input)
-}
-
 #[cfg(test)]
 mod tests {
     use super::{input_generator, part1, part2};
@@ -180,11 +174,11 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 33);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 33);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 56 * 62);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 56 * 62);
     }
 }
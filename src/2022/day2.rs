@@ -1,13 +1,5 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::aoc;
-// use aoc_runner_derive::aoc_generator;
-
-// #[aoc_generator(day2)]
-// pub fn input_generator(input: &str) -> Vec<u32> {
-// }
-
-#[aoc(day2, part1)]
 pub fn part1(input: &str) -> u32 {
     // A for Rock, B for Paper, and C for Scissors
     // X for Rock, Y for Paper, and Z for Scissors
@@ -41,7 +33,6 @@ pub fn part1(input: &str) -> u32 {
         .sum()
 }
 
-#[aoc(day2, part2)]
 pub fn part2(input: &str) -> u32 {
     // A for Rock, B for Paper, and C for Scissors
     // X to lose, Y to draw, and Z to win
@@ -59,11 +50,9 @@ pub fn part2(input: &str) -> u32 {
         ("A X", x + s),
         ("A Y", y + r),
         ("A Z", z + p),
-
         ("B X", x + r),
         ("B Y", y + p),
         ("B Z", z + s),
-
         ("C X", x + p),
         ("C Y", y + s),
         ("C Z", z + r),
@@ -87,11 +76,11 @@ C Z";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&INPUT), 15);
+        assert_eq!(part1(INPUT), 15);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&INPUT), 12);
+        assert_eq!(part2(INPUT), 12);
     }
 }
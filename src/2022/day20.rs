@@ -1,4 +1,3 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     bytes::complete::tag,
     character::complete::{digit1, line_ending},
@@ -7,11 +6,13 @@ use nom::{
     sequence::tuple,
 };
 
-#[aoc_generator(day20)]
-pub fn input_generator(input: &str) -> Vec<i32> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty(), "remaining {rem}");
-    ret
+use crate::{
+    parse::{finish, IResult},
+    Result,
+};
+
+pub fn input_generator(input: &str) -> Result<Vec<i32>> {
+    finish(input, parse(input))
 }
 
 fn do_round(input: &[i32], key: i64, rounds: i32) -> Vec<i64> {
@@ -40,7 +41,6 @@ fn do_round(input: &[i32], key: i64, rounds: i32) -> Vec<i64> {
     results.iter().map(|r| r.1).collect::<Vec<_>>()
 }
 
-#[aoc(day20, part1)]
 pub fn part1(input: &[i32]) -> i64 {
     let v = do_round(input, 1, 1);
 
@@ -48,7 +48,6 @@ pub fn part1(input: &[i32]) -> i64 {
     v[(zero + 1000) % v.len()] + v[(zero + 2000) % v.len()] + v[(zero + 3000) % v.len()]
 }
 
-#[aoc(day20, part2)]
 pub fn part2(input: &[i32]) -> i64 {
     let v = do_round(input, 811589153, 10);
 
@@ -56,7 +55,7 @@ pub fn part2(input: &[i32]) -> i64 {
     v[(zero + 1000) % v.len()] + v[(zero + 2000) % v.len()] + v[(zero + 3000) % v.len()]
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<i32>> {
+fn parse(input: &str) -> IResult<'_, Vec<i32>> {
     separated_list1(
         line_ending,
         map_res(recognize(tuple((opt(tag("-")), digit1))), str::parse),
@@ -77,11 +76,11 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 3);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 3);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 1623178306);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 1623178306);
     }
 }
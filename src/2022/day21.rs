@@ -1,6 +1,5 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -10,6 +9,11 @@ use nom::{
     sequence::{separated_pair, tuple},
 };
 
+use crate::{
+    parse::{finish, IResult},
+    Result,
+};
+
 const ROOT_MONKEY: &str = "root";
 const ME_MONKEY: &str = "humn";
 
@@ -49,11 +53,8 @@ impl From<(&str, &str, &str)> for Monkey {
     }
 }
 
-#[aoc_generator(day21)]
-pub fn input_generator(input: &str) -> Vec<(String, Monkey)> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty(), "remaining {rem}");
-    ret
+pub fn input_generator(input: &str) -> Result<Vec<(String, Monkey)>> {
+    finish(input, parse(input))
 }
 
 macro_rules! math {
@@ -93,14 +94,11 @@ fn resolve(map: &mut HashMap<String, Monkey>, part_2: bool) -> bool {
         // can we resolve this monkey?
         if let Monkey::Op(l, o, r) = monkey {
             // println!("found candidate");
-            match (map.get(l), map.get(r)) {
-                (Some(Monkey::Num(l)), Some(Monkey::Num(r))) => {
-                    let v = math!(l, o, r);
-                    let m_new = Monkey::Num(v);
-                    map.insert(name.to_owned(), m_new);
-                    return true;
-                }
-                _ => {}
+            if let (Some(Monkey::Num(l)), Some(Monkey::Num(r))) = (map.get(l), map.get(r)) {
+                let v = math!(l, o, r);
+                let m_new = Monkey::Num(v);
+                map.insert(name.to_owned(), m_new);
+                return true;
             }
         } else {
             unreachable!()
@@ -202,7 +200,6 @@ fn resolve(map: &mut HashMap<String, Monkey>, part_2: bool) -> bool {
     false
 }
 
-#[aoc(day21, part1)]
 pub fn part1(input: &[(String, Monkey)]) -> i64 {
     let mut monkeys: HashMap<String, Monkey> = input
         .iter()
@@ -211,11 +208,12 @@ pub fn part1(input: &[(String, Monkey)]) -> i64 {
 
     while resolve(&mut monkeys, false) {}
 
-    let Some(Monkey::Num(x)) = monkeys.get(ROOT_MONKEY) else {unreachable!()};
+    let Some(Monkey::Num(x)) = monkeys.get(ROOT_MONKEY) else {
+        unreachable!()
+    };
     *x
 }
 
-#[aoc(day21, part2)]
 pub fn part2(input: &[(String, Monkey)]) -> i64 {
     let mut monkeys: HashMap<String, Monkey> = input
         .iter()
@@ -227,17 +225,19 @@ pub fn part2(input: &[(String, Monkey)]) -> i64 {
 
     while resolve(&mut monkeys, true) {}
 
-    let Some(Monkey::Num(x)) = monkeys.get(ME_MONKEY) else {unreachable!()};
+    let Some(Monkey::Num(x)) = monkeys.get(ME_MONKEY) else {
+        unreachable!()
+    };
     *x
 }
 
-fn parse_monkey(input: &str) -> nom::IResult<&str, (String, Monkey)> {
+fn parse_monkey(input: &str) -> IResult<'_, (String, Monkey)> {
     map(
         separated_pair(
             alpha1,
             tag(": "),
             alt((
-                map(map_res(digit1, str::parse), |i| Monkey::Num(i)),
+                map(map_res(digit1, str::parse), Monkey::Num),
                 map(
                     tuple((
                         alpha1,
@@ -252,7 +252,7 @@ fn parse_monkey(input: &str) -> nom::IResult<&str, (String, Monkey)> {
     )(input)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<(String, Monkey)>> {
+fn parse(input: &str) -> IResult<'_, Vec<(String, Monkey)>> {
     separated_list1(line_ending, parse_monkey)(input)
 }
 
@@ -278,11 +278,11 @@ hmdt: 32";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 152);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 152);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 301);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 301);
     }
 }
@@ -1,13 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use aoc_runner_derive::{aoc, aoc_generator};
-use glam::IVec2;
+use glam::{IVec2, IVec3};
 use nom::{
     branch::alt,
     bytes::complete::take_till1,
-    character::complete::{alpha1, digit1, line_ending, space0},
-    combinator::{map, map_res},
+    character::complete::{char, digit1, line_ending, space0},
+    combinator::{map, map_res, value},
+    error::context,
     multi::{many1, separated_list1},
+    sequence::separated_pair,
+};
+
+use crate::{
+    parse::{blank_line, finish, IResult, ParseError},
+    visual::{self, Colour, Frame, Visualise},
+    Error, Result,
 };
 
 const LOOKUP_DIR: [Pos; 4] = [
@@ -17,7 +24,7 @@ const LOOKUP_DIR: [Pos; 4] = [
     Pos::new(0, -1),
 ];
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Step {
     Forward(i64),
     Right,
@@ -39,52 +46,37 @@ pub struct State<'a> {
     width: i32,
 }
 
-#[aoc_generator(day22)]
-pub fn input_generator(input: &str) -> (Map, Vec<Step>) {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty(), "remaining {rem}");
-    ret
-}
-
-#[inline]
-fn draw_direction(dir: &i8) {
-    match dir {
-        0 => print!(">"),
-        1 => print!("v"),
-        2 => print!("<"),
-        3 => print!("^"),
-        _ => unreachable!(),
+pub fn input_generator(input: &str) -> Result<(Map, Vec<Step>)> {
+    let (map, steps) = finish(input, parse(input))?;
+    // the path starts on the top row, which rules out an empty map too
+    if !map.iter().any(|(pos, &wall)| pos.y == 0 && !wall) {
+        return Err(ParseError::new(1, 1, "no open tile on the top row").into());
     }
+    Ok((map, steps))
 }
 
-#[allow(dead_code)]
-fn draw(state: &State) {
-    for y in 0..=state.height {
-        for x in 0..=state.width {
-            let p = (x, y).into();
-
-            // draw us
-            if state.pos == p {
-                // draw_direction(d);
-                print!("@");
-                continue;
-            }
-
-            // draw path
-            if let Some((_, d)) = state.path.iter().rev().find(|i| i.0 == p) {
-                draw_direction(d);
-                continue;
-            }
+/// [`input_generator`] for part 2, which needs the map to be the net of a cube.
+pub fn cube_generator(input: &str) -> Result<(Map, Vec<Step>, Cube)> {
+    let (map, steps) = input_generator(input)?;
+    let cube =
+        Cube::fold(&map).ok_or_else(|| Error::Parse("the map does not fold into a cube".into()))?;
+    Ok((map, steps, cube))
+}
 
-            match state.map.get(&p) {
-                None => print!(" "),
-                Some(true) => print!("#"),
-                Some(false) => print!("."),
-            }
+/// The board with the path walked so far, `@` being the current position.
+impl Visualise for State<'_> {
+    fn frame(&self) -> Frame {
+        let size = IVec2::new(self.width, self.height) + IVec2::ONE;
+        let mut frame = Frame::new(IVec2::ZERO, size, ' ');
+        for (&pos, &wall) in self.map {
+            frame.set(pos, if wall { '#' } else { '.' });
         }
-        println!("");
+        for &(pos, dir) in &self.path {
+            frame.set(pos, ['>', 'v', '<', '^'][dir as usize]);
+        }
+        frame.paint(self.pos, '@', Colour::Red);
+        frame
     }
-    println!("");
 }
 
 fn wrap(state: &mut State) -> bool {
@@ -106,91 +98,131 @@ fn wrap(state: &mut State) -> bool {
     }
 }
 
-// based on https://github.com/AxlLind/AdventOfCode2022/blob/a0c47df1fd608cd22f38a09a89c49f2f695fc253/src/bin/22.rs#L12
-fn wrap_p2(state: &mut State) -> bool {
-    #[cfg(test)]
-    let f = 4;
-    #[cfg(not(test))]
-    let f = 50;
-
-    // draw(state);
-
-    let y = state.pos.y;
-    let x = state.pos.x;
-    let dir = state.direction as usize;
-
-    // ┌─────────────────────────┐        ┌──────────────┐
-    // │                         │        │              │
-    // │                         │        │              │
-    // │                         │        │              │
-    // │                  ┌──────┴───┬────┴────┐         │
-    // │                  │          │         │         │
-    // │                  │          │         │         │
-    // │   ┌──────────────┤  1/0     │   2/0   ├──┐      │
-    // │   │              │          │         │  │      │
-    // │   │              │          │         │  │      │
-    // │   │              ├──────────┼────┬────┘  │      │
-    // │   │              │          │    │       │      │
-    // │   │              │          │    │       │      │
-    // │   │        ┌─────┤  1/1     ◄────┘       │      │
-    // │   │        │     │          │            │      │
-    // │   │        │     │          │            │      │
-    // │   │   ┌────▼─────┼──────────┤            │      │
-    // │   │   │          │          │            │      │
-    // │   │   │          │          ◄────────────┘      │
-    // │   └───►  0/2     │  1/2     │                   │
-    // │       │          │          │                   │
-    // │       │          │          │                   │
-    // │       ├──────────┼────▲─────┘                   │
-    // │       │          │    │                         │
-    // │       │          │    │                         │
-    // └───────►  0/3     │    │                         │
-    //         │          ├────┘                         │
-    //         │          │                              │
-    //         │          │                              │
-    //         └─────▲────┘                              │
-    //               │                                   │
-    //               │                                   │
-    //               └───────────────────────────────────┘
-
-    #[cfg(test)]
-    let (qx, qy, ndir) = match (x / f, y / f, dir) {
-        // test middle middle
-        (1, 1, 3) => (2, 0, 0),
-        // test middle right
-        (2, 1, 0) => (3, 2, 1),
-        // test bottom left
-        (2, 2, 1) => (0, 1, 3),
-        _ => unreachable!(),
-    };
-    #[cfg(not(test))]
-    let (qx, qy, ndir) = match (x / f, y / f, dir) {
-        // input
-        (0, 2, 2) => (1, 0, 0),
-        (0, 2, 3) => (1, 1, 0),
-        (0, 3, 0) => (1, 2, 3),
-        (0, 3, 1) => (2, 0, 1),
-        (0, 3, 2) => (1, 0, 1),
-
-        (1, 0, 2) => (0, 2, 0),
-        (1, 0, 3) => (0, 3, 0),
-        (1, 1, 0) => (2, 0, 3),
-        (1, 1, 2) => (0, 2, 1),
-        (1, 2, 0) => (2, 0, 2),
-        (1, 2, 1) => (0, 3, 2),
-
-        (2, 0, 0) => (1, 2, 2),
-        (2, 0, 1) => (1, 1, 2),
-        (2, 0, 3) => (0, 3, 3),
-
-        _ => unreachable!(),
-    };
+/// How the faces of a cube net are glued together once folded.
+#[derive(Debug)]
+pub struct Cube {
+    /// Edge length of the faces.
+    size: i32,
+    /// For a face, given by the position of its top left tile divided by `size`, and the direction
+    /// leaving it: the face entered and the direction on it.
+    edges: HashMap<(Pos, i8), (Pos, i8)>,
+}
+
+/// Orientation of a folded face in space: its outward normal and where right and down on the map
+/// point to.
+#[derive(Debug, Clone, Copy)]
+struct Orientation {
+    normal: IVec3,
+    right: IVec3,
+    down: IVec3,
+}
+
+impl Orientation {
+    /// Where the map direction `dir` points to in space.
+    fn towards(&self, dir: i8) -> IVec3 {
+        [self.right, self.down, -self.right, -self.down][dir as usize]
+    }
+
+    /// The orientation of the face next to this one in `dir` on the map, folded over their edge.
+    fn fold(&self, dir: i8) -> Self {
+        let Orientation {
+            normal,
+            right,
+            down,
+        } = *self;
+        match dir {
+            0 => Orientation {
+                normal: right,
+                right: -normal,
+                down,
+            },
+            1 => Orientation {
+                normal: down,
+                right,
+                down: -normal,
+            },
+            2 => Orientation {
+                normal: -right,
+                right: normal,
+                down,
+            },
+            _ => Orientation {
+                normal: -down,
+                right,
+                down: normal,
+            },
+        }
+    }
+}
+
+impl Cube {
+    /// Folds the map, which must be one of the 11 nets of a cube.
+    pub fn fold(map: &Map) -> Option<Cube> {
+        let size = (1..).find(|s| 6 * s * s >= map.len())? as i32;
+        let faces: HashSet<Pos> = map.keys().map(|&p| p / size).collect();
+        if 6 * size * size != map.len() as i32 || faces.len() != 6 {
+            return None;
+        }
+
+        // place the faces in space going along the net, starting with the top left one
+        let first = *faces.iter().min_by_key(|f| (f.y, f.x))?;
+        let mut oriented = HashMap::from([(
+            first,
+            Orientation {
+                normal: IVec3::Z,
+                right: IVec3::X,
+                down: IVec3::Y,
+            },
+        )]);
+        let mut queue = VecDeque::from([first]);
+        while let Some(face) = queue.pop_front() {
+            for dir in 0..4 {
+                let next = face + LOOKUP_DIR[dir as usize];
+                if faces.contains(&next) && !oriented.contains_key(&next) {
+                    oriented.insert(next, oriented[&face].fold(dir));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        // faces overlap when folding something else, like six in a row
+        let normals: HashSet<_> = oriented.values().map(|o| o.normal).collect();
+        if normals.len() != 6 {
+            return None;
+        }
 
-    let (dy, dx) = (y % f, x % f);
-    let i = [dy, f - 1 - dx, f - 1 - dy, dx][dir];
-    let (ny, nx) = [(i, 0), (0, f - 1 - i), (f - 1 - i, f - 1), (f - 1, i)][ndir];
-    let (new_pos, new_dir) = ((qx * f + nx, qy * f + ny).into(), ndir as i8);
+        // leaving a face, the next one is the face pointed to, entered heading away from the first
+        let mut edges = HashMap::new();
+        for (&face, orientation) in &oriented {
+            for dir in 0..4 {
+                let (&next, next_orientation) = oriented
+                    .iter()
+                    .find(|(_, o)| o.normal == orientation.towards(dir))?;
+                let next_dir =
+                    (0..4).find(|&d| next_orientation.towards(d) == -orientation.normal)?;
+                edges.insert((face, dir), (next, next_dir));
+            }
+        }
+
+        Some(Cube { size, edges })
+    }
+
+    /// The tile and direction after walking off the face at `pos` heading in `dir`.
+    fn wrap(&self, pos: Pos, dir: i8) -> (Pos, i8) {
+        let f = self.size;
+        let (next, next_dir) = self.edges[&(pos / f, dir)];
+
+        // index along the edge, going clockwise around the face, which is reversed on the other side
+        let (dx, dy) = (pos.x % f, pos.y % f);
+        let i = [dy, f - 1 - dx, f - 1 - dy, dx][dir as usize];
+        let (nx, ny) = [(0, i), (f - 1 - i, 0), (f - 1, f - 1 - i), (i, f - 1)][next_dir as usize];
+
+        (next * f + Pos::new(nx, ny), next_dir)
+    }
+}
 
+fn wrap_cube(state: &mut State, cube: &Cube) -> bool {
+    let (new_pos, new_dir) = cube.wrap(state.pos, state.direction);
     match state.map.get(&new_pos) {
         Some(false) => {
             state.path.push((state.pos, state.direction));
@@ -203,7 +235,8 @@ fn wrap_p2(state: &mut State) -> bool {
     }
 }
 
-fn do_it(state: &mut State, p2: bool) -> i32 {
+/// Follows the steps, wrapping around the map or, with a `cube`, around its faces.
+fn do_it(state: &mut State, cube: Option<&Cube>) -> i32 {
     for s in state.steps {
         match s {
             Step::Forward(i) => {
@@ -214,7 +247,10 @@ fn do_it(state: &mut State, p2: bool) -> i32 {
                     match state.map.get(&new_pos) {
                         None => {
                             //wrap around
-                            let hit_wall = if p2 { wrap_p2(state) } else { wrap(state) };
+                            let hit_wall = match cube {
+                                Some(cube) => wrap_cube(state, cube),
+                                None => wrap(state),
+                            };
                             if hit_wall {
                                 break;
                             }
@@ -236,10 +272,11 @@ fn do_it(state: &mut State, p2: bool) -> i32 {
         }
     }
 
+    visual::draw(state);
+
     (state.pos.y + 1) * 1000 + (state.pos.x + 1) * 4 + (state.direction) as i32
 }
 
-#[aoc(day22, part1)]
 pub fn part1(input: &(Map, Vec<Step>)) -> i32 {
     let (map, steps) = input;
 
@@ -248,7 +285,7 @@ pub fn part1(input: &(Map, Vec<Step>)) -> i32 {
 
     // find start pos
     let mut player = (0, 0);
-    for x in 0..width {
+    for x in 0..=width {
         match map.get(&(x, 0).into()) {
             None | Some(true) => continue,
             Some(false) => {
@@ -268,19 +305,18 @@ pub fn part1(input: &(Map, Vec<Step>)) -> i32 {
         width,
     };
 
-    do_it(&mut state, false)
+    do_it(&mut state, None)
 }
 
-#[aoc(day22, part2)]
-pub fn part2(input: &(Map, Vec<Step>)) -> i32 {
-    let (map, steps) = input;
+pub fn part2(input: &(Map, Vec<Step>, Cube)) -> i32 {
+    let (map, steps, cube) = input;
 
     let width = map.iter().map(|t| t.0.x).max().unwrap();
     let height = map.iter().map(|t| t.0.y).max().unwrap();
 
     // find start pos
     let mut player = (0, 0);
-    for x in 0..width {
+    for x in 0..=width {
         match map.get(&(x, 0).into()) {
             None | Some(true) => continue,
             Some(false) => {
@@ -300,12 +336,10 @@ pub fn part2(input: &(Map, Vec<Step>)) -> i32 {
         width,
     };
 
-    // 174109 too high
-
-    do_it(&mut state, true)
+    do_it(&mut state, Some(cube))
 }
 
-fn parse_map(input: &str) -> nom::IResult<&str, Vec<(usize, bool)>> {
+fn parse_map(input: &str) -> IResult<'_, Vec<(usize, bool)>> {
     let (rem, space_left) = space0(input)?;
     let space_left = space_left.len();
     let (rem, d) = take_till1(|c| c != '.' && c != '#')(rem)?;
@@ -324,22 +358,20 @@ fn parse_map(input: &str) -> nom::IResult<&str, Vec<(usize, bool)>> {
     Ok((rem, v))
 }
 
-fn parse_steps(input: &str) -> nom::IResult<&str, Vec<Step>> {
+fn parse_steps(input: &str) -> IResult<'_, Vec<Step>> {
     many1(alt((
-        map(map_res(digit1, str::parse), |i| Step::Forward(i)),
-        map(alpha1, |c| match c {
-            "R" => Step::Right,
-            "L" => Step::Left,
-            _ => unreachable!(),
-        }),
+        map(map_res(digit1, str::parse), Step::Forward),
+        value(Step::Right, char('R')),
+        value(Step::Left, char('L')),
     )))(input)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, (Map, Vec<Step>)> {
-    let mut it = input.split("\n\n");
-
-    let (_, map) = separated_list1(line_ending, parse_map)(it.next().unwrap())?;
-    let (rem, steps) = parse_steps(it.next().unwrap())?;
+fn parse(input: &str) -> IResult<'_, (Map, Vec<Step>)> {
+    let (rem, (map, steps)) = separated_pair(
+        context("map", separated_list1(line_ending, parse_map)),
+        blank_line,
+        context("path", parse_steps),
+    )(input)?;
 
     let map = map
         .into_iter()
@@ -356,7 +388,9 @@ fn parse(input: &str) -> nom::IResult<&str, (Map, Vec<Step>)> {
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use glam::IVec2;
+
+    use super::{cube_generator, input_generator, part1, part2, Cube, Map, LOOKUP_DIR};
 
     const INPUT: &str = "        ...#
         .#..
@@ -375,11 +409,108 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 6032);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 6032);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 5031);
+        assert_eq!(part2(&cube_generator(INPUT).unwrap()), 5031);
+    }
+
+    #[test]
+    fn nets() {
+        // the 11 ways to unfold a cube, one `#` per face
+        const NETS: [&str; 11] = [
+            "#...\n####\n#...",
+            "#...\n####\n.#..",
+            "#...\n####\n..#.",
+            "#...\n####\n...#",
+            ".#..\n####\n.#..",
+            ".#..\n####\n..#.",
+            "##..\n.###\n.#..",
+            "##..\n.###\n..#.",
+            "##..\n.###\n...#",
+            "##..\n.##.\n..##",
+            "###..\n..###",
+        ];
+
+        let size = 3;
+        for net in NETS {
+            let map: Map = net
+                .lines()
+                .enumerate()
+                .flat_map(|(y, row)| row.match_indices('#').map(move |(x, _)| (x, y)))
+                .flat_map(|(x, y)| {
+                    let face = IVec2::new(x as i32, y as i32) * size;
+                    (0..size * size).map(move |i| (face + IVec2::new(i % size, i / size), false))
+                })
+                .collect();
+            let cube = Cube::fold(&map).unwrap();
+
+            // walking off an edge and turning around leads back to the same tile
+            for &pos in map.keys() {
+                for dir in 0..4 {
+                    if map.contains_key(&(pos + LOOKUP_DIR[dir as usize])) {
+                        continue;
+                    }
+                    let (next, next_dir) = cube.wrap(pos, dir);
+                    assert!(map.contains_key(&next), "{net}: {pos} {dir}");
+                    let back = (next_dir + 2) % 4;
+                    assert!(!map.contains_key(&(next + LOOKUP_DIR[back as usize])));
+                    assert_eq!(
+                        cube.wrap(next, back),
+                        (pos, (dir + 2) % 4),
+                        "{net}: {pos} {dir}"
+                    );
+                }
+            }
+        }
+
+        let mut map = input_generator(INPUT).unwrap().0;
+        map.remove(&IVec2::new(8, 0));
+        assert!(Cube::fold(&map).is_none());
+
+        let row = (0..6).map(|x| (IVec2::new(x, 0), false)).collect();
+        assert!(Cube::fold(&row).is_none());
+
+        let input = "......\n\n4R2";
+        assert_eq!(part1(&input_generator(input).unwrap()), 1021);
+        assert_eq!(
+            cube_generator(input).unwrap_err().to_string(),
+            "invalid input: the map does not fold into a cube"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("..#.\n....\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 1: expected crlf"
+        );
+        let err = input_generator("..x.\n\n4R2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 3: expected crlf"
+        );
+        let err = input_generator("....\n\n4X2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 2: unexpected trailing input"
+        );
+    }
+
+    #[test]
+    fn empty_map() {
+        let err = input_generator("\n\n4R2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 1: expected taketill1 (in map)"
+        );
+        let err = input_generator("  ##\n\n4R2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 1: no open tile on the top row"
+        );
     }
 }
@@ -1,9 +1,9 @@
 use std::collections::HashSet;
 
-use aoc_runner_derive::aoc;
-use aoc_runner_derive::aoc_generator;
 use itertools::Itertools;
 
+use crate::{parse::ParseError, Result};
+
 fn convert_to_hashset(input: &str) -> HashSet<u32> {
     // println!("{input:?}");
 
@@ -11,28 +11,29 @@ fn convert_to_hashset(input: &str) -> HashSet<u32> {
         input
             .chars()
             .map(|c| match c as u8 {
-                c if c >= b'a' && c <= b'z' => c - b'a' + 1,
-                c if c >= b'A' && c <= b'Z' => c - b'A' + 1 + 26,
+                c if c.is_ascii_lowercase() => c - b'a' + 1,
+                c if c.is_ascii_uppercase() => c - b'A' + 1 + 26,
                 _ => panic!("unexpected character"),
             })
             .map(|c| c as u32),
     )
 }
 
-#[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> Vec<(HashSet<u32>, HashSet<u32>)> {
+pub fn input_generator(input: &str) -> Result<Vec<(HashSet<u32>, HashSet<u32>)>> {
     input
         .lines()
-        .map(|line| line.split_at(line.len() / 2))
-        .map(|(a, b)| {
-            assert_eq!(a.len(), b.len(), "number of rucksack items is odd");
-            (a, b)
+        .enumerate()
+        .map(|(i, line)| {
+            if line.len() % 2 != 0 {
+                let error = ParseError::new(i + 1, 1, "number of rucksack items is odd");
+                return Err(error.into());
+            }
+            let (a, b) = line.split_at(line.len() / 2);
+            Ok((convert_to_hashset(a), convert_to_hashset(b)))
         })
-        .map(|(a, b)| (convert_to_hashset(a), convert_to_hashset(b)))
         .collect()
 }
 
-#[aoc(day3, part1)]
 pub fn part1(input: &[(HashSet<u32>, HashSet<u32>)]) -> u32 {
     // println!("{input:#?}");
     input
@@ -46,19 +47,16 @@ pub fn part1(input: &[(HashSet<u32>, HashSet<u32>)]) -> u32 {
         .sum()
 }
 
-#[aoc(day3, part2)]
 pub fn part2(input: &[(HashSet<u32>, HashSet<u32>)]) -> u32 {
     input
-        .into_iter()
+        .iter()
         .map(|(a, b)| HashSet::from_iter(a.union(b)))
         .chunks(3)
         .into_iter()
         .map(|chunk| {
             let folded = chunk
                 .into_iter()
-                .reduce(|acc: HashSet<&u32>, c| {
-                    HashSet::from_iter(acc.intersection(&c).map(|a| *a))
-                })
+                .reduce(|acc: HashSet<&u32>, c| HashSet::from_iter(acc.intersection(&c).copied()))
                 .unwrap();
 
             // convert to vec to get first (only) element
@@ -82,11 +80,11 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 157);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 157);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 70);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 70);
     }
 }
@@ -1,12 +1,14 @@
-use aoc_runner_derive::aoc;
-use aoc_runner_derive::aoc_generator;
-use nom::Finish;
 use nom::bytes::complete::tag;
 use nom::character::complete::digit1;
 use nom::combinator::map;
 use nom::combinator::map_res;
 use nom::sequence::separated_pair;
 
+use crate::{
+    parse::{lines, IResult},
+    Result,
+};
+
 pub struct Data {
     a: (u32, u32),
     b: (u32, u32),
@@ -32,25 +34,19 @@ impl Data {
     }
 }
 
-#[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Vec<Data> {
-    input
-        .lines()
-        .map(|line| parse(line).finish().map(|(_, x)| x).unwrap())
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Data>> {
+    lines(input, parse)
 }
 
-#[aoc(day4, part1)]
 pub fn part1(input: &[Data]) -> u32 {
     input.iter().filter(|d| d.overlapping_p1()).count() as u32
 }
 
-#[aoc(day4, part2)]
 pub fn part2(input: &[Data]) -> u32 {
     input.iter().filter(|d| d.overlapping_p2()).count() as u32
 }
 
-fn parse_range(input: &str) -> nom::IResult<&str, (u32, u32)> {
+fn parse_range(input: &str) -> IResult<'_, (u32, u32)> {
     separated_pair(
         map_res(digit1, str::parse),
         tag("-"),
@@ -58,7 +54,7 @@ fn parse_range(input: &str) -> nom::IResult<&str, (u32, u32)> {
     )(input)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Data> {
+fn parse(input: &str) -> IResult<'_, Data> {
     map(
         separated_pair(parse_range, tag(","), parse_range),
         |(a, b)| Data { a, b },
@@ -78,11 +74,11 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 2);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 2);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 4);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 4);
     }
 }
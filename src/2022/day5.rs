@@ -1,14 +1,17 @@
 use std::collections::VecDeque;
 
-use aoc_runner_derive::aoc;
-use aoc_runner_derive::aoc_generator;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{anychar, char, digit1, line_ending},
+    character::complete::{anychar, char, digit1, line_ending, not_line_ending},
     combinator::map_res,
-    multi::separated_list1,
-    sequence::{delimited, tuple},
+    multi::{count, separated_list1},
+    sequence::{delimited, preceded, tuple},
+};
+
+use crate::{
+    parse::{finish, IResult},
+    Result,
 };
 
 #[derive(Debug, Clone)]
@@ -49,9 +52,8 @@ impl State {
                 }
             } else {
                 for _ in 0..*count {
-                    match stacks[src - 1].pop_front() {
-                        Some(a) => stacks[dst - 1].push_front(a),
-                        None => (),
+                    if let Some(a) = stacks[src - 1].pop_front() {
+                        stacks[dst - 1].push_front(a)
                     }
                 }
             }
@@ -61,13 +63,10 @@ impl State {
     }
 }
 
-#[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> State {
-    let (_, s) = parse(input).expect("failed to parse input");
-    s
+pub fn input_generator(input: &str) -> Result<State> {
+    finish(input, parse(input))
 }
 
-#[aoc(day5, part1)]
 pub fn part1(input: &State) -> String {
     // println!("{input:?}");
 
@@ -76,27 +75,26 @@ pub fn part1(input: &State) -> String {
     state.get_tops()
 }
 
-#[aoc(day5, part2)]
 pub fn part2(input: &State) -> String {
     let mut state = input.clone();
     state.run_moves(true);
     state.get_tops()
 }
 
-fn parse_stack_crate(input: &str) -> nom::IResult<&str, char> {
+fn parse_stack_crate(input: &str) -> IResult<'_, char> {
     delimited(char('['), anychar, char(']'))(input)
 }
 
 const EMPTY_STACK: &str = "   ";
-fn parse_stack_empty(input: &str) -> nom::IResult<&str, char> {
+fn parse_stack_empty(input: &str) -> IResult<'_, char> {
     nom::combinator::map(tag(EMPTY_STACK), |_| ' ')(input)
 }
 
-fn parse_stack(input: &str) -> nom::IResult<&str, Vec<char>> {
+fn parse_stack(input: &str) -> IResult<'_, Vec<char>> {
     separated_list1(char(' '), alt((parse_stack_crate, parse_stack_empty)))(input)
 }
 
-fn parse_moves(line: &str) -> nom::IResult<&str, Move> {
+fn parse_moves(line: &str) -> IResult<'_, Move> {
     nom::combinator::map(
         tuple((
             tag("move "),
@@ -110,14 +108,13 @@ fn parse_moves(line: &str) -> nom::IResult<&str, Move> {
     )(line)
 }
 
-fn parse(input: &str) -> nom::IResult<&str, State> {
-    let halves = input.split_once("\n\n").unwrap();
-    let (rem, stacks_horizontal) = separated_list1(line_ending, parse_stack)(halves.0)?;
-
-    dbg!(&rem);
-    // println!("{stacks_horizontal:#?}");
+fn parse(input: &str) -> IResult<'_, State> {
+    let (rem, stacks_horizontal) = separated_list1(line_ending, parse_stack)(input)?;
+    let (rem, labels) = preceded(line_ending, not_line_ending)(rem)?;
+    let (rem, _) = count(line_ending, 2)(rem)?;
+    let (rem, moves) = separated_list1(line_ending, parse_moves)(rem)?;
 
-    let num = (rem.chars().count() + 1) / 4;
+    let num = labels.split_whitespace().count();
 
     // flip stack to vertical layout
     let mut stacks: Vec<VecDeque<char>> = vec![];
@@ -139,12 +136,6 @@ fn parse(input: &str) -> nom::IResult<&str, State> {
         }
     }
 
-    let mut moves = vec![];
-    for line in halves.1.lines() {
-        let (_, mv) = parse_moves(line)?;
-        moves.push(mv);
-    }
-
     Ok((rem, State { stacks, moves }))
 }
 
@@ -164,11 +155,11 @@ move 1 from 1 to 2";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), "CMZ");
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), "CMZ");
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), "MCD");
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), "MCD");
     }
 }
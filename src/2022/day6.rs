@@ -1,5 +1,3 @@
-use aoc_runner_derive::aoc;
-
 fn do_it(input: &str, len: usize) -> u32 {
     for (i, section) in input.chars().collect::<Vec<_>>().windows(len).enumerate() {
         let mut passed = 0;
@@ -16,12 +14,10 @@ fn do_it(input: &str, len: usize) -> u32 {
     unreachable!()
 }
 
-#[aoc(day6, part1)]
 pub fn part1(input: &str) -> u32 {
     do_it(input, 4)
 }
 
-#[aoc(day6, part2)]
 pub fn part2(input: &str) -> u32 {
     do_it(input, 14)
 }
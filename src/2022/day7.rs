@@ -1,39 +1,37 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::{
-        complete::{alpha1, alphanumeric1, anychar},
+        complete::{alpha1, anychar, digit1},
         streaming::space1,
     },
-    combinator::map,
+    combinator::{map, map_res},
     multi::many1,
     sequence::{preceded, separated_pair},
 };
 use std::{collections::HashMap, ops::AddAssign, path::PathBuf};
 
+use crate::{
+    parse::{finish_line, IResult, ParseError},
+    Result,
+};
+
 #[derive(Debug)]
 enum Cmd {
     Cd(String),
     Ls,
 }
 
-#[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> HashMap<String, usize> {
+pub fn input_generator(input: &str) -> Result<HashMap<String, usize>> {
     parse(input)
 }
 
-#[aoc(day7, part1)]
 pub fn part1(directories: &HashMap<String, usize>) -> usize {
     const LIMIT: usize = 100000;
 
-    directories
-        .into_iter()
-        .filter_map(|(_, size)| if size <= &LIMIT { Some(size) } else { None })
-        .sum()
+    directories.values().filter(|&size| size <= &LIMIT).sum()
 }
 
-#[aoc(day7, part2)]
 pub fn part2(input: &HashMap<String, usize>) -> usize {
     const FS_SIZE: usize = 70_000_000;
     const FS_REQUIRED: usize = 30_000_000;
@@ -57,22 +55,17 @@ pub fn part2(input: &HashMap<String, usize>) -> usize {
         .unwrap()
 }
 
-fn parse_ls_file(line: &str) -> nom::IResult<&str, (String, usize)> {
+fn parse_ls_file(line: &str) -> IResult<'_, (String, usize)> {
     map(
-        separated_pair(alphanumeric1::<&str, _>, space1, many1(anychar)),
-        |(size, name)| {
-            (
-                String::from_iter(name.into_iter()),
-                size.parse().expect("failed to parse file size"),
-            )
-        },
+        separated_pair(map_res(digit1, str::parse), space1, many1(anychar)),
+        |(size, name)| (String::from_iter(name), size),
     )(line)
 }
-// fn parse_ls_dir(line: &str) -> nom::IResult<&str, &str> {
+// fn parse_ls_dir(line: &str) -> IResult<'_, &str> {
 //     preceded(tag("dir "), alpha1::<&str, _>)(line)
 // }
 
-fn parse_command(line: &str) -> nom::IResult<&str, Cmd> {
+fn parse_command(line: &str) -> IResult<'_, Cmd> {
     preceded(
         tag("$ "),
         alt((
@@ -85,13 +78,15 @@ fn parse_command(line: &str) -> nom::IResult<&str, Cmd> {
     )(line)
 }
 
-fn parse(input: &str) -> HashMap<String, usize> {
-    let mut iter = input.lines();
+fn parse(input: &str) -> Result<HashMap<String, usize>> {
+    let mut iter = input.lines().enumerate();
 
     // start with "cd /" command
-    let (rem, cmd) = parse_command(iter.next().unwrap()).unwrap();
-    assert!(rem.is_empty());
-    assert!(matches!(cmd, Cmd::Cd(dir) if &dir == "/"));
+    let first = iter.next().map_or("", |(_, line)| line);
+    let cmd = finish_line(0, first, parse_command(first))?;
+    if !matches!(cmd, Cmd::Cd(dir) if &dir == "/") {
+        return Err(ParseError::new(1, 1, "expected `$ cd /`").into());
+    }
 
     // Structure:
     // First, go over all file information and `cd` commands.
@@ -104,24 +99,19 @@ fn parse(input: &str) -> HashMap<String, usize> {
     let mut current_dir = PathBuf::from("/");
     let mut files = vec![];
 
-    while let Some(line) = iter.next() {
+    for (i, line) in iter {
         match line {
-            cmd if cmd.starts_with("$") => {
-                let (rem, cmd) = parse_command(cmd).unwrap();
-                assert!(rem.is_empty());
-                match cmd {
-                    Cmd::Cd(path) => match path.as_ref() {
-                        ".." => _ = current_dir.pop(),
-                        "/" => unreachable!(), // just in case
-                        path => current_dir.push(path),
-                    },
-                    Cmd::Ls => {}
-                }
-            }
+            cmd if cmd.starts_with("$") => match finish_line(i, cmd, parse_command(cmd))? {
+                Cmd::Cd(path) => match path.as_ref() {
+                    ".." => _ = current_dir.pop(),
+                    "/" => current_dir = PathBuf::from("/"),
+                    path => current_dir.push(path),
+                },
+                Cmd::Ls => {}
+            },
             dir if dir.starts_with("dir") => {}
             file => {
-                let (rem, (name, size)) = parse_ls_file(file).unwrap();
-                assert!(rem.is_empty());
+                let (name, size) = finish_line(i, file, parse_ls_file(file))?;
                 files.push((current_dir.to_str().unwrap().to_owned(), name, size));
             }
         }
@@ -137,7 +127,7 @@ fn parse(input: &str) -> HashMap<String, usize> {
                 .add_assign(size);
         }
     }
-    directories
+    Ok(directories)
 }
 
 #[cfg(test)]
@@ -170,11 +160,11 @@ $ ls
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 95437);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 95437);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 24933642);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 24933642);
     }
 }
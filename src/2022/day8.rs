@@ -1,189 +1,82 @@
-use std::collections::HashMap;
-
-use aoc_runner_derive::{aoc, aoc_generator};
+use glam::IVec2;
 use nom::{
     character::complete::{digit1, line_ending},
     combinator::map,
     multi::separated_list1,
 };
 
-#[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Vec<Vec<u32>> {
-    let (rem, ret) = parse(input).expect("failed to parse input");
-    assert!(rem.is_empty());
-    ret
-}
-
-#[aoc(day8, part1)]
-pub fn part1(input: &[Vec<u32>]) -> usize {
-    // should be a square
-    assert_eq!(input.len(), input[0].len());
-    let size = input.len();
-
-    let mut visible: HashMap<(usize, usize), bool> = HashMap::new();
-
-    // add edges
-    for i in 0..size {
-        *visible.entry((i, 0)).or_default() |= true;
-        *visible.entry((0, i)).or_default() |= true;
-        *visible.entry((i, size - 1)).or_default() |= true;
-        *visible.entry((size - 1, i)).or_default() |= true;
-    }
-
-    // X
-    for x in 0..size {
-        // skip edges
-        if x == 0 || x == size - 1 {
-            continue;
-        }
-
-        let res = do_comparison(input[x].iter());
-        for (y, v) in res.into_iter().enumerate() {
-            *visible.entry((x, y)).or_default() |= v;
-        }
-    }
-    for x in 0..size {
-        // skip edges
-        if x == 0 || x == size - 1 {
-            continue;
-        }
-
-        let res = do_comparison(input[x].iter().rev());
-        for (y, v) in res.into_iter().rev().enumerate() {
-            *visible.entry((x, y)).or_default() |= v;
-        }
-    }
-
-    // Y
-    for y in 0..size {
-        // skip edges
-        if y == 0 || y == size - 1 {
-            continue;
-        }
-
-        let res = do_comparison(input.iter().map(|row| &row[y]));
-        for (x, v) in res.into_iter().enumerate() {
-            *visible.entry((x, y)).or_default() |= v;
-        }
-    }
-    for y in 0..size {
-        // skip edges
-        if y == 0 || y == size - 1 {
-            continue;
-        }
-
-        let res = do_comparison(input.iter().map(|row| &row[y]).rev());
-        for (x, v) in res.into_iter().rev().enumerate() {
-            *visible.entry((x, y)).or_default() |= v;
-        }
-    }
+use crate::grid::{Grid, DIRECTIONS4};
 
-    // pretty_print(&visible, size);
+use crate::{
+    parse::{finish, IResult},
+    visual::{self, Frame, Visualise},
+    Result,
+};
 
-    visible.iter().filter(|&(_, val)| *val).count()
+pub fn input_generator(input: &str) -> Result<Grid<u32>> {
+    Grid::from_rows(finish(input, parse(input))?)
 }
 
-#[allow(dead_code)]
-fn pretty_print(map: &HashMap<(usize, usize), bool>, size: usize) {
-    for y in 0..size {
-        for x in 0..size {
-            match map.get(&(x, y)).unwrap() {
-                true => print!("#"),
-                false => print!("."),
-            }
-        }
-        print!("\n");
+/// The trees of the map, seen from outside.
+struct Forest<'a>(&'a Grid<u32>);
+
+impl Forest<'_> {
+    /// A tree is visible if all trees towards one of the edges are lower, trees on the edge have
+    /// none.
+    fn is_visible(&self, pos: IVec2) -> bool {
+        let map = self.0;
+        DIRECTIONS4
+            .into_iter()
+            .any(|dir| map.ray(pos, dir).all(|p| map[p] < map[pos]))
     }
 }
 
-fn do_comparison<'a>(trees: impl Iterator<Item = &'a u32>) -> Vec<bool> {
-    let mut max_hight = -1;
-    let mut visible = vec![];
-
-    for tree in trees {
-        // initialize max_hight with first value
-        if max_hight == -1 {
-            max_hight = *tree as i32;
-
-            // this tree is always visible
-            visible.push(true);
-            continue;
-        }
-
-        if *tree as i32 > max_hight {
-            max_hight = *tree as i32;
-            visible.push(true);
-        } else {
-            visible.push(false);
+/// The visible trees as `#`, the hidden ones as `.`.
+impl Visualise for Forest<'_> {
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::from_grid(self.0, |_| '.');
+        for pos in self.0.positions().filter(|&pos| self.is_visible(pos)) {
+            frame.set(pos, '#');
         }
+        frame
     }
-
-    visible
 }
 
-#[aoc(day8, part2)]
-pub fn part2(input: &[Vec<u32>]) -> usize {
-    let size = input.len();
-
-    let mut max_score = 0;
-
-    for y in 0..size {
-        for x in 0..size {
-            let s = calc_score(input, x, y);
-            max_score = max_score.max(s);
-            // print!("{s}");
-        }
-        // println!("");
-    }
-    max_score
+pub fn part1(input: &Grid<u32>) -> usize {
+    let forest = Forest(input);
+    visual::draw(&forest);
+    input
+        .positions()
+        .filter(|&pos| forest.is_visible(pos))
+        .count()
 }
 
-fn calc_score(map: &[Vec<u32>], start_x: usize, start_y: usize) -> usize {
-    let size = map.len();
-
-    let mut l = 0;
-    let mut r = 0;
-    let mut u = 0;
-    let mut d = 0;
-
-    let base = map[start_x][start_y];
-
-    // left
-    for (count, x) in (0..start_x).rev().enumerate() {
-        if map[x][start_y] >= base || x == 0 {
-            l = count + 1;
-            break;
-        }
-    }
-
-    // right
-    for (count, x) in (start_x + 1..size).enumerate() {
-        if map[x][start_y] >= base || x == size - 1 {
-            r = count + 1;
-            break;
-        }
-    }
-
-    // up
-    for (count, y) in (0..start_y).rev().enumerate() {
-        if map[start_x][y] >= base || y == 0 {
-            u = count + 1;
-            break;
-        }
-    }
-
-    // down
-    for (count, y) in (start_y + 1..size).enumerate() {
-        if map[start_x][y] >= base || y == size - 1 {
-            d = count + 1;
-            break;
-        }
-    }
+pub fn part2(input: &Grid<u32>) -> usize {
+    input
+        .iter()
+        .map(|(pos, &tree)| calc_score(input, pos, tree))
+        .max()
+        .unwrap()
+}
 
-    l * r * u * d
+fn calc_score(map: &Grid<u32>, start: IVec2, base: u32) -> usize {
+    DIRECTIONS4
+        .into_iter()
+        .map(|dir| {
+            // the view ends at the edge or includes the first tree that is not lower
+            let mut count = 0;
+            for p in map.ray(start, dir) {
+                count += 1;
+                if map[p] >= base {
+                    break;
+                }
+            }
+            count
+        })
+        .product()
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Vec<u32>>> {
+fn parse(input: &str) -> IResult<'_, Vec<Vec<u32>>> {
     separated_list1(
         line_ending,
         map(digit1, |a: &str| {
@@ -194,7 +87,9 @@ fn parse(input: &str) -> nom::IResult<&str, Vec<Vec<u32>>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use crate::visual::Visualise;
+
+    use super::{input_generator, part1, part2, Forest};
 
     const INPUT: &str = "30373
 25512
@@ -204,11 +99,25 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 21);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 21);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 8);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 8);
+    }
+
+    #[test]
+    fn frame() {
+        let grid = input_generator(INPUT).unwrap();
+        assert_eq!(
+            Forest(&grid).frame().to_string(),
+            "\
+#####
+###.#
+##.##
+#.#.#
+#####"
+        );
     }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use glam::IVec2;
 use nom::{
     character::complete::{digit1, line_ending, one_of, space1},
     combinator::map,
@@ -8,6 +8,12 @@ use nom::{
     sequence::separated_pair,
 };
 
+use crate::{
+    parse::{finish, IResult},
+    visual::{self, Frame, Visualise},
+    Result,
+};
+
 pub enum Direction {
     Up,
     Down,
@@ -31,18 +37,51 @@ pub struct Steps {
     num: u32,
 }
 
-#[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Vec<Steps> {
-    let (rem, v) = parse(input).unwrap();
-    assert!(rem.is_empty());
-    v
+pub fn input_generator(input: &str) -> Result<Vec<Steps>> {
+    finish(input, parse(input))
 }
 
 type Pos = (i32, i32);
 
+/// The move of a knot towards the one before it, `diff` away.
+type Follow = fn(i32, i32) -> (i32, i32);
+
+/// Looks the move up for every distance the knots can be apart.
+fn follow_table(diff_x: i32, diff_y: i32) -> (i32, i32) {
+    match (diff_x, diff_y) {
+        // valid states
+        (0, 0) | (1, 0) | (-1, 0) | (0, 1) | (0, -1) | (1, 1) | (-1, 1) | (1, -1) | (-1, -1) => {
+            (0, 0)
+        }
+
+        // horizontal
+        (0, 2) => (0, 1),
+        (0, -2) => (0, -1),
+
+        // vertical
+        (2, 0) => (1, 0),
+        (-2, 0) => (-1, 0),
+
+        // diagonal
+        (x, y) if x != 0 && y != 0 => (x.signum(), y.signum()),
+
+        // everything else can't happen
+        _ => unreachable!(),
+    }
+}
+
+/// Moves by one step in each direction the knots are apart, unless they touch.
+fn follow_signum(diff_x: i32, diff_y: i32) -> (i32, i32) {
+    match diff_x.abs().max(diff_y.abs()) {
+        0 | 1 => (0, 0),
+        _ => (diff_x.signum(), diff_y.signum()),
+    }
+}
+
 #[derive(Debug)]
 pub struct State {
     knots: Vec<Pos>,
+    follow: Follow,
     visited: HashMap<Pos, bool>,
 }
 
@@ -63,32 +102,7 @@ impl State {
             debug_assert!(diff_x <= 2);
             debug_assert!(diff_y <= 2);
 
-            let mv_b = match (diff_x, diff_y) {
-                // valid states
-                (0, 0)
-                | (1, 0)
-                | (-1, 0)
-                | (0, 1)
-                | (0, -1)
-                | (1, 1)
-                | (-1, 1)
-                | (1, -1)
-                | (-1, -1) => (0, 0),
-
-                // horizontal
-                (0, 2) => (0, 1),
-                (0, -2) => (0, -1),
-
-                // vertical
-                (2, 0) => (1, 0),
-                (-2, 0) => (-1, 0),
-
-                // diagonal
-                (x, y) if x != 0 && y != 0 => (x.signum(), y.signum()),
-
-                // everything else can't happen
-                _ => unreachable!(),
-            };
+            let mv_b = (self.follow)(diff_x, diff_y);
 
             // skip
             if mv_b == (0, 0) {
@@ -118,75 +132,63 @@ impl State {
                 _ = self.visited.insert(*self.knots.last().unwrap(), true);
             }
 
-            #[cfg(test)]
-            {
-                // pretty print knot snake
-                for x in 0..=5 {
-                    for y in 0..=4 {
-                        match self
-                            .knots
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, &k)| k == (x, y))
-                            .last()
-                        {
-                            Some((n, _)) => print!("{n}"),
-                            None => print!("."),
-                        }
-                    }
-                    println!("");
-                }
-                println!("");
-            }
+            visual::draw(self);
         }
 
         &self.visited
     }
 }
 
-#[aoc(day9, part1)]
+/// The knots over the positions visited by the tail, `H` being the head and `s` the start.
+impl Visualise for State {
+    fn frame(&self) -> Frame {
+        // y grows upwards
+        let at = |(x, y): Pos| IVec2::new(x, -y);
+        let visited = self.visited.keys().map(|&pos| (at(pos), '#'));
+        let knots = self.knots.iter().enumerate().rev().map(|(n, &pos)| {
+            let c = match n {
+                0 => 'H',
+                n => char::from_digit(n as u32, 36).unwrap(),
+            };
+            (at(pos), c)
+        });
+        let start = std::iter::once((at((0, 0)), 's'));
+        Frame::from_points(visited.chain(start).chain(knots), '.')
+    }
+}
+
 pub fn part1(input: &[Steps]) -> usize {
-    let mut state = State {
-        knots: vec![(0, 0), (0, 0)],
-        visited: HashMap::new(),
-    };
-    _ = state.visited.insert((0, 0), true);
-    _ = state.handle_steps(input);
+    visited_by_tail(input, 2, follow_table)
+}
 
-    state.visited.into_iter().filter(|(_, v)| *v).count()
+/// [`part1`] with the knots moving one step towards the one ahead, see `follow_signum`.
+pub fn part1_signum(input: &[Steps]) -> usize {
+    visited_by_tail(input, 2, follow_signum)
 }
 
-#[aoc(day9, part2)]
 pub fn part2(input: &[Steps]) -> usize {
+    visited_by_tail(input, 10, follow_table)
+}
+
+/// [`part2`] with the knots moving one step towards the one ahead, see `follow_signum`.
+pub fn part2_signum(input: &[Steps]) -> usize {
+    visited_by_tail(input, 10, follow_signum)
+}
+
+/// Number of positions visited by the tail of a rope of `knots` knots.
+fn visited_by_tail(input: &[Steps], knots: usize, follow: Follow) -> usize {
     let mut state = State {
-        knots: Vec::from([(0, 0); 10]),
+        knots: vec![(0, 0); knots],
+        follow,
         visited: HashMap::new(),
     };
     _ = state.visited.insert((0, 0), true);
     _ = state.handle_steps(input);
 
-    #[cfg(test)]
-    {
-        // pretty print
-        for x in 0..=5 {
-            for y in 0..=4 {
-                print!(
-                    "{}",
-                    match state.visited.get(&(x, y)) {
-                        Some(true) => "#",
-                        Some(false) | None => ".",
-                    }
-                )
-            }
-            println!("");
-        }
-        println!("");
-    }
-
     state.visited.into_iter().filter(|(_, v)| *v).count()
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Steps>> {
+fn parse(input: &str) -> IResult<'_, Vec<Steps>> {
     map(
         separated_list1(line_ending, separated_pair(one_of("UDLR"), space1, digit1)),
         |a| {
@@ -212,7 +214,7 @@ fn parse(input: &str) -> nom::IResult<&str, Vec<Steps>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use super::{input_generator, part1, part1_signum, part2, part2_signum};
 
     const INPUT: &str = "R 4
 U 4
@@ -225,11 +227,18 @@ R 2";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 13);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 13);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 1);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 1);
+    }
+
+    #[test]
+    fn signum() {
+        let input = input_generator(INPUT).unwrap();
+        assert_eq!(part1_signum(&input), 13);
+        assert_eq!(part2_signum(&input), 1);
     }
 }
@@ -1,8 +1,8 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::{parse::ParseError, Result};
 
 type T = u32;
 
-fn read_input(input: &str, include_words: bool) -> Vec<T> {
+fn read_input(input: &str, include_words: bool) -> Result<Vec<T>> {
     let numbers = [
         ("one", 1),
         ("two", 2),
@@ -17,7 +17,8 @@ fn read_input(input: &str, include_words: bool) -> Vec<T> {
 
     input
         .lines()
-        .map(|line| {
+        .enumerate()
+        .map(|(i, line)| {
             let mut found = vec![];
 
             for index in 0..line.len() {
@@ -41,29 +42,26 @@ fn read_input(input: &str, include_words: bool) -> Vec<T> {
                 }
             }
 
-            let x = found.first().unwrap();
-            let y = found.last().unwrap();
-            x * 10 + y
+            match (found.first(), found.last()) {
+                (Some(x), Some(y)) => Ok(x * 10 + y),
+                _ => Err(ParseError::new(i + 1, 1, "no digit in line").into()),
+            }
         })
         .collect()
 }
 
-#[aoc_generator(day1, part1)]
-pub fn input_generator1(input: &str) -> Vec<T> {
+pub fn input_generator1(input: &str) -> Result<Vec<T>> {
     read_input(input, false)
 }
 
-#[aoc_generator(day1, part2)]
-pub fn input_generator2(input: &str) -> Vec<T> {
+pub fn input_generator2(input: &str) -> Result<Vec<T>> {
     read_input(input, true)
 }
 
-#[aoc(day1, part1)]
 pub fn part1(input: &[T]) -> u32 {
     input.iter().sum()
 }
 
-#[aoc(day1, part2)]
 pub fn part2(input: &[T]) -> u32 {
     input.iter().sum()
 }
@@ -79,21 +77,24 @@ treb7uchet";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator1(INPUT)), 142);
+        assert_eq!(part1(&input_generator1(INPUT).unwrap()), 142);
     }
 
     #[test]
     fn test2() {
         assert_eq!(
-            part2(&input_generator2(
-                "two1nine
+            part2(
+                &input_generator2(
+                    "two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
 4nineeightseven2
 zoneight234
 7pqrstsixteen"
-            )),
+                )
+                .unwrap()
+            ),
             281
         );
     }
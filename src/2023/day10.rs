@@ -3,7 +3,7 @@ use std::{
     fmt::Display,
 };
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::{parse::ParseError, Error, Result};
 
 type T = (HashMap<Pos, Tile>, Pos);
 
@@ -59,9 +59,11 @@ pub enum Tile {
     Start, // S is the starting position of the animal; there is a pipe on this tile, but your sketch doesn't show what shape the pipe has.
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
-        match value {
+impl TryFrom<char> for Tile {
+    type Error = char;
+
+    fn try_from(value: char) -> std::result::Result<Self, char> {
+        Ok(match value {
             '|' => Tile::Vertical,
             '-' => Tile::Horizontal,
             'L' => Tile::NorthEast,
@@ -70,8 +72,8 @@ impl From<char> for Tile {
             'F' => Tile::SouthEast,
             '.' => Tile::Ground,
             'S' => Tile::Start,
-            c => unreachable!("character {c} is unknown"),
-        }
+            c => return Err(c),
+        })
     }
 }
 
@@ -91,43 +93,36 @@ impl Tile {
     }
 }
 
-#[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Vec<T> {
+pub fn input_generator(input: &str) -> Result<T> {
     let mut map = HashMap::new();
-    let mut start = Pos { x: 0, y: 0 };
-
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, c)| {
-            let tile = c.into();
-            match tile {
-                Tile::Start => {
-                    debug_assert_eq!(start, Pos { x: 0, y: 0 });
-                    start = Pos {
-                        x: x as i32,
-                        y: y as i32,
-                    };
-                }
-                _ => {}
+    let mut start = None;
+
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let tile = Tile::try_from(c)
+                .map_err(|c| ParseError::new(y + 1, x + 1, format!("unexpected `{c}`")))?;
+            let pos = Pos {
+                x: x as i32,
+                y: y as i32,
+            };
+            if tile == Tile::Start && start.replace(pos).is_some() {
+                return Err(ParseError::new(y + 1, x + 1, "second start `S`").into());
             }
-            map.insert(
-                Pos {
-                    x: x as i32,
-                    y: y as i32,
-                },
-                tile,
-            );
-        })
-    });
-
-    debug_assert_ne!(start, Pos { x: 0, y: 0 });
+            map.insert(pos, tile);
+        }
+    }
 
-    let (_, tile) = find_start_candidates(&map, &start);
-    map.entry(start).and_modify(|e| *e = tile);
+    let start = start.ok_or_else(|| Error::Parse("no start `S`".into()))?;
+    let (_, tile) = find_start_candidates(&map, &start).ok_or_else(|| {
+        let (line, column) = (start.y as usize + 1, start.x as usize + 1);
+        ParseError::new(line, column, "start `S` does not join two pipes")
+    })?;
+    map.insert(start, tile);
 
-    vec![(map, start)]
+    Ok((map, start))
 }
 
-fn find_start_candidates(map: &HashMap<Pos, Tile>, start: &Pos) -> (VecDeque<Pos>, Tile) {
+fn find_start_candidates(map: &HashMap<Pos, Tile>, start: &Pos) -> Option<(VecDeque<Pos>, Tile)> {
     let mut next_candidate = VecDeque::new();
 
     let mut north = false;
@@ -204,7 +199,9 @@ fn find_start_candidates(map: &HashMap<Pos, Tile>, start: &Pos) -> (VecDeque<Pos
         }
     }
 
-    assert_eq!(next_candidate.len(), 2);
+    if next_candidate.len() != 2 {
+        return None;
+    }
 
     let tile = {
         match (north, east, south, west) {
@@ -221,17 +218,17 @@ fn find_start_candidates(map: &HashMap<Pos, Tile>, start: &Pos) -> (VecDeque<Pos
         }
     };
 
-    (next_candidate, tile)
+    Some((next_candidate, tile))
 }
 
 fn traverse_map(map: &HashMap<Pos, Tile>, start: &Pos) -> HashSet<Pos> {
     // BFS or DFS? -> judging by reddit, it's probably one giant loop -> DFS
-    let (mut next_candidate, _) = find_start_candidates(map, start);
+    let (mut next_candidate, _) = find_start_candidates(map, start).expect("checked when parsing");
     let mut last = *start;
     let mut visited: HashSet<Pos> = HashSet::new();
 
     while let Some(current) = next_candidate.pop_back() {
-        assert_eq!(visited.insert(current), true);
+        assert!(visited.insert(current));
 
         if &current == start {
             return visited;
@@ -258,12 +255,9 @@ fn traverse_map(map: &HashMap<Pos, Tile>, start: &Pos) -> HashSet<Pos> {
     unreachable!()
 }
 
-#[aoc(day10, part1)]
-pub fn part1(input: &[T]) -> u32 {
-    let (map, start) = input.first().unwrap();
-
+pub fn part1((map, start): &T) -> u32 {
     let visited = traverse_map(map, start);
-    ((visited.len() + 1) / 2) as u32
+    visited.len().div_ceil(2) as u32
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -302,15 +296,12 @@ impl State {
             State::OnLine(expected, true) if expected != tile => State::Inside,
             State::OnLine(expected, false) if expected != tile => State::Outside,
 
-            _ => unreachable!()
+            _ => unreachable!(),
         }
     }
 }
 
-#[aoc(day10, part2)]
-pub fn part2(input: &[T]) -> u32 {
-    let (map, start) = input.first().unwrap();
-
+pub fn part2((map, start): &T) -> u32 {
     let visited = traverse_map(map, start);
 
     let mut x = 0;
@@ -386,12 +377,12 @@ L|-JF";
 
     #[test]
     fn test11() {
-        assert_eq!(part1(&input_generator(INPUT2)), 4);
+        assert_eq!(part1(&input_generator(INPUT2).unwrap()), 4);
     }
 
     #[test]
     fn test12() {
-        assert_eq!(part1(&input_generator(INPUT)), 8);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 8);
     }
 
     const INPUT3: &str = "...........
@@ -406,7 +397,7 @@ L|-JF";
 
     #[test]
     fn test21() {
-        assert_eq!(part2(&input_generator(INPUT3)), 4);
+        assert_eq!(part2(&input_generator(INPUT3).unwrap()), 4);
     }
 
     const INPUT4: &str = ".F----7F7F7F7F-7....
@@ -422,7 +413,7 @@ L--J.L7...LJS7F-7L7.
 
     #[test]
     fn test22() {
-        assert_eq!(part2(&input_generator(INPUT4)), 8);
+        assert_eq!(part2(&input_generator(INPUT4).unwrap()), 8);
     }
 
     const INPUT5: &str = "FF7FSF7F7F7F7F7F---7
@@ -438,6 +429,27 @@ L7JLJL-JLJLJL--JLJ.L";
 
     #[test]
     fn test23() {
-        assert_eq!(part2(&input_generator(INPUT5)), 10);
+        assert_eq!(part2(&input_generator(INPUT5).unwrap()), 10);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator(".S-7\n.|x|\n.L-J").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 3: unexpected `x`"
+        );
+        let err = input_generator(".F-7\n.|.|\n.L-J").unwrap_err();
+        assert_eq!(err.to_string(), "invalid input: no start `S`");
+        let err = input_generator(".S-7\n.|.|\n.L-S").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 4: second start `S`"
+        );
+        let err = input_generator(".S.7\n.|.|\n.L-J").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 2: start `S` does not join two pipes"
+        );
     }
 }
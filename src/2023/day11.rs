@@ -1,8 +1,9 @@
 use std::fmt::Display;
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 
+use crate::{grid::Grid, Result};
+
 // (0,0) is top left
 #[derive(Debug, Eq, Hash, PartialEq, Clone, Copy)]
 pub struct Pos {
@@ -16,6 +17,7 @@ impl Display for Pos {
     }
 }
 
+#[derive(Debug)]
 pub struct Input {
     unexpanded: Vec<Pos>,
     max_x: usize,
@@ -23,37 +25,27 @@ pub struct Input {
 }
 
 type T = Input;
-#[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    let unexpanded: Vec<_> = input
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .filter_map(|(x, c)| {
-                    if c == '#' {
-                        Some(Pos {
-                            x: x as i64,
-                            y: y as i64,
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    let image = Grid::parse(input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+
+    let unexpanded = image
+        .iter()
+        .filter(|&(_, &galaxy)| galaxy)
+        .map(|(pos, _)| Pos {
+            x: pos.x as i64,
+            y: pos.y as i64,
         })
-        .flatten()
         .collect();
 
-    let max_y = input.lines().count();
-    let max_x = input.lines().next().unwrap().len();
-
-    vec![Input {
+    Ok(vec![Input {
         unexpanded,
-        max_x,
-        max_y,
-    }]
+        max_x: image.width(),
+        max_y: image.height(),
+    }])
 }
 
 fn expand(input: &[T], factor: i32) -> Vec<Pos> {
@@ -65,11 +57,9 @@ fn expand(input: &[T], factor: i32) -> Vec<Pos> {
     } = input;
 
     let empty_rows: Vec<_> = (0..*max_y)
-        .into_iter()
         .filter(|y| !unexpanded.iter().any(|pos| pos.y == *y as i64))
         .collect();
     let empty_columns: Vec<_> = (0..*max_x)
-        .into_iter()
         .filter(|x| !unexpanded.iter().any(|pos| pos.x == *x as i64))
         .collect();
 
@@ -111,7 +101,6 @@ fn expand(input: &[T], factor: i32) -> Vec<Pos> {
     result
 }
 
-#[aoc(day11, part1)]
 pub fn part1(input: &[T]) -> u64 {
     let map = expand(input, 2);
 
@@ -126,7 +115,6 @@ pub fn part1(input: &[T]) -> u64 {
     result / 2
 }
 
-#[aoc(day11, part2)]
 pub fn part2(input: &[T]) -> u64 {
     let map = expand(input, 1000000);
 
@@ -159,11 +147,20 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 374);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 374);
     }
 
     // #[test]
     // fn test2() {
-    //     assert_eq!(part2(&input_generator(INPUT)), 0);
+    //     assert_eq!(part2(&input_generator(INPUT).unwrap()), 0);
     // }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("...#\n.#..\n..x.").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 3: unexpected `x`"
+        );
+    }
 }
@@ -1,6 +1,22 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use nom::{
+    branch::alt,
+    character::complete::{char, space1},
+    combinator::value,
+    error::context,
+    multi::many1,
+    sequence::separated_pair,
+};
+
+use crate::{
+    parallel::par_iter,
+    parse::{int, lines, list, IResult},
+    Result,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub enum State {
@@ -11,27 +27,21 @@ pub enum State {
 
 type T = (Vec<State>, Vec<usize>);
 
-#[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    input
-        .lines()
-        .map(|line| {
-            let (springs, groups) = line.split_once(' ').unwrap();
-
-            (
-                springs
-                    .chars()
-                    .map(|c| match c {
-                        '.' => State::Operational,
-                        '#' => State::Broken,
-                        '?' => State::Unknown,
-                        _ => unreachable!(),
-                    })
-                    .collect(),
-                groups.split(',').filter_map(|g| g.parse().ok()).collect(),
-            )
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    lines(input, parse_record)
+}
+
+fn parse_record(input: &str) -> IResult<'_, T> {
+    let state = alt((
+        value(State::Operational, char('.')),
+        value(State::Broken, char('#')),
+        value(State::Unknown, char('?')),
+    ));
+    separated_pair(
+        context("springs", many1(state)),
+        space1,
+        list(char(','), int),
+    )(input)
 }
 
 fn possible_ways(
@@ -82,25 +92,17 @@ fn possible_ways(
     ways
 }
 
-#[aoc(day12, part1)]
 pub fn part1(input: &[T]) -> u64 {
-    let mut history = HashMap::new();
-
-    input
-        .iter()
+    par_iter(input)
         .map(|(springs, groups)| {
-            history.clear();
+            let mut history = HashMap::new();
             possible_ways(&mut history, springs, None, groups)
         })
         .sum()
 }
 
-#[aoc(day12, part2)]
 pub fn part2(input: &[T]) -> u64 {
-    let mut history = HashMap::new();
-
-    input
-        .iter()
+    par_iter(input)
         .map(|(springs, groups)| {
             // can we do better/nicer?!
             let new_springs = (0..5).map(|_| springs).fold(vec![], |acc, cur| {
@@ -113,7 +115,7 @@ pub fn part2(input: &[T]) -> u64 {
             });
             let new_groups = (0..5).flat_map(|_| groups).copied().collect::<Vec<_>>();
 
-            history.clear();
+            let mut history = HashMap::new();
             possible_ways(&mut history, &new_springs, None, &new_groups)
         })
         .sum()
@@ -132,12 +134,21 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 21);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 21);
     }
 
     #[test]
     fn test2() {
         // 3350142103 too low
-        assert_eq!(part2(&input_generator(INPUT)), 525152);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 525152);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("???.### 1,1,3\n.??..??...?##. 1,x,3").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 17: unexpected trailing input"
+        );
     }
 }
@@ -1,6 +1,10 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use tracing::info;
 
+use crate::{
+    parse::{blocks, finish, grid},
+    Result,
+};
+
 #[derive(Debug)]
 pub struct Input {
     rows: Vec<String>,
@@ -10,32 +14,23 @@ pub struct Input {
 
 type T = Input;
 
-#[aoc_generator(day13)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> Vec<T> {
-    input
-        .split("\n\n")
-        .map(|block| {
-            let rows: Vec<String> = block.lines().map(|line| line.to_string()).collect();
-            let x = rows.first().unwrap().len();
-            let y = rows.len();
-
-            let mut columns = vec![];
-            for idx in 0..x {
-                let mut column = String::new();
-                for row in &rows {
-                    column += &row.chars().nth(idx).unwrap().to_string();
-                }
-                columns.push(column);
-            }
-
-            Input {
-                rows,
-                columns,
-                size: (x, y),
-            }
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    let patterns = finish(
+        input,
+        blocks(grid(|c| matches!(c, '#' | '.').then_some(c)))(input),
+    )?;
+
+    Ok(patterns
+        .into_iter()
+        .map(|pattern| Input {
+            rows: pattern.rows().map(|row| row.iter().collect()).collect(),
+            columns: (0..pattern.width())
+                .map(|x| pattern.column(x).collect())
+                .collect(),
+            size: (pattern.width(), pattern.height()),
         })
-        .collect()
+        .collect())
 }
 
 #[tracing::instrument]
@@ -58,7 +53,7 @@ fn locate_potential_smudge(a: &str, b: &str) -> Option<usize> {
 fn valid_mirror(
     range: usize,
     mirror_pos: usize,
-    data: &Vec<String>,
+    data: &[String],
 ) -> (bool, Option<(usize, usize)>) {
     let mut valid = None;
     let mut smudge = None;
@@ -110,7 +105,6 @@ fn valid_mirror(
     (valid.unwrap_or_default(), smudge)
 }
 
-#[aoc(day13, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &[T]) -> u32 {
     input
@@ -137,19 +131,18 @@ pub fn part1(input: &[T]) -> u32 {
         .sum()
 }
 
-#[aoc(day13, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &[T]) -> u32 {
     let clone_and_replace = |input: &Vec<String>, pos_a: usize, pos_b: usize| {
-        let old = input.iter().nth(pos_a).unwrap().chars().nth(pos_b).unwrap();
+        let old = input.get(pos_a).unwrap().chars().nth(pos_b).unwrap();
         let new = match old {
             '.' => '#',
             '#' => '.',
             _ => unreachable!(),
         };
-        let old_value = input.iter().nth(pos_a).unwrap();
+        let old_value = input.get(pos_a).unwrap();
         let mut new_values = input.to_owned();
-        *new_values.iter_mut().nth(pos_a).unwrap() = old_value
+        *new_values.get_mut(pos_a).unwrap() = old_value
             .chars()
             .enumerate()
             .map(|(idx, c)| if idx == pos_b { new } else { c })
@@ -218,11 +211,11 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 405);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 405);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 400);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 400);
     }
 }
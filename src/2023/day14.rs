@@ -1,178 +1,83 @@
-use aoc_runner_derive::{aoc, aoc_generator};
-use fxhash::FxHashMap;
 use glam::IVec2;
-use itertools::Itertools;
 use tracing::info;
 
-// true == O
-// false == #
-// not found == .
-type T = (FxHashMap<IVec2, bool>, IVec2);
+use crate::{cycle::History, grid::Grid, Result};
 
-#[aoc_generator(day14)]
-#[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
-    let y = input.lines().count() as i32;
-    let x = input.lines().next().unwrap().len() as i32;
+// 'O' round rock, '#' cube rock, '.' empty
+type T = Grid<char>;
 
-    (
-        input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.char_indices().filter_map(move |(x, c)| match c {
-                    '.' => None,
-                    'O' => Some((
-                        IVec2 {
-                            x: x as i32,
-                            y: y as i32,
-                        },
-                        true,
-                    )),
-                    '#' => Some((
-                        IVec2 {
-                            x: x as i32,
-                            y: y as i32,
-                        },
-                        false,
-                    )),
-                    _ => unreachable!(),
-                })
-            })
-            .collect(),
-        IVec2 { x, y },
-    )
+#[tracing::instrument(skip(input))]
+pub fn input_generator(input: &str) -> Result<T> {
+    Grid::parse(input, |c| matches!(c, 'O' | '#' | '.').then_some(c))
 }
 
 #[tracing::instrument(skip(input))]
-fn tilt(input: &mut FxHashMap<IVec2, bool>, dim: &IVec2, dir: &IVec2) {
-    // all keys in the correct order
-    let keys: Vec<_> = {
-        match dir {
-            IVec2 { x, y } if *x == 1 && *y == 0 => {
-                ((0..dim.x).rev()).cartesian_product(0..dim.y).collect()
-            }
-            IVec2 { x, y } if *x == 0 && *y == 1 => {
-                ((0..dim.y).rev()).cartesian_product(0..dim.x).collect()
-            }
-            IVec2 { x, y } if *x == -1 && *y == 0 => {
-                (0..dim.x).cartesian_product(0..dim.y).collect()
-            }
-            IVec2 { x, y } if *x == 0 && *y == -1 => {
-                (0..dim.y).cartesian_product(0..dim.x).collect()
-            }
-            _ => unreachable!(),
-        }
-    };
-
-    for key in keys {
-        let pos = match dir {
-            IVec2 { x, y } if *x == 1 && *y == 0 => IVec2 { x: key.0, y: key.1 },
-            IVec2 { x, y } if *x == 0 && *y == 1 => IVec2 { x: key.1, y: key.0 },
-            IVec2 { x, y } if *x == -1 && *y == 0 => IVec2 { x: key.0, y: key.1 },
-            IVec2 { x, y } if *x == 0 && *y == -1 => IVec2 { x: key.1, y: key.0 },
-            _ => unreachable!(),
-        };
-
-        match input.get(&pos) {
-            None | Some(false) => continue,
-            Some(true) => {}
-        }
-
-        // we have a round rock at `key`
-        let mut test_pos = pos + *dir;
-        while !(test_pos.x < 0 || test_pos.y < 0 || test_pos.x >= dim.x || test_pos.y >= dim.y) {
-            match input.get(&test_pos) {
-                None => {}
-                Some(_) => break,
-            }
+fn tilt(input: &mut Grid<char>, dir: IVec2) {
+    // visit the rocks closest to the edge `dir` points to first
+    let mut positions: Vec<_> = input.positions().collect();
+    if dir.x + dir.y > 0 {
+        positions.reverse();
+    }
 
-            test_pos += *dir;
+    for pos in positions {
+        if input[pos] != 'O' {
+            continue;
         }
 
-        // correct off by one
-        test_pos -= *dir;
-
-        if test_pos == pos {
-            continue;
+        // we have a round rock at `pos`
+        let mut target = pos;
+        while input.get(target + dir) == Some(&'.') {
+            target += dir;
         }
-        // remove old entry
-        assert_eq!(input.remove(&pos), Some(true));
-        assert_eq!(input.insert(test_pos, true), None);
+        input.swap(pos, target);
     }
 }
 
 #[tracing::instrument(skip(map))]
-fn score_map(map: &FxHashMap<IVec2, bool>, hight: i32) -> u32 {
+fn score_map(map: &Grid<char>) -> u32 {
     map.iter()
-        .filter_map(|(pos, val)| if !val { None } else { Some(hight - pos.y) })
-        .sum::<i32>() as u32
+        .filter(|&(_, &c)| c == 'O')
+        .map(|(pos, _)| map.height() as u32 - pos.y as u32)
+        .sum()
+}
+
+fn spin(map: &mut Grid<char>) {
+    tilt(map, IVec2::NEG_Y);
+    tilt(map, IVec2::NEG_X);
+    tilt(map, IVec2::Y);
+    tilt(map, IVec2::X);
 }
 
-#[aoc(day14, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> u32 {
-    let mut map = input.0.to_owned();
-    tilt(&mut map, &input.1, &IVec2 { x: 0, y: -1 });
+    let mut map = input.to_owned();
+    tilt(&mut map, IVec2::NEG_Y);
 
-    score_map(&map, input.1.y)
+    score_map(&map)
 }
 
-#[aoc(day14, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &T) -> u32 {
-    let mut map = input.0.to_owned();
-
-    let map_to_key = |map: &FxHashMap<IVec2, bool>| -> String {
-        let mut output = String::new();
-        for y in 0..input.1.y {
-            for x in 0..input.1.x {
-                output.push(match map.get(&IVec2 { x, y }) {
-                    None => '.',
-                    Some(true) => 'O',
-                    Some(false) => '#',
-                });
-            }
-        }
-        output
-    };
+    let mut map = input.to_owned();
 
     let max_rounds = 1_000_000_000;
-    let mut history: FxHashMap<String, (usize, u32)> = FxHashMap::default();
-    for idx in 0..max_rounds {
-        // spin!
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: -1 });
-        tilt(&mut map, &input.1, &IVec2 { x: -1, y: 0 });
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: 1 });
-        tilt(&mut map, &input.1, &IVec2 { x: 1, y: 0 });
-
-        // insert and check for loop
-        if let Some((idx_start, _)) =
-            history.insert(map_to_key(&map), (idx, score_map(&map, input.1.y)))
-        {
-            // found loop
-            let len = idx - idx_start;
-            let target_idx = idx_start + (max_rounds - idx_start) % len - 1;
-
-            info!("found loop at {idx}, starting at {idx_start}, len {len}");
-
-            return history
-                .iter()
-                .find(|(_, (idx, _))| target_idx == *idx)
-                .unwrap()
-                .1
-                 .1;
+    let mut history = History::new();
+    history.push(map.clone(), score_map(&map));
+    loop {
+        spin(&mut map);
+        if let Some(cycle) = history.push(map.clone(), score_map(&map)) {
+            info!(
+                "found loop of {} spins after {}",
+                cycle.period, cycle.offset
+            );
+            return *history.value_at(cycle, max_rounds);
         }
     }
-
-    unreachable!()
 }
 
 #[cfg(test)]
 mod tests {
-    use glam::IVec2;
-
-    use super::{input_generator, part1, part2, tilt};
+    use super::{input_generator, part1, part2, spin};
 
     const INPUT: &str = "O....#....
 O.OO#....#
@@ -187,17 +92,17 @@ O.#..O.#.#
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 136);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 136);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 64);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 64);
     }
 
     #[test_log::test]
     fn test3() {
-        let (map1, _) = input_generator(
+        let map1 = input_generator(
             ".....#....
 ....#...O#
 ...OO##...
@@ -208,8 +113,9 @@ O.#..O.#.#
 ......OOOO
 #...O###..
 #..OO#....",
-        );
-        let (map2, _) = input_generator(
+        )
+        .unwrap();
+        let map2 = input_generator(
             ".....#....
 ....#...O#
 .....##...
@@ -220,8 +126,9 @@ O.#..O.#.#
 .......OOO
 #..OO###..
 #.OOO#...O",
-        );
-        let (map3, _) = input_generator(
+        )
+        .unwrap();
+        let map3 = input_generator(
             ".....#....
 ....#...O#
 .....##...
@@ -232,27 +139,18 @@ O.#..O.#.#
 .......OOO
 #...O###.O
 #.OOO#...O",
-        );
+        )
+        .unwrap();
 
-        let input = input_generator(INPUT);
-        let mut map = input.0.to_owned();
+        let mut map = input_generator(INPUT).unwrap();
 
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: -1 });
-        tilt(&mut map, &input.1, &IVec2 { x: -1, y: 0 });
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: 1 });
-        tilt(&mut map, &input.1, &IVec2 { x: 1, y: 0 });
+        spin(&mut map);
         assert_eq!(map, map1);
 
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: -1 });
-        tilt(&mut map, &input.1, &IVec2 { x: -1, y: 0 });
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: 1 });
-        tilt(&mut map, &input.1, &IVec2 { x: 1, y: 0 });
+        spin(&mut map);
         assert_eq!(map, map2);
 
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: -1 });
-        tilt(&mut map, &input.1, &IVec2 { x: -1, y: 0 });
-        tilt(&mut map, &input.1, &IVec2 { x: 0, y: 1 });
-        tilt(&mut map, &input.1, &IVec2 { x: 1, y: 0 });
+        spin(&mut map);
         assert_eq!(map, map3);
     }
 }
@@ -1,11 +1,10 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::Result;
 
 type T = Vec<String>;
 
-#[aoc_generator(day15)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
-    input.split(',').map(|s| s.to_string()).collect()
+pub fn input_generator(input: &str) -> Result<T> {
+    Ok(input.split(',').map(|s| s.to_string()).collect())
 }
 
 #[tracing::instrument]
@@ -19,7 +18,6 @@ fn hash(data: &str) -> u32 {
     state
 }
 
-#[aoc(day15, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> u32 {
     input.iter().map(|s| hash(s)).sum()
@@ -36,7 +34,6 @@ pub enum Op {
     Insert(u32),
 }
 
-#[aoc(day15, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &T) -> u32 {
     let mut boxes = (0..256).map(|_| Vec::<Lens>::default()).collect::<Vec<_>>();
@@ -92,12 +89,12 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 1320);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 1320);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 145);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 145);
     }
 
     #[test_log::test]
@@ -1,8 +1,12 @@
-use aoc_runner_derive::{aoc, aoc_generator};
-use fxhash::{FxHashMap, FxHashSet};
+use fxhash::FxHashSet;
 use glam::IVec2;
 use itertools::Itertools;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{grid::Grid, parallel::par_iter, Result};
+
 pub enum Tile {
     SplitterVertical,   // |
     SplitterHorizontal, // -
@@ -10,37 +14,19 @@ pub enum Tile {
     MirrorRight,        // /
 }
 
-type T = (FxHashMap<IVec2, Tile>, IVec2);
+// `None` is empty space
+type T = Grid<Option<Tile>>;
 
-#[aoc_generator(day16)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
-    let y = input.lines().count() as i32;
-    let x = input.lines().next().unwrap().len() as i32;
-
-    (
-        input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.char_indices().filter_map(move |(x, c)| {
-                    let pos = IVec2 {
-                        x: x as i32,
-                        y: y as i32,
-                    };
-                    match c {
-                        '|' => Some((pos, Tile::SplitterVertical)),
-                        '-' => Some((pos, Tile::SplitterHorizontal)),
-                        '\\' => Some((pos, Tile::MirrorLeft)),
-                        '/' => Some((pos, Tile::MirrorRight)),
-                        '.' => None,
-                        _ => unreachable!(),
-                    }
-                })
-            })
-            .collect(),
-        IVec2 { x, y },
-    )
+pub fn input_generator(input: &str) -> Result<T> {
+    Grid::parse(input, |c| match c {
+        '|' => Some(Some(Tile::SplitterVertical)),
+        '-' => Some(Some(Tile::SplitterHorizontal)),
+        '\\' => Some(Some(Tile::MirrorLeft)),
+        '/' => Some(Some(Tile::MirrorRight)),
+        '.' => Some(None),
+        _ => None,
+    })
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -49,7 +35,7 @@ pub struct Beam {
     dir: IVec2,
 }
 
-fn process_beam(map: &FxHashMap<IVec2, Tile>, dim: IVec2, start_beam: Beam) -> u32 {
+fn process_beam(map: &T, start_beam: Beam) -> u32 {
     let mut energized = FxHashSet::default();
     let mut beams = vec![start_beam];
 
@@ -60,13 +46,13 @@ fn process_beam(map: &FxHashMap<IVec2, Tile>, dim: IVec2, start_beam: Beam) -> u
                 break;
             }
             // left map?
-            if beam.pos.x < 0 || beam.pos.y < 0 || beam.pos.x >= dim.x || beam.pos.y >= dim.y {
+            let Some(tile) = map.get(beam.pos) else {
                 break;
-            }
+            };
 
             energized.insert(beam);
 
-            match map.get(&beam.pos) {
+            match tile {
                 None => beam.pos += beam.dir,
                 Some(tile) => match (tile, beam.dir) {
                     (Tile::MirrorLeft, IVec2 { x, y }) => {
@@ -114,24 +100,18 @@ fn process_beam(map: &FxHashMap<IVec2, Tile>, dim: IVec2, start_beam: Beam) -> u
     energized.iter().unique_by(|b| b.pos).count() as u32
 }
 
-#[aoc(day16, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> u32 {
     let start_beam = Beam {
         pos: IVec2 { x: 0, y: 0 },
         dir: IVec2::X,
     };
-    let map = &input.0;
-    let dim = input.1;
-
-    process_beam(map, dim, start_beam)
+    process_beam(input, start_beam)
 }
 
-#[aoc(day16, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &T) -> u32 {
-    let map = &input.0;
-    let dim = input.1;
+    let dim = input.dim();
 
     let start_positions = []
         .into_iter()
@@ -150,10 +130,11 @@ pub fn part2(input: &T) -> u32 {
         .chain((0..dim.y).map(|y| Beam {
             pos: IVec2 { x: dim.x - 1, y },
             dir: IVec2::NEG_X,
-        }));
+        }))
+        .collect::<Vec<_>>();
 
-    start_positions
-        .map(|start_beam| process_beam(map, dim, start_beam))
+    par_iter(start_positions)
+        .map(|start_beam| process_beam(input, start_beam))
         .max()
         .unwrap()
 }
@@ -175,11 +156,11 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 46);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 46);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 51);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 51);
     }
 }
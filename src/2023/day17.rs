@@ -1,59 +1,22 @@
-use std::collections::BTreeSet;
-
-use aoc_runner_derive::{aoc, aoc_generator};
-use fxhash::{FxHashMap, FxHashSet};
 use glam::IVec2;
 
-type T = (FxHashMap<IVec2, u32>, IVec2);
+use crate::{grid::Grid, search::dijkstra, Result};
 
-#[aoc_generator(day17)]
-#[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
-    let y = input.lines().count() as i32;
-    let x = input.lines().next().unwrap().len() as i32;
+type T = Grid<u32>;
 
-    (
-        input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.char_indices().map(move |(x, c)| {
-                    (
-                        IVec2 {
-                            x: x as i32,
-                            y: y as i32,
-                        },
-                        c.to_digit(10).unwrap(),
-                    )
-                })
-            })
-            .collect(),
-        IVec2 { x, y },
-    )
+#[tracing::instrument(skip(input))]
+pub fn input_generator(input: &str) -> Result<T> {
+    Grid::parse(input, |c| c.to_digit(10))
 }
 
 #[tracing::instrument(skip(map))]
-fn find_path(map: &FxHashMap<IVec2, u32>, dim: &IVec2, part2: bool) -> u32 {
+fn find_path(map: &Grid<u32>, part2: bool) -> u32 {
     let pos_start = IVec2::ZERO;
-    let pos_end = *dim - IVec2::ONE;
-
-    // heat_loss, position, direction (we went to or come from, doesn't really matter)
-    let mut candidates: BTreeSet<(u32, [i32; 2], [i32; 2])> = BTreeSet::default();
-    // keep track of direction as well!!
-    let mut seen: FxHashSet<(IVec2, IVec2)> = FxHashSet::default();
-
-    candidates.insert((0, pos_start.to_array(), [0, 0]));
-    while let Some((heat_loss, pos, dir)) = candidates.pop_first() {
-        let pos: IVec2 = pos.into();
-        let dir: IVec2 = dir.into();
-
-        if pos == pos_end {
-            return heat_loss;
-        }
-        if !seen.insert((pos, dir)) {
-            continue;
-        }
+    let pos_end = map.dim() - IVec2::ONE;
+    let (min_step, max_step) = if part2 { (4, 10) } else { (1, 3) };
 
+    // position, direction (we went to or come from, doesn't really matter)
+    let neighbours = |&(pos, dir): &(IVec2, IVec2)| {
         let dir_options = match dir {
             IVec2::X | IVec2::NEG_X => [IVec2::Y, IVec2::NEG_Y],
             IVec2::Y | IVec2::NEG_Y => [IVec2::X, IVec2::NEG_X],
@@ -63,60 +26,36 @@ fn find_path(map: &FxHashMap<IVec2, u32>, dim: &IVec2, part2: bool) -> u32 {
 
             _ => unreachable!(),
         };
-        for dir_new in dir_options {
-            let mut hl = heat_loss;
-            let mut new_pos = pos;
 
-            if part2 {
-                const MIN_STEP: i32 = 4;
-                const MAX_STEP: i32 = 10;
-                // start at `1` and go to (inclusive) `10`
-                for step in 1..=MAX_STEP {
-                    new_pos += dir_new;
-
-                    if let Some(tile) = map.get(&new_pos) {
-                        hl += tile;
-                        if step >= MIN_STEP {
-                            candidates.insert((hl, new_pos.to_array(), dir_new.to_array()));
-                        }
-                    } else {
-                        break;
-                    }
-                }
-            } else {
-                for _ in 0..3 {
-                    new_pos += dir_new;
-
-                    if let Some(tile) = map.get(&new_pos) {
-                        hl += tile;
-                        candidates.insert((hl, new_pos.to_array(), dir_new.to_array()));
-                    } else {
-                        break;
-                    }
+        let mut next = vec![];
+        for dir_new in dir_options {
+            let mut hl = 0;
+            // start at `1` and go to (inclusive) `max_step`
+            for (step, new_pos) in (1..=max_step).zip(map.ray(pos, dir_new)) {
+                hl += map[new_pos];
+                if step >= min_step {
+                    next.push(((new_pos, dir_new), hl));
                 }
             }
         }
-    }
-
-    unreachable!()
+        next
+    };
+
+    dijkstra((pos_start, IVec2::ZERO), neighbours, |&(pos, _)| {
+        pos == pos_end
+    })
+    .unwrap()
+    .cost
 }
 
-#[aoc(day17, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> u32 {
-    let map = &input.0;
-    let dim = input.1;
-
-    find_path(map, &dim, false)
+    find_path(input, false)
 }
 
-#[aoc(day17, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &T) -> u32 {
-    let map = &input.0;
-    let dim = input.1;
-
-    find_path(map, &dim, true)
+    find_path(input, true)
 }
 
 #[cfg(test)]
@@ -139,11 +78,11 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 102);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 102);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 94);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 94);
     }
 }
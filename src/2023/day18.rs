@@ -1,6 +1,19 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use glam::I64Vec2;
 
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::char,
+    combinator::{map, map_res, value},
+    error::context,
+    sequence::{delimited, pair, tuple},
+};
+
+use crate::{
+    parse::{int, lines, ws, IResult},
+    Result,
+};
+
 #[derive(Debug)]
 pub struct Instruction {
     dir: I64Vec2,
@@ -11,43 +24,40 @@ pub struct Instruction {
 
 type T = Instruction;
 
-#[aoc_generator(day18)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> Vec<T> {
-    input
-        .lines()
-        .map(|line| {
-            let mut it = line.split_ascii_whitespace();
-            let dir = match it.next().unwrap() {
-                "U" => I64Vec2::NEG_Y,
-                "D" => I64Vec2::Y,
-                "L" => I64Vec2::NEG_X,
-                "R" => I64Vec2::X,
-                _ => unreachable!(),
-            };
-            let count = it.next().unwrap().parse().unwrap();
-            let color = it.next().unwrap();
-
-            let (dist, c) = color[2..color.len() - 1].split_at(5);
-            let dist2 = i64::from_str_radix(&dist, 16).unwrap();
-            let dir2 = match c {
-                "0" => I64Vec2::X,
-                "1" => I64Vec2::Y,
-                "2" => I64Vec2::NEG_X,
-                "3" => I64Vec2::NEG_Y,
-                _ => unreachable!(),
-            };
-
-            Instruction {
-                dir,
-                count,
-                part2: (dir2, dist2),
-            }
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    lines(input, parse_instruction)
+}
+
+/// `R 6 (#70c710)`
+fn parse_instruction(input: &str) -> IResult<'_, Instruction> {
+    let dir = alt((
+        value(I64Vec2::NEG_Y, char('U')),
+        value(I64Vec2::Y, char('D')),
+        value(I64Vec2::NEG_X, char('L')),
+        value(I64Vec2::X, char('R')),
+    ));
+    let dist2 = map_res(take_while_m_n(5, 5, |c: char| c.is_ascii_hexdigit()), |d| {
+        i64::from_str_radix(d, 16)
+    });
+    let dir2 = alt((
+        value(I64Vec2::X, char('0')),
+        value(I64Vec2::Y, char('1')),
+        value(I64Vec2::NEG_X, char('2')),
+        value(I64Vec2::NEG_Y, char('3')),
+    ));
+    let color = context("color", delimited(tag("(#"), pair(dist2, dir2), char(')')));
+    map(
+        tuple((context("direction", dir), ws(int), color)),
+        |(dir, count, (dist2, dir2))| Instruction {
+            dir,
+            count,
+            part2: (dir2, dist2),
+        },
+    )(input)
 }
 
-fn shoe_lace(lines: &Vec<I64Vec2>) -> i64 {
+fn shoe_lace(lines: &[I64Vec2]) -> i64 {
     let mut ret: i64 = 0;
 
     for lines in lines.windows(2) {
@@ -60,7 +70,6 @@ fn shoe_lace(lines: &Vec<I64Vec2>) -> i64 {
     ret.abs() / 2
 }
 
-#[aoc(day18, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &[T]) -> i64 {
     let mut border = 0;
@@ -77,7 +86,6 @@ pub fn part1(input: &[T]) -> i64 {
     shoe_lace(&lines) + border / 2 + 1
 }
 
-#[aoc(day18, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &[T]) -> i64 {
     let mut border = 0;
@@ -115,11 +123,20 @@ U 2 (#7a21e3)";
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 62);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 62);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 952408144115);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 952408144115);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("R 6 (#70c710)\nD 5 (#0dc575)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 12: expected `3` (in color)"
+        );
     }
 }
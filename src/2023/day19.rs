@@ -1,7 +1,20 @@
 use std::{collections::hash_map::Entry, ops::RangeInclusive};
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::FxHashMap;
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alpha1, char, line_ending, one_of},
+    combinator::map,
+    error::context,
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, separated_pair, tuple},
+};
+
+use crate::{
+    parse::{blank_line, finish, int, list, IResult},
+    Result,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Field {
@@ -23,12 +36,6 @@ impl From<char> for Field {
     }
 }
 
-impl From<&str> for Field {
-    fn from(value: &str) -> Self {
-        value.chars().next().unwrap().into()
-    }
-}
-
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Action {
     Accept,
@@ -84,67 +91,73 @@ pub struct Input {
 
 type T = Input;
 
-#[tracing::instrument]
-fn parse_rule(rule: &str) -> Rule {
-    if rule.contains(':') {
-        let field = rule[0..1].into();
-        let greater = match &rule[1..2] {
-            ">" => true,
-            "<" => false,
-            _ => unreachable!(),
-        };
-
-        let (value, action) = rule[2..].split_once(':').unwrap();
-
-        Rule {
-            valid: true,
-            field,
-            action: action.into(),
-            value: value.parse().unwrap(),
-            greater,
-        }
-    } else {
-        Rule {
+fn parse_rule(input: &str) -> IResult<'_, Rule> {
+    alt((
+        map(
+            tuple((
+                one_of("xmas"),
+                one_of("<>"),
+                int,
+                preceded(char(':'), alpha1),
+            )),
+            |(field, op, value, action)| Rule {
+                valid: true,
+                field: field.into(),
+                action: action.into(),
+                value,
+                greater: op == '>',
+            },
+        ),
+        map(alpha1, |action: &str| Rule {
             valid: false,
-            action: rule.into(),
+            action: action.into(),
             value: 0,
             greater: false,
             field: Field::A, // doesn't matter
-        }
-    }
+        }),
+    ))(input)
 }
 
-#[tracing::instrument]
-fn parse_part(part: &str) -> Part {
-    let mut it = part.split(',');
-    let x = it.next().unwrap()[3..].parse().unwrap();
-    let m = it.next().unwrap()[2..].parse().unwrap();
-    let a = it.next().unwrap()[2..].parse().unwrap();
-    let s = &it.next().unwrap()[2..];
-    let s = s[..s.len() - 1].parse().unwrap();
-    Part { x, m, a, s }
+fn parse_workflow(input: &str) -> IResult<'_, (String, Vec<Rule>)> {
+    pair(
+        map(alpha1, str::to_owned),
+        delimited(char('{'), list(char(','), parse_rule), char('}')),
+    )(input)
 }
 
-#[aoc_generator(day19)]
-#[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
-    let (rules, parts) = input.split_once("\n\n").unwrap();
-
-    let rules = rules
-        .lines()
-        .map(|line| {
-            let (name, rest) = line.split_once('{').unwrap();
-            let rules = rest[..rest.len() - 1].split(',').map(parse_rule).collect();
-            (name.to_string(), rules)
-        })
-        .collect();
-
-    let parts = parts.lines().map(parse_part).collect();
+fn parse_part(input: &str) -> IResult<'_, Part> {
+    map(
+        delimited(
+            char('{'),
+            tuple((
+                delimited(tag("x="), int, char(',')),
+                delimited(tag("m="), int, char(',')),
+                delimited(tag("a="), int, char(',')),
+                preceded(tag("s="), int),
+            )),
+            char('}'),
+        ),
+        |(x, m, a, s)| Part { x, m, a, s },
+    )(input)
+}
 
-    Input { rules, parts }
+#[tracing::instrument(skip(input))]
+pub fn input_generator(input: &str) -> Result<T> {
+    let (rules, parts) = finish(
+        input,
+        separated_pair(
+            separated_list1(line_ending, context("workflow", parse_workflow)),
+            blank_line,
+            separated_list1(line_ending, context("part", parse_part)),
+        )(input),
+    )?;
+
+    Ok(Input {
+        rules: rules.into_iter().collect(),
+        parts,
+    })
 }
 
-#[aoc(day19, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> u32 {
     let start = "in";
@@ -177,7 +190,7 @@ pub fn part1(input: &T) -> u32 {
                         Action::Accept => return true,
                         Action::Reject => return false,
                         Action::Continue(next) => {
-                            state = &next;
+                            state = next;
                             break;
                         }
                     }
@@ -188,7 +201,6 @@ pub fn part1(input: &T) -> u32 {
         .sum()
 }
 
-#[aoc(day19, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &T) -> u64 {
     let rules = &input.rules;
@@ -242,7 +254,7 @@ pub fn part2(input: &T) -> u64 {
             match &r.action {
                 Action::Reject => continue,
                 Action::Accept => winner.push(filter),
-                Action::Continue(next) => candidates.push((&next, filter)),
+                Action::Continue(next) => candidates.push((next, filter)),
             }
         }
     }
@@ -322,11 +334,11 @@ hdj{m>838:A,pv}
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 19114);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 19114);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 167409079868000);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 167409079868000);
     }
 }
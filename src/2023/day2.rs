@@ -1,6 +1,19 @@
 use std::cmp;
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, line_ending, u32},
+    combinator::map,
+    error::context,
+    multi::separated_list1,
+    sequence::{preceded, separated_pair},
+};
+
+use crate::{
+    parse::{finish, IResult},
+    Result,
+};
 
 #[derive(Debug)]
 pub struct Round {
@@ -16,63 +29,54 @@ pub struct Game {
 
 type T = Game;
 
-#[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    parse(input).expect("failed to parse input").1
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    finish(input, parse(input))
 }
 
-fn parse(input: &str) -> nom::IResult<&str, Vec<Game>> {
-    let games = input
-        .lines()
-        .map(|line| {
-            let mut s = line.split(':');
-            let game = s.next().unwrap();
-            let rest = s.next().unwrap();
-            assert!(s.next().is_none());
-
-            let rounds = rest
-                .split(';')
-                .map(|game| {
-                    let mut red = 0;
-                    let mut gre = 0;
-                    let mut blu = 0;
-
-                    let get = |input: &str, l: usize| -> u32 {
-                        input[..input.len() - l].trim().parse().unwrap()
-                    };
-
-                    game.split(',').for_each(|c| {
-                        if c.ends_with("red") {
-                            red = get(c, 4);
-                        } else if c.ends_with("green") {
-                            gre = get(c, 6);
-                        } else if c.ends_with("blue") {
-                            blu = get(c, 5);
-                        }
-                    });
-
-                    Round { red, gre, blu }
-                })
-                .collect();
-
-            Game {
-                id: game[5..].parse().unwrap(),
-                rounds,
+fn parse_round(input: &str) -> IResult<'_, Round> {
+    let cubes = separated_pair(u32, char(' '), alt((tag("red"), tag("green"), tag("blue"))));
+    map(separated_list1(tag(", "), cubes), |cubes| {
+        let mut round = Round {
+            red: 0,
+            gre: 0,
+            blu: 0,
+        };
+        for (n, color) in cubes {
+            match color {
+                "red" => round.red = n,
+                "green" => round.gre = n,
+                _ => round.blu = n,
             }
-        })
-        .collect();
+        }
+        round
+    })(input)
+}
+
+fn parse_game(input: &str) -> IResult<'_, Game> {
+    context(
+        "game",
+        map(
+            separated_pair(
+                preceded(tag("Game "), u32),
+                tag(": "),
+                separated_list1(tag("; "), parse_round),
+            ),
+            |(id, rounds)| Game { id, rounds },
+        ),
+    )(input)
+}
 
-    Ok((input, games))
+fn parse(input: &str) -> IResult<'_, Vec<Game>> {
+    separated_list1(line_ending, parse_game)(input)
 }
 
-#[aoc(day2, part1)]
 pub fn part1(input: &[T]) -> u32 {
     const MAX_RED: u32 = 12;
     const MAX_GRE: u32 = 13;
     const MAX_BLU: u32 = 14;
 
     input
-        .into_iter()
+        .iter()
         .filter(|&game| {
             game.rounds
                 .iter()
@@ -83,10 +87,9 @@ pub fn part1(input: &[T]) -> u32 {
         .sum()
 }
 
-#[aoc(day2, part2)]
 pub fn part2(input: &[T]) -> u32 {
     input
-        .into_iter()
+        .iter()
         .map(|game| {
             let (red, gre, blu) = game.rounds.iter().fold((0, 0, 0), |(red, gre, blu), r| {
                 (
@@ -113,11 +116,11 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 8);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 8);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 2286);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 2286);
     }
 }
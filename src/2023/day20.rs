@@ -1,7 +1,20 @@
 use std::collections::{hash_map::Entry, VecDeque};
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::FxHashMap;
+use num::integer::lcm;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, char, one_of},
+    combinator::{map, opt},
+    error::context,
+    sequence::{pair, separated_pair},
+};
+
+use crate::{
+    parse::{lines, list, ws, IResult, ParseError},
+    Error, Result,
+};
 
 #[derive(Debug, PartialEq, Clone)]
 
@@ -101,101 +114,90 @@ impl HandleSignal for Node {
 type N = u8;
 type T = FxHashMap<N, Node>;
 
-#[aoc_generator(day20)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> (T, Vec<N>) {
+pub fn input_generator(input: &str) -> Result<(T, Vec<N>)> {
+    let modules = lines(input, parse_module)?;
+
     let mut mapping = FxHashMap::default();
     mapping.insert(String::from("button"), 0);
     mapping.insert(String::from("broadcaster"), 1);
     mapping.insert(String::from("rx"), 2);
     let mut last_id = 10;
 
-    let mut get_id = |s: String| -> N {
-        if let Some(id) = mapping.get(&s) {
+    let mut get_id = |s: &str| -> N {
+        if let Some(id) = mapping.get(s) {
             return *id;
         }
-        mapping.insert(s, last_id);
+        mapping.insert(s.to_string(), last_id);
         last_id += 1;
         last_id - 1
     };
 
-    let mut nodes: T = input
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once("->").unwrap();
-            let a = a.trim();
-
-            let connected_to = b.split(',').map(|s| get_id(s.trim().to_string())).collect();
-            let node = match a {
-                "broadcaster" => Node::Broadcaster(Broadcaster { connected_to }),
-                flip if flip.starts_with("%") => Node::FlipFlop(FlipFlop {
-                    connected_to,
-                    state: false,
-                }),
-                con if con.starts_with("&") => Node::Conjunction(Conjunction {
-                    connected_to,
-                    inputs: FxHashMap::default(),
-                }),
-                n => unreachable!("reached unknown node {n}"),
-            };
-
-            let a = match a {
-                flip if flip.starts_with("%") => &flip[1..],
-                con if con.starts_with("&") => &con[1..],
-                x => x,
-            }
-            .to_string();
-
-            (get_id(a.to_string()), node)
-        })
-        .collect();
-
-    // update conjunctions
-    input.lines().for_each(|line| {
-        let (from, to) = line.split_once("->").unwrap();
-        let from: &str = from.trim();
-
-        let from: &str = match from {
-            flip if flip.starts_with("%") => &flip[1..],
-            con if con.starts_with("&") => &con[1..],
-            x => x,
+    let mut nodes: T = FxHashMap::default();
+    for (i, (prefix, name, targets)) in modules.iter().enumerate() {
+        let connected_to = targets.iter().map(|s| get_id(s)).collect();
+        let node = match (prefix, *name) {
+            (None, "broadcaster") => Node::Broadcaster(Broadcaster { connected_to }),
+            (Some('%'), _) => Node::FlipFlop(FlipFlop {
+                connected_to,
+                state: false,
+            }),
+            (Some(_), _) => Node::Conjunction(Conjunction {
+                connected_to,
+                inputs: FxHashMap::default(),
+            }),
+            (None, _) => return Err(ParseError::new(i + 1, 1, "expected `%` or `&`").into()),
         };
-        let from = get_id(from.to_string());
+        nodes.insert(get_id(name), node);
+    }
 
-        to.split(',')
-            .map(|s| get_id(s.trim().to_string()))
-            .for_each(|n| match nodes.entry(n) {
+    // update conjunctions
+    for (_, from, targets) in &modules {
+        let from = get_id(from);
+        for target in targets {
+            match nodes.entry(get_id(target)) {
                 Entry::Occupied(mut occ) => occ.get_mut().add_receiver(&from),
                 Entry::Vacant(_vac) => {
                     // thanks AoC for having loose ends
                     // rx goes here
                 }
-            })
-    });
+            }
+        }
+    }
 
     // part 2, get loop IDs
     let loops = if !cfg!(test) {
-        let rx = &input
-            .lines()
-            .find(|line| line.ends_with("rx"))
-            .unwrap()
-            .split_ascii_whitespace()
-            .next()
-            .unwrap()[1..];
-        input
-            .lines()
-            .filter(|line| line.ends_with(rx))
-            .map(|line| &line.split_ascii_whitespace().next().unwrap()[1..])
-            .map(|id| get_id(id.to_string()))
+        let (_, rx, _) = modules
+            .iter()
+            .find(|(_, _, targets)| targets.contains(&"rx"))
+            .ok_or_else(|| Error::Parse("no module sends to `rx`".into()))?;
+        modules
+            .iter()
+            .filter(|(_, _, targets)| targets.contains(rx))
+            .map(|(_, id, _)| get_id(id))
             .collect()
     } else {
         vec![]
     };
 
-    (nodes, loops)
+    Ok((nodes, loops))
+}
+
+/// `%a -> inv, con`
+fn parse_module(input: &str) -> IResult<'_, (Option<char>, &str, Vec<&str>)> {
+    context(
+        "module",
+        map(
+            separated_pair(
+                pair(opt(one_of("%&")), alpha1),
+                ws(tag("->")),
+                list(char(','), alpha1),
+            ),
+            |((prefix, name), targets)| (prefix, name, targets),
+        ),
+    )(input)
 }
 
-#[aoc(day20, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &(T, Vec<N>)) -> u64 {
     let mut state = input.0.to_owned();
@@ -233,7 +235,6 @@ pub fn part1(input: &(T, Vec<N>)) -> u64 {
     cnt_low * cnt_high
 }
 
-#[aoc(day20, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &(T, Vec<N>)) -> u64 {
     let mut state = input.0.to_owned();
@@ -262,15 +263,7 @@ pub fn part2(input: &(T, Vec<N>)) -> u64 {
                         if signal == Signal::LowPulse && loops.get(&r) == Some(&0) {
                             loops.insert(r, i);
                             if loops.values().all(|&v| v > 0) {
-                                let mut lcm = 1;
-                                for mut c in loops.values().copied() {
-                                    let d = lcm * c;
-                                    while c != 0 {
-                                        (lcm, c) = (c, lcm % c);
-                                    }
-                                    lcm = d / lcm;
-                                }
-                                return lcm;
+                                return loops.values().copied().fold(1, lcm);
                             }
                         }
                     }
@@ -292,7 +285,7 @@ mod tests {
 &inv -> a";
     #[test_log::test]
     fn test11() {
-        assert_eq!(part1(&input_generator(INPUT1)), 32000000);
+        assert_eq!(part1(&input_generator(INPUT1).unwrap()), 32000000);
     }
 
     const INPUT2: &str = "broadcaster -> a
@@ -302,6 +295,20 @@ mod tests {
 &con -> output";
     #[test_log::test]
     fn test12() {
-        assert_eq!(part1(&input_generator(INPUT2)), 11687500);
+        assert_eq!(part1(&input_generator(INPUT2).unwrap()), 11687500);
+    }
+
+    #[test_log::test]
+    fn invalid() {
+        let err = input_generator("broadcaster -> a\na -> b").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 1: expected `%` or `&`"
+        );
+        let err = input_generator("broadcaster -> a\n%a => b").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 4: expected tag (in module)"
+        );
     }
 }
@@ -1,42 +1,29 @@
 use std::collections::VecDeque;
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::FxHashSet;
 use glam::IVec2;
 
+use crate::{grid::Grid, parse::ParseError, Error, Result};
+
 type T = (FxHashSet<IVec2>, IVec2);
 
-#[aoc_generator(day21)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
+pub fn input_generator(input: &str) -> Result<T> {
+    let grid = Grid::parse(input, |c| matches!(c, '.' | '#' | 'S').then_some(c))?;
     let mut gardens = FxHashSet::default();
-    let mut start = IVec2 { x: 0, y: 0 };
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '.' => {
-                    gardens.insert(IVec2 {
-                        x: x as i32,
-                        y: y as i32,
-                    });
-                }
-                'S' => {
-                    start = IVec2 {
-                        x: x as i32,
-                        y: y as i32,
-                    };
-                    gardens.insert(IVec2 {
-                        x: x as i32,
-                        y: y as i32,
-                    });
-                }
-                '#' => {}
-                _ => unimplemented!(),
-            };
+    let mut start = None;
+    for (pos, &c) in grid.iter() {
+        if c == 'S' && start.replace(pos).is_some() {
+            let (line, column) = (pos.y as usize + 1, pos.x as usize + 1);
+            return Err(ParseError::new(line, column, "second start `S`").into());
+        }
+        if c != '#' {
+            gardens.insert(pos);
         }
     }
+    let start = start.ok_or_else(|| Error::Parse("no start `S`".into()))?;
 
-    (gardens, start)
+    Ok((gardens, start))
 }
 
 #[tracing::instrument(skip(gardens))]
@@ -73,7 +60,6 @@ pub fn walk(gardens: &FxHashSet<IVec2>, starting_point: IVec2, step_limit: i32)
     destinations.len()
 }
 
-#[aoc(day21, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> usize {
     let gardens = &input.0;
@@ -96,7 +82,6 @@ fn extrapolate_last_value(values: &[usize]) -> usize {
     }
 }
 
-#[aoc(day21, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &T) -> usize {
     let gardens = &input.0;
@@ -106,7 +91,7 @@ pub fn part2(input: &T) -> usize {
     for i in 0..=2 {
         progression.push(walk(gardens, starting_point, 65 + i * 131));
     }
-    println!("{:?}", progression);
+    tracing::debug!(?progression);
     while progression.len() < (26501365 - 65) / 131 {
         progression.push(extrapolate_last_value(&progression))
     }
@@ -133,6 +118,17 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 16);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 16);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("...\n.S.\n..x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 3: unexpected `x`"
+        );
+        let err = input_generator("...\n.#.\n...").unwrap_err();
+        assert_eq!(err.to_string(), "invalid input: no start `S`");
     }
 }
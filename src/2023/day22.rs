@@ -1,23 +1,21 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
 
+use nom::{character::complete::char, combinator::map, error::context, sequence::separated_pair};
+
+use crate::{
+    parse::{coords3, lines, IResult},
+    Result,
+};
+
 type T = (usize, usize);
 
-#[aoc_generator(day22)]
+// both ends of a brick, `x1, y1, z1, x2, y2, z2`
+type Brick = (usize, usize, usize, usize, usize, usize);
+
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
-    let mut bricks = input
-        .lines()
-        .map(|l| {
-            let (x1, y1, z1, x2, y2, z2) = l
-                .split(|c: char| !c.is_ascii_digit())
-                .map(|w| w.parse::<usize>().unwrap())
-                .collect_tuple()
-                .unwrap();
-            (x1, y1, z1, x2, y2, z2)
-        })
-        .collect::<Vec<_>>();
+pub fn input_generator(input: &str) -> Result<T> {
+    let mut bricks = lines(input, parse_brick)?;
     bricks.sort_by_key(|b| b.2);
     let mut space = FxHashMap::default();
     for (i, b) in bricks.iter_mut().enumerate() {
@@ -56,7 +54,17 @@ pub fn input_generator(input: &str) -> T {
         p1 += (falling.len() == 1) as usize;
         p2 += falling.len() - 1;
     }
-    (p1, p2)
+    Ok((p1, p2))
+}
+
+fn parse_brick(input: &str) -> IResult<'_, Brick> {
+    context(
+        "brick",
+        map(
+            separated_pair(coords3, char('~'), coords3),
+            |((x1, y1, z1), (x2, y2, z2))| (x1, y1, z1, x2, y2, z2),
+        ),
+    )(input)
 }
 
 fn disintegrate_all(
@@ -78,13 +86,11 @@ fn disintegrate_all(
     }
 }
 
-#[aoc(day22, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> u32 {
     input.0 as u32
 }
 
-#[aoc(day22, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &T) -> u32 {
     input.1 as u32
@@ -106,11 +112,11 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 5);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 5);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 7);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 7);
     }
 }
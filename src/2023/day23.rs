@@ -1,17 +1,21 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::FxHashMap;
+use glam::IVec2;
 use itertools::Itertools;
 
+use crate::{grid::Grid, parse::ParseError, Result};
+
 type T = Vec<u8>;
 
-#[aoc_generator(day23)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> Vec<T> {
-    let x = input
-        .lines()
-        .map(|line| line.bytes().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-    x
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    let grid = Grid::parse(input, |c| "#.^>v<".contains(c).then_some(c as u8))?;
+    if grid.get(IVec2::new(1, 0)) != Some(&b'.') {
+        return Err(ParseError::new(1, 2, "expected the start `.`").into());
+    }
+    if !grid.row(grid.height() - 1).contains(&b'.') {
+        return Err(ParseError::new(grid.height(), 1, "no exit `.` in the last row").into());
+    }
+    Ok(grid.rows().map(<[u8]>::to_vec).collect())
 }
 
 const NEIGHBORS: &[(isize, isize)] = &[(-1, 0), (0, 1), (1, 0), (0, -1)];
@@ -89,13 +93,11 @@ fn solve(grid: &[Vec<u8>], part2: bool) -> usize {
     .unwrap()
 }
 
-#[aoc(day23, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &[T]) -> u32 {
     solve(input, false) as u32
 }
 
-#[aoc(day23, part2)]
 #[tracing::instrument(skip(input))]
 pub fn part2(input: &[T]) -> u32 {
     solve(input, true) as u32
@@ -133,11 +135,30 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 94);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 94);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 154);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 154);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("#.#\n#x#\n#.#").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 2: unexpected `x`"
+        );
+        let err = input_generator("###\n#.#\n#.#").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 2: expected the start `.`"
+        );
+        let err = input_generator("#.#\n#.#\n###").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 1: no exit `.` in the last row"
+        );
     }
 }
@@ -1,8 +1,13 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use z3::ast::{Ast, Int};
+use nom::{character::complete::char, combinator::map, sequence::separated_pair};
+use num::{BigRational, ToPrimitive, Zero};
 
-struct Hailstone<T> {
+use crate::{
+    parse::{coords3, lines, ws, IResult},
+    Error, Param, Params, Result,
+};
+
+pub struct Hailstone<T> {
     pos: (T, T, T),
     vel: (T, T, T),
 }
@@ -20,32 +25,16 @@ impl Hailstone<i128> {
 
 type T = Hailstone<i128>;
 
-#[aoc_generator(day24)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> Vec<T> {
-    input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (pos, vel) = line.split_once('@').unwrap();
-            let pos: (i128, i128, i128) = pos
-                .split(", ")
-                .map(str::trim)
-                .map(|c| c.parse().unwrap())
-                .collect_tuple()
-                .unwrap();
-            let vel: (i128, i128, i128) = vel
-                .split(", ")
-                .map(str::trim)
-                .map(|c| c.parse().unwrap())
-                .collect_tuple()
-                .unwrap();
-            Hailstone {
-                pos: (pos.0, pos.1, pos.2),
-                vel: (vel.0, vel.1, vel.2),
-            }
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    lines(input, parse_hailstone)
+}
+
+fn parse_hailstone(input: &str) -> IResult<'_, T> {
+    map(
+        separated_pair(ws(coords3), char('@'), ws(coords3)),
+        |(pos, vel)| Hailstone { pos, vel },
+    )(input)
 }
 
 #[tracing::instrument]
@@ -62,20 +51,16 @@ fn intersection(
     Some((x, y))
 }
 
-#[aoc(day24, part1)]
-#[tracing::instrument(skip(input))]
-pub fn part1(input: &[T]) -> u32 {
-    let hailstones = input;
+/// Bounds of the test area.
+pub const PARAMS1: &[Param] = &[
+    Param::new("min", 200_000_000_000_000),
+    Param::new("max", 400_000_000_000_000),
+];
 
-    #[cfg(not(test))]
-    const MIN: i128 = 200_000_000_000_000;
-    #[cfg(not(test))]
-    const MAX: i128 = 400_000_000_000_000;
-
-    #[cfg(test)]
-    const MIN: i128 = 7;
-    #[cfg(test)]
-    const MAX: i128 = 27;
+#[tracing::instrument(skip(input, params))]
+pub fn part1(input: &[T], params: &Params) -> u32 {
+    let hailstones = input;
+    let area = params.get("min") as i128..=params.get("max") as i128;
 
     let lines = hailstones.iter().map(|h| h.to_line()).collect::<Vec<_>>();
     let mut total = 0;
@@ -92,7 +77,7 @@ pub fn part1(input: &[T]) -> u32 {
                     continue;
                 }
 
-                if x >= MIN && x <= MAX && y >= MIN && y <= MAX {
+                if area.contains(&x) && area.contains(&y) {
                     total += 1;
                 }
             }
@@ -102,49 +87,94 @@ pub fn part1(input: &[T]) -> u32 {
     total
 }
 
-#[aoc(day24, part2)]
-#[tracing::instrument(skip(input))]
-pub fn part2(input: &[T]) -> u32 {
-    let hailstones = input;
-
-    let cfg = z3::Config::new();
-    let ctx = z3::Context::new(&cfg);
-    let s = z3::Solver::new(&ctx);
-
-    let x = Int::new_const(&ctx, "x");
-    let y = Int::new_const(&ctx, "y");
-    let z = Int::new_const(&ctx, "z");
-    let vx = Int::new_const(&ctx, "vx");
-    let vy = Int::new_const(&ctx, "vy");
-    let vz = Int::new_const(&ctx, "vz");
-
-    for (i, hs) in hailstones.iter().take(3).enumerate() {
-        let a = Int::from_i64(&ctx, hs.pos.0 as i64);
-        let va = Int::from_i64(&ctx, hs.vel.0 as i64);
-        let b = Int::from_i64(&ctx, hs.pos.1 as i64);
-        let vb = Int::from_i64(&ctx, hs.vel.1 as i64);
-        let c = Int::from_i64(&ctx, hs.pos.2 as i64);
-        let vc = Int::from_i64(&ctx, hs.vel.2 as i64);
-
-        let t = Int::new_const(&ctx, format!("t{i}"));
-        s.assert(&t.gt(&Int::from_i64(&ctx, 0)));
-        s.assert(&(x.clone() + vx.clone() * t.clone())._eq(&(a + va * t.clone())));
-        s.assert(&(y.clone() + vy.clone() * t.clone())._eq(&(b + vb * t.clone())));
-        s.assert(&(z.clone() + vz.clone() * t.clone())._eq(&(c + vc * t.clone())));
+/// Solves the square system `matrix * x = rhs` exactly, `None` if it has no single solution.
+fn solve_linear(
+    mut matrix: Vec<Vec<BigRational>>,
+    mut rhs: Vec<BigRational>,
+) -> Option<Vec<BigRational>> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| !matrix[row][col].is_zero())?;
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let pivot_row = matrix[col].clone();
+        for row in 0..n {
+            if row == col || matrix[row][col].is_zero() {
+                continue;
+            }
+            let factor = &matrix[row][col] / &pivot_row[col];
+            for (m, p) in matrix[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *m -= &factor * p;
+            }
+            let delta = &factor * &rhs[col];
+            rhs[row] -= delta;
+        }
     }
 
-    assert_eq!(s.check(), z3::SatResult::Sat);
-    let model = s.get_model().unwrap();
-    let res = model.eval(&(x + y + z), true).unwrap();
-    res.as_i64().unwrap() as u32
+    Some((0..n).map(|i| &rhs[i] / &matrix[i][i]).collect())
+}
 
+/// Equations for the rock's position `P` and velocity `V` from two hailstones `i` and `j`.
+///
+/// The rock hits a hailstone when `(P - p) × (V - v) = 0`. The product `P × V` is the same for all
+/// hailstones, so subtracting the equations of two of them leaves the linear
+/// `P × (v_i - v_j) + (p_i - p_j) × V = p_i × v_i - p_j × v_j`.
+fn equations(i: &T, j: &T) -> [([i128; 6], i128); 3] {
+    let cross = |(a1, a2, a3): (i128, i128, i128), (b1, b2, b3): (i128, i128, i128)| {
+        (a2 * b3 - a3 * b2, a3 * b1 - a1 * b3, a1 * b2 - a2 * b1)
+    };
+    let (dpx, dpy, dpz) = (i.pos.0 - j.pos.0, i.pos.1 - j.pos.1, i.pos.2 - j.pos.2);
+    let (dvx, dvy, dvz) = (i.vel.0 - j.vel.0, i.vel.1 - j.vel.1, i.vel.2 - j.vel.2);
+    let (ci, cj) = (cross(i.pos, i.vel), cross(j.pos, j.vel));
+
+    // coefficients of Px, Py, Pz, Vx, Vy, Vz
+    [
+        ([0, dvz, -dvy, 0, -dpz, dpy], ci.0 - cj.0),
+        ([-dvz, 0, dvx, dpz, 0, -dpx], ci.1 - cj.1),
+        ([dvy, -dvx, 0, -dpy, dpx, 0], ci.2 - cj.2),
+    ]
 }
 
-// thanks https://gist.github.com/WaterFace/1240609d0d4e15fa4ade3e471e7b501e
+/// Fails with [`Error::NoSolution`] if the hailstones do not determine a rock starting at integer
+/// coordinates.
+#[tracing::instrument(skip(input))]
+pub fn part2(input: &[T]) -> Result<i64> {
+    let hailstones = input;
+    let first = hailstones.first().ok_or(Error::NoSolution)?;
+
+    // any three hailstones with independent equations determine the rock
+    let solution = hailstones
+        .iter()
+        .skip(1)
+        .tuple_combinations()
+        .find_map(|(j, k)| {
+            let (matrix, rhs) = equations(first, j)
+                .into_iter()
+                .chain(equations(first, k))
+                .map(|(row, r)| {
+                    (
+                        row.map(|c| BigRational::from_integer(c.into())).to_vec(),
+                        BigRational::from_integer(r.into()),
+                    )
+                })
+                .unzip();
+            solve_linear(matrix, rhs)
+        })
+        .ok_or(Error::NoSolution)?;
+
+    let sum: BigRational = solution[..3].iter().sum();
+    if !sum.is_integer() {
+        return Err(Error::NoSolution);
+    }
+    sum.to_integer().to_i64().ok_or(Error::NoSolution)
+}
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use crate::{Error, Params};
+
+    use super::{input_generator, part1, part2, PARAMS1};
 
     const INPUT: &str = "19, 13, 30 @ -2,  1, -2
 18, 19, 22 @ -1, -1, -2
@@ -154,11 +184,22 @@ mod tests {
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 2);
+        let params = Params::new(PARAMS1).with("min", 7).with("max", 27);
+        assert_eq!(part1(&input_generator(INPUT).unwrap(), &params), 2);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 47);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()).unwrap(), 47);
+    }
+
+    #[test]
+    fn no_solution() {
+        // parallel hailstones leave the rock undetermined
+        let input = "0, 0, 0 @ 1, 1, 1\n1, 0, 0 @ 1, 1, 1\n2, 0, 0 @ 1, 1, 1";
+        assert!(matches!(
+            part2(&input_generator(input).unwrap()),
+            Err(Error::NoSolution)
+        ));
     }
 }
@@ -1,19 +1,21 @@
-use std::collections::VecDeque;
-
-use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::{FxHashMap, FxHashSet};
 
+use nom::character::complete::alpha1;
+
+use crate::{
+    parse::{key_value, lines, spaced},
+    search::{bfs, reachable},
+    Result,
+};
+
 type T = FxHashMap<u16, FxHashSet<u16>>;
 
-#[aoc_generator(day25)]
 #[tracing::instrument(skip(input))]
-pub fn input_generator(input: &str) -> T {
+pub fn input_generator(input: &str) -> Result<T> {
     let mut node_ids = FxHashMap::default();
     let mut nodes = FxHashMap::default();
 
-    input.lines().for_each(|line| {
-        let (start, ends) = line.split_once(": ").unwrap();
-        let ends: Vec<_> = ends.split(' ').collect();
+    for (start, ends) in lines(input, key_value(alpha1, spaced(alpha1)))? {
         for &end in ends.iter() {
             let id = node_ids.len() as u16;
             node_ids.entry(end).or_insert(id);
@@ -33,64 +35,26 @@ pub fn input_generator(input: &str) -> T {
             .entry(start)
             .or_insert_with(FxHashSet::default)
             .extend(ends);
-    });
+    }
 
-    nodes
+    Ok(nodes)
 }
 
 #[tracing::instrument(skip(nodes))]
 fn connected_count(nodes: &T) -> usize {
-    let mut visited = FxHashSet::default();
-    let mut queue = Vec::new();
-    queue.push(*nodes.keys().next().unwrap());
-    while let Some(node) = queue.pop() {
-        if !visited.insert(node) {
-            continue;
-        }
-        for &neighbor in nodes[&node].iter() {
-            queue.push(neighbor);
-        }
-    }
-    visited.len()
+    let start = *nodes.keys().next().unwrap();
+    reachable(start, |node| {
+        nodes[node].iter().copied().collect::<Vec<_>>()
+    })
+    .len()
 }
 
 #[tracing::instrument(skip(nodes))]
 fn pathfind(nodes: &T, start: u16, end: u16) -> Option<Vec<u16>> {
-    let mut visited = FxHashSet::default();
-    let mut queue = VecDeque::new();
-    let mut parents = FxHashMap::default();
-    queue.push_back(start);
-    while let Some(node) = queue.pop_front() {
-        if !visited.insert(node) {
-            continue;
-        }
-        if node == end {
-            break;
-        }
-        for &neighbor in nodes[&node].iter() {
-            if !visited.contains(&neighbor) {
-                parents.insert(neighbor, node);
-                queue.push_back(neighbor);
-            }
-        }
-    }
-
-    let mut path = Vec::new();
-    let mut node = end;
-    while node != start {
-        path.push(node);
-        if parents.contains_key(&node) {
-            node = parents[&node];
-        } else {
-            return None;
-        }
-    }
-    path.push(start);
-    path.reverse();
-    Some(path)
+    let neighbours = |node: &u16| nodes[node].iter().copied().collect::<Vec<_>>();
+    bfs(start, neighbours, |&node| node == end).map(|path| path.states)
 }
 
-#[aoc(day25, part1)]
 #[tracing::instrument(skip(input))]
 pub fn part1(input: &T) -> usize {
     let mut components = input.to_owned();
@@ -129,7 +93,6 @@ pub fn part1(input: &T) -> usize {
     0
 }
 
-#[aoc(day25, part2)]
 #[tracing::instrument(skip(_input))]
 pub fn part2(_input: &T) -> u32 {
     0
@@ -157,11 +120,11 @@ frs: qnr lhk lsr";
 
     #[test_log::test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 54);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 54);
     }
 
     #[test_log::test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 0);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 0);
     }
 }
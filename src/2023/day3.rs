@@ -1,4 +1,4 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::{parse::ParseError, Result};
 
 type T = Challenge;
 
@@ -26,27 +26,37 @@ pub struct Challenge {
     symbols: Vec<Symbol>,
 }
 
-#[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> T {
+pub fn input_generator(input: &str) -> Result<T> {
     let mut challenge = Challenge::default();
 
     let mut x;
-    let mut y = 0;
     let mut num_buf = String::new();
 
     // helper function
-    let convert_number = |challenge: &mut Challenge, num_buf: &mut String, x, y| {
-        assert!(num_buf.len() <= 3);
+    let convert_number = |challenge: &mut Challenge, num_buf: &mut String, x: u32, y: u32| {
+        // x is right after the number
+        let pos_begin = x - num_buf.len() as u32;
+        let val = num_buf
+            .parse()
+            .ok()
+            .filter(|_| num_buf.len() <= 3)
+            .ok_or_else(|| {
+                ParseError::new(
+                    y as usize + 1,
+                    pos_begin as usize + 1,
+                    "expected a number of at most 3 digits",
+                )
+            })?;
         challenge.numbers.push(Number {
-            val: num_buf.parse().unwrap(),
-            // x is right after the number
-            pos_begin: x - num_buf.len() as u32,
+            val,
+            pos_begin,
             pos_end: Pos { x: x - 1, y },
         });
         num_buf.clear();
+        Ok::<_, ParseError>(())
     };
 
-    for line in input.lines() {
+    for (y, line) in (0..).zip(input.lines()) {
         x = 0;
         num_buf.clear();
 
@@ -59,7 +69,7 @@ pub fn input_generator(input: &str) -> T {
                 _ => {
                     // can be a symbol or a dot
                     if !num_buf.is_empty() {
-                        convert_number(&mut challenge, &mut num_buf, x, y);
+                        convert_number(&mut challenge, &mut num_buf, x, y)?;
                     }
                     if char != '.' {
                         challenge.symbols.push(Symbol {
@@ -74,18 +84,15 @@ pub fn input_generator(input: &str) -> T {
         }
 
         if !num_buf.is_empty() {
-            convert_number(&mut challenge, &mut num_buf, x, y);
+            convert_number(&mut challenge, &mut num_buf, x, y)?;
         }
-
-        y += 1;
     }
 
     // dbg!(&challenge);
 
-    challenge
+    Ok(challenge)
 }
 
-#[aoc(day3, part1)]
 pub fn part1(input: &T) -> u32 {
     input
         .numbers
@@ -106,7 +113,6 @@ pub fn part1(input: &T) -> u32 {
         .sum()
 }
 
-#[aoc(day3, part2)]
 pub fn part2(input: &T) -> u32 {
     // this is exactly like part1 ... just different!
     input
@@ -150,11 +156,20 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 4361);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 4361);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 467835);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 467835);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("....\n.*..\n1234").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 1: expected a number of at most 3 digits"
+        );
     }
 }
@@ -1,46 +1,55 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, space1, u32},
+    combinator::map,
+    error::context,
+    sequence::{preceded, separated_pair, tuple},
+};
+
+use crate::{
+    parse::{lines, spaced, ws, IResult},
+    Result,
+};
 
 type T = Card;
 
+#[derive(Debug)]
 pub struct Card {
     // win: Vec<u32>,
     // you: Vec<u32>,
     matches: u32,
 }
 
-#[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    input
-        .lines()
-        .map(|line| {
-            let (_card, rest) = line.split_once(':').unwrap();
-
-            let (win, you) = rest.split_once('|').unwrap();
-            let win: Vec<u32> = win
-                .split_whitespace()
-                .map(|num| num.parse().unwrap())
-                .collect();
-            let you: Vec<u32> = you
-                .split_whitespace()
-                .map(|num| num.parse().unwrap())
-                .collect();
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    lines(input, parse_card)
+}
 
+fn parse_card(input: &str) -> IResult<'_, Card> {
+    let header = tuple((tag("Card"), space1, u32, char(':'), space1));
+    let numbers = separated_pair(spaced(u32), ws(char('|')), spaced(u32));
+    context(
+        "card",
+        map(preceded(header, numbers), |(win, you)| {
             let matches = you.iter().filter(|y| win.contains(y)).count() as u32;
             Card { matches }
-        })
-        .collect()
+        }),
+    )(input)
 }
 
-#[aoc(day4, part1)]
 pub fn part1(input: &[T]) -> u32 {
     input
         .iter()
         .map(|card| card.matches)
-        .map(|matches| if matches == 0 { 0 } else { 1u32 << matches - 1 })
+        .map(|matches| {
+            if matches == 0 {
+                0
+            } else {
+                1u32 << (matches - 1)
+            }
+        })
         .sum()
 }
 
-#[aoc(day4, part2)]
 pub fn part2(input: &[T]) -> u32 {
     // double the size to skip the `if`
     let mut cards = vec![1; input.len() * 2];
@@ -70,11 +79,20 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 13);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 13);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 30);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 30);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("Card 1: 41 48 | 83 86\nCard 2: 13 32 / 61 30").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 15: expected `|` (in card)"
+        );
     }
 }
@@ -1,6 +1,18 @@
 use itertools::Itertools;
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{line_ending, space1, u32},
+    combinator::{map, verify},
+    error::context,
+    multi::separated_list1,
+    sequence::{pair, preceded, terminated, tuple},
+};
+
+use crate::{
+    parse::{blank_line, finish, spaced, IResult},
+    Result,
+};
 
 type T = Input;
 
@@ -31,81 +43,83 @@ pub struct Input {
     humidity_to_location: Vec<Mapping>,
 }
 
-#[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> T {
-    let mut lines = input.lines();
-
-    let seeds = lines
-        .next()
-        .unwrap()
-        .split_at(7)
-        .1
-        .split_whitespace()
-        .map(|n| n.parse().unwrap());
-    let seeds_p1 = seeds.clone().collect();
-    let seeds_p2 = seeds
-        .tuples()
-        .map(|(s, l)| Range { s, e: s + l - 1 })
-        .collect();
-
-    let read_mappings = |lines: &mut dyn Iterator<Item = &str>| -> Vec<Mapping> {
-        let mut mappings = vec![];
-
-        while let Some(next) = lines.next() {
-            if next.is_empty() {
-                break;
-            }
+pub fn input_generator(input: &str) -> Result<T> {
+    finish(input, parse(input))
+}
 
-            let mut it = next.split_ascii_whitespace();
-            let dst_range_start: u32 = it.next().unwrap().parse().unwrap();
-            let src_range_start: u32 = it.next().unwrap().parse().unwrap();
-            let range_len: u32 = it.next().unwrap().parse().unwrap();
-            debug_assert!(it.next().is_none());
-
-            mappings.push(Mapping {
-                dst_range_start,
-                src_range_start,
-                range_len,
-            })
-        }
+fn parse_seeds(input: &str) -> IResult<'_, Vec<u32>> {
+    preceded(
+        tag("seeds: "),
+        context(
+            "pairs of seeds",
+            verify(spaced(u32), |seeds: &Vec<u32>| {
+                seeds.len().is_multiple_of(2)
+            }),
+        ),
+    )(input)
+}
 
-        mappings
-    };
+fn parse_mapping(input: &str) -> IResult<'_, Mapping> {
+    map(
+        tuple((terminated(u32, space1), terminated(u32, space1), u32)),
+        |(dst_range_start, src_range_start, range_len)| Mapping {
+            dst_range_start,
+            src_range_start,
+            range_len,
+        },
+    )(input)
+}
 
-    assert!(lines.next() == Some(""));
-
-    assert!(lines.next() == Some("seed-to-soil map:"));
-    let seed_to_soil = read_mappings(&mut lines);
-    assert!(lines.next() == Some("soil-to-fertilizer map:"));
-    let soil_to_fertilizer = read_mappings(&mut lines);
-    assert!(lines.next() == Some("fertilizer-to-water map:"));
-    let fertilizer_to_water = read_mappings(&mut lines);
-    assert!(lines.next() == Some("water-to-light map:"));
-    let water_to_light = read_mappings(&mut lines);
-    assert!(lines.next() == Some("light-to-temperature map:"));
-    let light_to_temperature = read_mappings(&mut lines);
-    assert!(lines.next() == Some("temperature-to-humidity map:"));
-    let temperature_to_humidity = read_mappings(&mut lines);
-    assert!(lines.next() == Some("humidity-to-location map:"));
-    let humidity_to_location = read_mappings(&mut lines);
-
-    debug_assert!(lines.next().is_none());
-
-    Input {
-        seeds_p1,
-        seeds_p2,
-
-        seed_to_soil,
-        soil_to_fertilizer,
-        fertilizer_to_water,
-        water_to_light,
-        light_to_temperature,
-        temperature_to_humidity,
-        humidity_to_location,
-    }
+/// A blank line and the map called `name`.
+fn parse_map<'a>(name: &'static str) -> impl FnMut(&'a str) -> IResult<'a, Vec<Mapping>> {
+    preceded(
+        pair(blank_line, context(name, pair(tag(name), line_ending))),
+        separated_list1(line_ending, parse_mapping),
+    )
+}
+
+fn parse(input: &str) -> IResult<'_, Input> {
+    let maps = tuple((
+        parse_map("seed-to-soil map:"),
+        parse_map("soil-to-fertilizer map:"),
+        parse_map("fertilizer-to-water map:"),
+        parse_map("water-to-light map:"),
+        parse_map("light-to-temperature map:"),
+        parse_map("temperature-to-humidity map:"),
+        parse_map("humidity-to-location map:"),
+    ));
+    map(
+        pair(parse_seeds, maps),
+        |(
+            seeds,
+            (
+                seed_to_soil,
+                soil_to_fertilizer,
+                fertilizer_to_water,
+                water_to_light,
+                light_to_temperature,
+                temperature_to_humidity,
+                humidity_to_location,
+            ),
+        )| Input {
+            seeds_p2: seeds
+                .iter()
+                .tuples()
+                .map(|(&s, &l)| Range { s, e: s + l - 1 })
+                .collect(),
+            seeds_p1: seeds,
+
+            seed_to_soil,
+            soil_to_fertilizer,
+            fertilizer_to_water,
+            water_to_light,
+            light_to_temperature,
+            temperature_to_humidity,
+            humidity_to_location,
+        },
+    )(input)
 }
 
-#[aoc(day5, part1)]
 pub fn part1(input: &T) -> u32 {
     let state = &input.seeds_p1;
 
@@ -132,7 +146,7 @@ pub fn part1(input: &T) -> u32 {
         result
     };
 
-    let state = apply_mapping(&state, &input.seed_to_soil);
+    let state = apply_mapping(state, &input.seed_to_soil);
     let state = apply_mapping(&state, &input.soil_to_fertilizer);
     let state = apply_mapping(&state, &input.fertilizer_to_water);
     let state = apply_mapping(&state, &input.water_to_light);
@@ -143,106 +157,101 @@ pub fn part1(input: &T) -> u32 {
     state.iter().min().unwrap().to_owned()
 }
 
-#[aoc(day5, part2)]
-pub fn part2(input: &T) -> u32 {
+/// Maps the part of a range covered by a mapping, returning it moved along with the parts left
+/// over, or `None` if they don't overlap.
+type MapRange = fn(&Range, &Mapping) -> Option<(Range, Vec<Range>)>;
+
+fn move_range(r: &Range, length: i32) -> Range {
+    Range {
+        s: (r.s as i32 + length) as u32,
+        e: (r.e as i32 + length) as u32,
+    }
+}
+
+/// Moves the intersection of the ranges.
+fn intersect(prev: &Range, m: &Mapping) -> Option<(Range, Vec<Range>)> {
+    // prev
+    let ps = &prev.s;
+    let pe = &prev.e;
+    // mapping
+    let ms = &m.src_range_start;
+    let me = &(m.src_range_start + m.range_len - 1);
+
+    let mv = m.dst_range_start as i32 - *ms as i32;
+
+    // intersection
+    let is = ps.max(ms);
+    let ie = pe.min(me);
+    if is > ie {
+        return None;
+    }
+
+    let mut rest = vec![];
+    if ps < is {
+        rest.push(Range { s: *ps, e: is - 1 });
+    }
+    if ie < pe {
+        rest.push(Range { s: ie + 1, e: *pe });
+    }
+    Some((move_range(&Range { s: *is, e: *ie }, mv), rest))
+}
+
+/// Goes through every way the ranges can overlap.
+fn case_split(prev: &Range, m: &Mapping) -> Option<(Range, Vec<Range>)> {
+    let s = &m.src_range_start;
+    let l = &m.range_len;
+    let d = &m.dst_range_start;
+
+    let mv = *d as i32 - *s as i32;
+
+    match (prev.s, prev.e, *s, s + l - 1) {
+        // prev range is below mapping range
+        (_, pe, ms, _) if pe < ms => None,
+        // prev range is beyond mapping range
+        (ps, _, _, me) if ps > me => None,
+
+        // prev range is subset of mapping range
+        (ps, pe, ms, me) if ps >= ms && pe <= me => Some((move_range(prev, mv), vec![])),
+        // prev range is below mapping range but overlaps partially
+        (ps, pe, ms, me) if ps < ms && pe >= ms && pe <= me => {
+            // split range
+            let range_a = Range { s: ps, e: ms - 1 };
+            let range_b = Range { s: ms, e: pe };
+            Some((move_range(&range_b, mv), vec![range_a]))
+        }
+        // prev range is above mapping range but overlaps partially
+        (ps, pe, ms, me) if ps >= ms && ps <= me && pe > me => {
+            // split range
+            let range_a = Range { s: ps, e: me };
+            let range_b = Range { s: me + 1, e: pe };
+            Some((move_range(&range_a, mv), vec![range_b]))
+        }
+        // mapping range is subset of prev range
+        (ps, pe, ms, me) if ps < ms && pe > me => {
+            // split range
+            let range_a = Range { s: ps, e: ms - 1 };
+            let range_b = Range { s: ms, e: me };
+            let range_c = Range { s: me + 1, e: pe };
+            Some((move_range(&range_b, mv), vec![range_a, range_c]))
+        }
+        (_, _, _, _) => unreachable!(),
+    }
+}
+
+fn lowest_location(input: &T, map_range: MapRange) -> u32 {
     let state = &input.seeds_p2;
 
     let apply_mapping = |old_state: &Vec<Range>, mapping: &Vec<Mapping>| -> Vec<Range> {
-        let move_range = |r: &Range, length: i32| -> Range {
-            Range {
-                s: (r.s as i32 + length) as u32,
-                e: (r.e as i32 + length) as u32,
-            }
-        };
-
         let mut result = vec![];
         for prev in old_state {
             let mut to_process = vec![*prev];
 
             'outer: while let Some(prev) = to_process.pop() {
                 for m in mapping {
-                    const OLD_CODE: bool = false;
-                    if OLD_CODE {
-                        // old code
-
-                        let p = prev;
-                        let s = &m.src_range_start;
-                        let l = &m.range_len;
-                        let d = &m.dst_range_start;
-
-                        let mv = *d as i32 - *s as i32;
-
-                        match (p.s, p.e, *s, s + l - 1) {
-                            // prev range is below mapping range
-                            (_, pe, ms, _) if pe < ms => {}
-                            // prev range is beyond mapping range
-                            (ps, _, _, me) if ps > me => {}
-
-                            // prev range is subset of mapping range
-                            (ps, pe, ms, me) if ps >= ms && pe <= me => {
-                                result.push(move_range(&prev, mv));
-                                continue 'outer;
-                            }
-                            // prev range is below mapping range but overlaps partially
-                            (ps, pe, ms, me) if ps < ms && pe >= ms && pe <= me => {
-                                // split range
-                                let range_a = Range { s: ps, e: ms - 1 };
-                                let range_b = Range { s: ms, e: pe };
-
-                                to_process.push(range_a);
-                                result.push(move_range(&range_b, mv));
-                                continue 'outer;
-                            }
-                            // prev range is above mapping range but overlaps partially
-                            (ps, pe, ms, me) if ps >= ms && ps <= me && pe > me => {
-                                // split range
-                                let range_a = Range { s: ps, e: me };
-                                let range_b = Range { s: me + 1, e: pe };
-
-                                result.push(move_range(&range_a, mv));
-                                to_process.push(range_b);
-                                continue 'outer;
-                            }
-                            // mapping range is subset of prev range
-                            (ps, pe, ms, me) if ps < ms && pe > me => {
-                                // split range
-                                let range_a = Range { s: ps, e: ms - 1 };
-                                let range_b = Range { s: ms, e: me };
-                                let range_c = Range { s: me + 1, e: pe };
-
-                                to_process.push(range_a);
-                                result.push(move_range(&range_b, mv));
-                                to_process.push(range_c);
-                                continue 'outer;
-                            }
-                            (_, _, _, _) => unreachable!(),
-                        }
-                    } else {
-                        // new code
-
-                        // prev
-                        let ps = &prev.s;
-                        let pe = &prev.e;
-                        // mapping
-                        let ms = &m.src_range_start;
-                        let me = &(m.src_range_start + m.range_len - 1);
-
-                        let mv = m.dst_range_start as i32 - *ms as i32;
-
-                        // intersection
-                        let is = ps.max(ms);
-                        let ie = pe.min(me);
-
-                        if is <= ie {
-                            result.push(move_range(&Range { s: *is, e: *ie }, mv));
-                            if ps < is {
-                                to_process.push(Range { s: *ps, e: is - 1 });
-                            }
-                            if ie < pe {
-                                to_process.push(Range { s: ie + 1, e: *pe });
-                            }
-                            continue 'outer;
-                        }
+                    if let Some((moved, rest)) = map_range(&prev, m) {
+                        result.push(moved);
+                        to_process.extend(rest);
+                        continue 'outer;
                     }
                 }
 
@@ -253,7 +262,7 @@ pub fn part2(input: &T) -> u32 {
         result
     };
 
-    let state = apply_mapping(&state, &input.seed_to_soil);
+    let state = apply_mapping(state, &input.seed_to_soil);
     let state = apply_mapping(&state, &input.soil_to_fertilizer);
     let state = apply_mapping(&state, &input.fertilizer_to_water);
     let state = apply_mapping(&state, &input.water_to_light);
@@ -264,9 +273,18 @@ pub fn part2(input: &T) -> u32 {
     state.iter().map(|r| r.s).min().unwrap().to_owned()
 }
 
+pub fn part2(input: &T) -> u32 {
+    lowest_location(input, intersect)
+}
+
+/// [`part2`] with the overlaps of the ranges told apart case by case.
+pub fn part2_case_split(input: &T) -> u32 {
+    lowest_location(input, case_split)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, part1, part2};
+    use super::{input_generator, part1, part2, part2_case_split};
 
     const INPUT: &str = "seeds: 79 14 55 13
 
@@ -304,11 +322,22 @@ humidity-to-location map:
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 35);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 35);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 46);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 46);
+        assert_eq!(part2_case_split(&input_generator(INPUT).unwrap()), 46);
+    }
+
+    #[test]
+    fn invalid() {
+        let input = INPUT.replace("water-to-light", "water-to-lamp");
+        let err = input_generator(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 18, column 1: expected tag (in water-to-light map:)"
+        );
     }
 }
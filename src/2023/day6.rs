@@ -1,35 +1,44 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{line_ending, u32},
+    combinator::map,
+    sequence::separated_pair,
+};
+
+use crate::{
+    parse::{finish, key_value, spaced, IResult, ParseError},
+    Result,
+};
 
 type T = Race;
 
+#[derive(Debug)]
 pub struct Race {
     duration: u32,
     best: u32,
 }
 
-#[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    let (time, distance) = input.split_once('\n').unwrap();
-
-    debug_assert!(time.starts_with("Time:"));
-    debug_assert!(distance.starts_with("Distance:"));
-
-    let read_line = |input: &str| -> Vec<u32> {
-        let (_, times) = input.split_once(':').unwrap();
-        times
-            .trim()
-            .split_ascii_whitespace()
-            .map(|i| i.parse().unwrap())
-            .collect()
-    };
-
-    let time = read_line(time);
-    let distance = read_line(distance);
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    let (time, distance) = finish(input, parse(input))?;
+    if time.len() != distance.len() {
+        return Err(ParseError::new(2, 1, "expected as many distances as times").into());
+    }
 
-    time.into_iter()
-        .zip(distance.into_iter())
+    Ok(time
+        .into_iter()
+        .zip(distance)
         .map(|(duration, best)| Race { best, duration })
-        .collect()
+        .collect())
+}
+
+fn parse(input: &str) -> IResult<'_, (Vec<u32>, Vec<u32>)> {
+    let time = key_value(tag("Time"), spaced(u32));
+    let distance = key_value(tag("Distance"), spaced(u32));
+    separated_pair(
+        map(time, |(_, t)| t),
+        line_ending,
+        map(distance, |(_, d)| d),
+    )(input)
 }
 
 fn calc(duration: i64, best: i64) -> u32 {
@@ -37,8 +46,8 @@ fn calc(duration: i64, best: i64) -> u32 {
     let q = best as f64 + 0.1f64.powi(5); // little offset for part 1
     let sqrt = (p.powi(2) - q).sqrt();
 
-    let x1 = (-p + sqrt) * -1.0;
-    let x2 = (-p - sqrt) * -1.0;
+    let x1 = -(-p + sqrt);
+    let x2 = -(-p - sqrt);
 
     let x1 = x1.ceil() as u32;
     let x2 = x2.floor() as u32;
@@ -46,7 +55,6 @@ fn calc(duration: i64, best: i64) -> u32 {
     x2 - x1 + 1
 }
 
-#[aoc(day6, part1)]
 pub fn part1(input: &[T]) -> u32 {
     input
         .iter()
@@ -55,7 +63,6 @@ pub fn part1(input: &[T]) -> u32 {
         .unwrap()
 }
 
-#[aoc(day6, part2)]
 pub fn part2(input: &[T]) -> u32 {
     let (duration, best): (i64, i64) = input
         .iter()
@@ -76,11 +83,20 @@ Distance:  9  40  200";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 288);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 288);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 71503);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 71503);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("Time:      7  15   30\nDistance:  9  40").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 1: expected as many distances as times"
+        );
     }
 }
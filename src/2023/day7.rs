@@ -1,7 +1,18 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use std::cmp::{Ordering, PartialOrd};
 
+use nom::{
+    character::complete::{one_of, space1, u32},
+    error::context,
+    multi::count,
+    sequence::separated_pair,
+};
+
+use crate::{
+    parse::{lines, IResult},
+    Result,
+};
+
 type T = (Vec<char>, u32);
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -16,9 +27,15 @@ pub enum Classification {
     FiveOfAKind,
 }
 
-#[derive(Debug, Eq, PartialEq, PartialOrd)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct Card<const PART2: bool>(char);
 
+impl<const PART2: bool> PartialOrd for Card<PART2> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl<const PART2: bool> Ord for Card<PART2> {
     fn cmp(&self, other: &Self) -> Ordering {
         let chars = if !PART2 {
@@ -39,7 +56,7 @@ impl<const PART2: bool> Ord for Card<PART2> {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, PartialOrd)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct Hand<const PART2: bool> {
     cards: Vec<Card<PART2>>,
     bid: u32,
@@ -47,6 +64,12 @@ pub struct Hand<const PART2: bool> {
     ty: Classification,
 }
 
+impl<const PART2: bool> PartialOrd for Hand<PART2> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl<const PART2: bool> Ord for Hand<PART2> {
     fn cmp(&self, other: &Self) -> Ordering {
         match self.ty.cmp(&other.ty) {
@@ -67,22 +90,16 @@ impl<const PART2: bool> Ord for Hand<PART2> {
     }
 }
 
-#[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    input
-        .lines()
-        .map(|line| {
-            let (cards, bid) = line.split_at(5);
-
-            let cards: Vec<char> = cards.chars().collect();
-            debug_assert_eq!(cards.len(), 5);
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    lines(input, parse_hand)
+}
 
-            (cards, bid.trim().parse().unwrap())
-        })
-        .collect()
+fn parse_hand(input: &str) -> IResult<'_, T> {
+    let cards = context("5 cards", count(one_of("AKQJT98765432"), 5));
+    separated_pair(cards, space1, u32)(input)
 }
 
-fn to_classification(same: &Vec<usize>) -> Classification {
+fn to_classification(same: &[usize]) -> Classification {
     if same.contains(&5) {
         Classification::FiveOfAKind
     } else if same.contains(&4) {
@@ -122,10 +139,9 @@ fn input_hands<const PART2: bool>(
         .collect()
 }
 
-#[aoc(day7, part1)]
 pub fn part1(input: &[T]) -> u32 {
     let classify = |cards: &Vec<Card<false>>| -> Classification {
-        let same = cards
+        let same: Vec<_> = cards
             .iter()
             .map(|c| c.0)
             // count the occurrence of a card
@@ -138,7 +154,7 @@ pub fn part1(input: &[T]) -> u32 {
 
     let mut hands = input_hands(input, classify);
 
-    hands.sort_by(|a, b| a.cmp(b));
+    hands.sort();
 
     hands
         .iter()
@@ -147,7 +163,6 @@ pub fn part1(input: &[T]) -> u32 {
         .sum()
 }
 
-#[aoc(day7, part2)]
 pub fn part2(input: &[T]) -> u32 {
     let classify = |cards: &Vec<Card<true>>| -> Classification {
         // it only makes sense to replace J with other existing cards
@@ -158,7 +173,7 @@ pub fn part2(input: &[T]) -> u32 {
             .iter()
             .map(|j| {
                 let a = {
-                    let same = cards
+                    let same: Vec<_> = cards
                         .iter()
                         .map(|c| c.0)
                         .counts()
@@ -170,7 +185,7 @@ pub fn part2(input: &[T]) -> u32 {
 
                 // count the occurrence of a card but replace J
                 let b = {
-                    let same = cards
+                    let same: Vec<_> = cards
                         .iter()
                         .map(|c| c.0)
                         .map(|card| if card == 'J' { *j } else { card })
@@ -188,7 +203,7 @@ pub fn part2(input: &[T]) -> u32 {
 
     let mut hands = input_hands(input, classify);
 
-    hands.sort_by(|a, b| a.cmp(b));
+    hands.sort();
 
     hands
         .iter()
@@ -232,15 +247,15 @@ JJJJ2 41";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 6440);
-        assert_eq!(part1(&input_generator(INPUT2)), 6592);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 6440);
+        assert_eq!(part1(&input_generator(INPUT2).unwrap()), 6592);
     }
 
     #[test]
     fn test2() {
         // 250073180    too low
-        assert_eq!(part2(&input_generator(INPUT)), 5905);
-        assert_eq!(part2(&input_generator(INPUT2)), 6839);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 5905);
+        assert_eq!(part2(&input_generator(INPUT2).unwrap()), 6839);
     }
 
     #[test]
@@ -282,12 +297,12 @@ JJJJ2 41";
         // So, 33332 and 2AAAA are both four of a kind hands, but 33332 is stronger because its first card is stronger.
         let a: Hand<false> = Hand {
             bid: 0,
-            cards: { "33332".chars().map(|c| Card(c)).collect() },
+            cards: { "33332".chars().map(Card).collect() },
             ty: Classification::FourOfAKind,
         };
         let b: Hand<false> = Hand {
             bid: 0,
-            cards: { "2AAAA".chars().map(|c| Card(c)).collect() },
+            cards: { "2AAAA".chars().map(Card).collect() },
             ty: Classification::FourOfAKind,
         };
         assert_eq!(a.cmp(&b), Ordering::Greater);
@@ -295,12 +310,12 @@ JJJJ2 41";
         // Similarly, 77888 and 77788 are both a full house, but 77888 is stronger because its third card is stronger
         let a: Hand<false> = Hand {
             bid: 0,
-            cards: { "77888".chars().map(|c| Card(c)).collect() },
+            cards: { "77888".chars().map(Card).collect() },
             ty: Classification::FullHouse,
         };
         let b: Hand<false> = Hand {
             bid: 0,
-            cards: { "77788".chars().map(|c| Card(c)).collect() },
+            cards: { "77788".chars().map(Card).collect() },
             ty: Classification::FullHouse,
         };
         assert_eq!(a.cmp(&b), Ordering::Greater);
@@ -308,14 +323,23 @@ JJJJ2 41";
         // JKKK2 is weaker than QQQQ2 because J is weaker than Q.
         let a: Hand<true> = Hand {
             bid: 0,
-            cards: { "JKKK2".chars().map(|c| Card(c)).collect() },
+            cards: { "JKKK2".chars().map(Card).collect() },
             ty: Classification::FullHouse,
         };
         let b: Hand<true> = Hand {
             bid: 0,
-            cards: { "QQQQ2".chars().map(|c| Card(c)).collect() },
+            cards: { "QQQQ2".chars().map(Card).collect() },
             ty: Classification::FullHouse,
         };
         assert_eq!(a.cmp(&b), Ordering::Less);
     }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("32T3K 765\nT55X5 684").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 4: expected oneof (in 5 cards)"
+        );
+    }
 }
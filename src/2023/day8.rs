@@ -1,7 +1,19 @@
 use num::integer::lcm;
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{
+    branch::alt,
+    character::complete::{alphanumeric1, char, line_ending},
+    combinator::{map, value},
+    error::context,
+    multi::{many1, separated_list1},
+    sequence::{delimited, separated_pair},
+};
+
+use crate::{
+    parse::{blank_line, finish, ws, IResult},
+    Result,
+};
 
 #[derive(Debug)]
 pub struct Task {
@@ -11,39 +23,39 @@ pub struct Task {
 
 type T = Task;
 
-#[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    let mut lines = input.lines();
-    let dir = lines
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| match c {
-            'R' => true,
-            'L' => false,
-            _ => unreachable!(),
-        })
-        .collect();
-    assert!(lines.next().unwrap().is_empty());
-
-    let map = lines
-        .map(|line| {
-            let (a, b) = line.split_once('=').unwrap();
-            let (b, c) = b.split_once(',').unwrap();
-
-            // some sanity checks
-            debug_assert!(a.ends_with(' '));
-            debug_assert!(b.starts_with(" ("));
-            debug_assert!(c.ends_with(')'));
-
-            let b = b[2..].to_owned();
-            let c = c[1..4].to_owned();
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    Ok(vec![finish(input, parse(input))?])
+}
 
-            (a.trim().to_owned(), (b, c))
-        })
-        .collect();
+/// `AAA = (BBB, CCC)`
+fn parse_node(input: &str) -> IResult<'_, (String, (String, String))> {
+    let name = || map(alphanumeric1, str::to_owned);
+    context(
+        "node",
+        separated_pair(
+            name(),
+            ws(char('=')),
+            delimited(
+                char('('),
+                separated_pair(name(), ws(char(',')), name()),
+                char(')'),
+            ),
+        ),
+    )(input)
+}
 
-    vec![Task { dir, map }]
+fn parse(input: &str) -> IResult<'_, Task> {
+    let dir = context(
+        "directions",
+        many1(alt((value(true, char('R')), value(false, char('L'))))),
+    );
+    map(
+        separated_pair(dir, blank_line, separated_list1(line_ending, parse_node)),
+        |(dir, nodes)| Task {
+            dir,
+            map: nodes.into_iter().collect(),
+        },
+    )(input)
 }
 
 fn step<'a>(input: &'a Task, pos: &str, counter: usize) -> &'a str {
@@ -57,7 +69,6 @@ fn step<'a>(input: &'a Task, pos: &str, counter: usize) -> &'a str {
     new_pos
 }
 
-#[aoc(day8, part1)]
 pub fn part1(input: &[T]) -> u32 {
     let input = input.first().unwrap();
 
@@ -68,14 +79,13 @@ pub fn part1(input: &[T]) -> u32 {
     let mut counter = 0;
 
     while pos != end {
-        pos = step(input, &pos, counter);
+        pos = step(input, pos, counter);
         counter += 1;
     }
 
     counter as u32
 }
 
-#[aoc(day8, part2)]
 pub fn part2(input: &[T]) -> u64 {
     let input = input.first().unwrap();
 
@@ -92,13 +102,13 @@ pub fn part2(input: &[T]) -> u64 {
             let mut counter = 0;
 
             while !pos.ends_with('Z') {
-                pos = step(input, &pos, counter);
+                pos = step(input, pos, counter);
                 counter += 1;
             }
 
             counter as u64
         })
-        .reduce(|a, b| lcm(a, b))
+        .reduce(lcm)
         .unwrap()
 }
 
@@ -125,12 +135,21 @@ XXX = (XXX, XXX)";
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 6);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 6);
     }
 
     #[test]
     fn test2() {
         // 3228318959 too low
-        assert_eq!(part2(&input_generator(INPUT2)), 6);
+        assert_eq!(part2(&input_generator(INPUT2).unwrap()), 6);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = input_generator("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA ZZZ)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 3, column 17: unexpected trailing input"
+        );
     }
 }
@@ -1,17 +1,15 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 
+use crate::{
+    parse::{int, lines, spaced},
+    Result,
+};
+
 type T = Vec<Vec<i64>>;
 
-#[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Vec<T> {
-    input
-        .lines()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|num| num.parse().unwrap())
-                .collect::<Vec<i64>>()
-        })
+pub fn input_generator(input: &str) -> Result<Vec<T>> {
+    Ok(lines(input, spaced(int::<i64>))?
+        .into_iter()
         .map(|history| {
             let mut diffs = vec![];
             diffs.push(history.to_owned());
@@ -32,10 +30,9 @@ pub fn input_generator(input: &str) -> Vec<T> {
             }
             diffs
         })
-        .collect()
+        .collect())
 }
 
-#[aoc(day9, part1)]
 pub fn part1(input: &[T]) -> i64 {
     input
         .iter()
@@ -48,7 +45,6 @@ pub fn part1(input: &[T]) -> i64 {
         .sum()
 }
 
-#[aoc(day9, part2)]
 pub fn part2(input: &[T]) -> i64 {
     input
         .iter()
@@ -73,11 +69,11 @@ mod tests {
 
     #[test]
     fn test1() {
-        assert_eq!(part1(&input_generator(INPUT)), 114);
+        assert_eq!(part1(&input_generator(INPUT).unwrap()), 114);
     }
 
     #[test]
     fn test2() {
-        assert_eq!(part2(&input_generator(INPUT)), 2);
+        assert_eq!(part2(&input_generator(INPUT).unwrap()), 2);
     }
 }
@@ -0,0 +1,157 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use num::{BigInt, ToPrimitive};
+
+/// A puzzle answer.
+///
+/// Integers are normalized on conversion, so equal values always compare equal regardless of
+/// the type the solver returned. Text is normalized the same way as it is read back, so text
+/// that spells an integer is an integer, and text with several lines is art.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    /// Integer fitting an `i64`.
+    Int(i64),
+    /// Integer outside the `i64` range.
+    BigInt(BigInt),
+    /// Single line of text.
+    Text(String),
+    /// Multi-line ASCII art, e.g. letters drawn with `#`.
+    Art(String),
+}
+
+impl Answer {
+    /// Builds an [`Answer::Art`] from rows of pixels.
+    pub fn art<R, P>(rows: R) -> Self
+    where
+        R: IntoIterator<Item = P>,
+        P: IntoIterator<Item = bool>,
+    {
+        let rows: Vec<String> = rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|lit| if lit { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        Answer::Art(rows.join("\n"))
+    }
+
+    /// Returns the answer as `i64`, if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Answer::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(i) => write!(f, "{i}"),
+            Answer::BigInt(i) => write!(f, "{i}"),
+            Answer::Text(s) | Answer::Art(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Parses the [`Display`](fmt::Display) form back into an answer.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains('\n') {
+            Ok(Answer::Art(s.to_owned()))
+        } else if let Ok(i) = s.parse::<BigInt>() {
+            Ok(i.into())
+        } else {
+            Ok(Answer::Text(s.to_owned()))
+        }
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        match value.to_i64() {
+            Some(i) => Answer::Int(i),
+            None => Answer::BigInt(value),
+        }
+    }
+}
+
+macro_rules! from_small_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Int(value.into())
+            }
+        })*
+    };
+}
+
+macro_rules! from_large_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                BigInt::from(value).into()
+            }
+        })*
+    };
+}
+
+from_small_int!(i8, i16, i32, i64, u8, u16, u32);
+from_large_int!(isize, usize, u64, i128, u128);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        let Ok(answer) = value.parse();
+        answer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+
+    use super::Answer;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(Answer::from(42u64), Answer::from(42i32));
+        assert_eq!(Answer::from(42usize), Answer::Int(42));
+        assert_eq!(
+            Answer::from(u64::MAX),
+            Answer::BigInt(BigInt::from(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(Answer::from("123"), Answer::Int(123));
+        assert_eq!(Answer::from("-4"), Answer::from(-4));
+        assert_eq!(Answer::from("CMZ"), Answer::Text("CMZ".to_owned()));
+        assert_eq!(Answer::from("#\n."), Answer::Art("#\n.".to_owned()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let answers = [
+            Answer::Int(-12),
+            Answer::from(u128::MAX),
+            Answer::from("CMZ"),
+            Answer::from("123"),
+            Answer::from(String::from("ab\ncd")),
+            Answer::art([[true, false], [false, true]]),
+        ];
+        for answer in answers {
+            assert_eq!(answer.to_string().parse::<Answer>().unwrap(), answer);
+        }
+    }
+}
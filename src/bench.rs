@@ -0,0 +1,235 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::ErrorKind,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{registry::Solution, Answer, Error, Result};
+
+/// How often each stage is run, at least once and at most `runs` times or until `time` is spent.
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    pub runs: usize,
+    pub time: Duration,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            runs: 100,
+            time: Duration::from_secs(5),
+        }
+    }
+}
+
+/// The part of a solution being measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// The input generator, or parsing for the 2021 solutions.
+    Generator,
+    /// The part itself, on already generated input.
+    Solver,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Generator => f.pad("generator"),
+            Stage::Solver => f.pad("solver"),
+        }
+    }
+}
+
+/// Summary of the timings of one stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Panics if `samples` is empty.
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        let at = |q: f64| samples[((samples.len() - 1) as f64 * q).round() as usize];
+        Stats {
+            runs: samples.len(),
+            min: samples[0],
+            median: at(0.5),
+            p95: at(0.95),
+        }
+    }
+}
+
+/// Key of a measurement, `(year, day, part, variant, stage)`.
+pub type Key = (u16, u8, u8, String, Stage);
+
+/// Times the generator and the part of `solution` separately, also returning the answer so that
+/// variants can be compared.
+pub fn bench(
+    solution: &Solution,
+    input: &str,
+    budget: Budget,
+) -> Result<([(Key, Stats); 2], Answer)> {
+    let mut generator = vec![];
+    let mut solver = vec![];
+
+    let mut answer = None;
+    let start = Instant::now();
+    while generator.len() < budget.runs.max(1) {
+        let t = Instant::now();
+        let prepared = solution.prepare(input)?;
+        generator.push(t.elapsed());
+
+        let t = Instant::now();
+        answer = Some(prepared.solve()?);
+        solver.push(t.elapsed());
+
+        if start.elapsed() > budget.time {
+            break;
+        }
+    }
+
+    let key = |stage| {
+        let variant = solution.variant.to_owned();
+        (solution.year, solution.day, solution.part, variant, stage)
+    };
+    let results = [
+        (key(Stage::Generator), Stats::new(generator)),
+        (key(Stage::Solver), Stats::new(solver)),
+    ];
+    Ok((results, answer.expect("ran at least once")))
+}
+
+/// Results of a benchmark run, saved as tab separated values.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub results: BTreeMap<Key, Stats>,
+}
+
+impl Report {
+    const HEADER: &'static str = "year\tday\tpart\tvariant\tstage\truns\tmin_ns\tmedian_ns\tp95_ns";
+
+    /// Loads a previous run, a missing file is an empty report.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut results = BTreeMap::new();
+        for line in content
+            .lines()
+            .filter(|&l| !l.is_empty() && l != Self::HEADER)
+        {
+            let invalid = || Error::Parse(format!("invalid benchmark entry `{line}`"));
+            let fields: Vec<_> = line.split('\t').collect();
+            let [year, day, part, variant, stage, runs, min, median, p95] = fields[..] else {
+                return Err(invalid());
+            };
+            let stage = match stage {
+                "generator" => Stage::Generator,
+                "solver" => Stage::Solver,
+                _ => return Err(invalid()),
+            };
+            let nanos = |s: &str| s.parse().map(Duration::from_nanos).map_err(|_| invalid());
+            let key = (
+                year.parse().map_err(|_| invalid())?,
+                day.parse().map_err(|_| invalid())?,
+                part.parse().map_err(|_| invalid())?,
+                variant.to_owned(),
+                stage,
+            );
+            let stats = Stats {
+                runs: runs.parse().map_err(|_| invalid())?,
+                min: nanos(min)?,
+                median: nanos(median)?,
+                p95: nanos(p95)?,
+            };
+            results.insert(key, stats);
+        }
+
+        Ok(Report { results })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut content = format!("{}\n", Self::HEADER);
+        for ((year, day, part, variant, stage), stats) in &self.results {
+            content += &format!(
+                "{year}\t{day}\t{part}\t{variant}\t{stage}\t{}\t{}\t{}\t{}\n",
+                stats.runs,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.p95.as_nanos()
+            );
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Ratio of the median to the one in `baseline`, if it was measured there.
+    pub fn compare(&self, baseline: &Report, key: &Key) -> Option<f64> {
+        let new = self.results.get(key)?;
+        let old = baseline.results.get(key)?;
+        Some(new.median.as_secs_f64() / old.median.as_secs_f64().max(1e-9))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        registry::{find, DEFAULT_VARIANT},
+        Answer,
+    };
+
+    use super::{bench, Budget, Key, Report, Stage, Stats};
+
+    fn key(year: u16, day: u8, stage: Stage) -> Key {
+        (year, day, 1, DEFAULT_VARIANT.to_owned(), stage)
+    }
+
+    #[test]
+    fn stats() {
+        let samples = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::new(samples);
+        assert_eq!(stats.runs, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(11));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+    }
+
+    #[test]
+    fn report() {
+        let solution = find(2021, 1, 1).unwrap();
+        let budget = Budget {
+            runs: 5,
+            ..Default::default()
+        };
+        let (results, answer) = bench(&solution, "199\n200\n208\n210\n200", budget).unwrap();
+        assert_eq!(answer, Answer::Int(3));
+        assert_eq!(results[0].0, key(2021, 1, Stage::Generator));
+        assert_eq!(results[1].1.runs, 5);
+
+        let path = std::env::temp_dir().join("aoc-bench-test.txt");
+        let report = Report {
+            results: results.into_iter().collect(),
+        };
+        report.save(&path).unwrap();
+        let loaded = Report::load(&path).unwrap();
+        assert_eq!(loaded.results, report.results);
+        assert_eq!(
+            report.compare(&loaded, &key(2021, 1, Stage::Solver)),
+            Some(1.0)
+        );
+        assert_eq!(report.compare(&loaded, &key(2021, 2, Stage::Solver)), None);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
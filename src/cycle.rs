@@ -0,0 +1,178 @@
+use std::hash::Hash;
+
+use fxhash::FxHashMap;
+use num::PrimInt;
+
+/// A repetition in a sequence of states: the state after `offset + period + i` steps is the same
+/// as the one after `offset + i` steps, for any `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Steps before the first state of the cycle.
+    pub offset: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.offset {
+            n
+        } else {
+            self.offset + (n - self.offset) % self.period
+        }
+    }
+}
+
+/// Brent's cycle detection, which only keeps two states around but computes most of them more
+/// than once.
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // search for the period with a tortoise staying at powers of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // the first repetition is where two states `period` apart meet
+    let mut offset = 0;
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        offset += 1;
+    }
+
+    Cycle { offset, period }
+}
+
+/// The state after `n` steps, skipping the repetitions found with [`brent`].
+pub fn nth_state<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let cycle = brent(start.clone(), &mut step);
+    (0..cycle.reduce(n)).fold(start, |state, _| step(&state))
+}
+
+/// Hash-keyed history of a simulation, for states too big or too costly to compute twice.
+///
+/// Every step is recorded by a `key`, which must identify the state, and a `value` derived from it
+/// like a score.
+#[derive(Debug, Clone)]
+pub struct History<K, V> {
+    seen: FxHashMap<K, usize>,
+    values: Vec<V>,
+}
+
+impl<K: Eq + Hash, V> Default for History<K, V> {
+    fn default() -> Self {
+        History {
+            seen: FxHashMap::default(),
+            values: vec![],
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> History<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the next step, returning the cycle once its `key` was seen before.
+    pub fn push(&mut self, key: K, value: V) -> Option<Cycle> {
+        let step = self.values.len();
+        self.values.push(value);
+        self.seen.insert(key, step).map(|offset| Cycle {
+            offset,
+            period: step - offset,
+        })
+    }
+
+    /// Values of the steps recorded so far.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// The value at step `n` when it only depends on the state.
+    pub fn value_at(&self, cycle: Cycle, n: usize) -> &V {
+        &self.values[cycle.reduce(n)]
+    }
+
+    /// The value at step `n` when it grows by the same amount every period, like a height.
+    pub fn extrapolate(&self, cycle: Cycle, n: usize) -> V
+    where
+        V: PrimInt,
+    {
+        if n < self.values.len() {
+            return self.values[n];
+        }
+        let Cycle { offset, period } = cycle;
+        let growth = self.values[offset + period] - self.values[offset];
+        let periods = V::from((n - offset) / period).expect("number of periods fits the value");
+        self.values[cycle.reduce(n)] + growth * periods
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brent, nth_state, Cycle, History};
+
+    // 2, 5, 26, 13, then 4, 17, 41, 22, 70 over and over
+    fn step(&x: &u32) -> u32 {
+        (x * x + 1) % 83
+    }
+
+    #[test]
+    fn brent_cycle() {
+        let cycle = brent(2, step);
+        assert_eq!(
+            cycle,
+            Cycle {
+                offset: 4,
+                period: 5
+            }
+        );
+        assert_eq!(cycle.reduce(3), 3);
+        assert_eq!(cycle.reduce(11), 6);
+
+        assert_eq!(nth_state(2, step, 1_000_000_000), 17);
+    }
+
+    #[test]
+    fn history() {
+        let mut states = History::new();
+        let mut sums = History::new();
+        let mut state = 2;
+        let mut sum = 0u64;
+        let cycle = loop {
+            sum += state as u64;
+            sums.push(state, sum);
+            if let Some(cycle) = states.push(state, state) {
+                break cycle;
+            }
+            state = step(&state);
+        };
+        assert_eq!(
+            cycle,
+            Cycle {
+                offset: 4,
+                period: 5
+            }
+        );
+        assert_eq!(*states.value_at(cycle, 11), 41);
+
+        // the sum grows by 4 + 17 + 41 + 22 + 70 = 154 every period
+        assert_eq!(sums.extrapolate(cycle, 2), 33);
+        assert_eq!(sums.extrapolate(cycle, 9), 204);
+        assert_eq!(sums.extrapolate(cycle, 4 + 5 * 1000 + 2), 108 + 154 * 1000);
+    }
+}
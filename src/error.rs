@@ -0,0 +1,30 @@
+use thiserror::Error;
+
+use crate::parse::ParseError;
+
+/// Errors reported by the solutions.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// No input is available for the given day.
+    #[error("no input for {year} day {day}")]
+    MissingInput { year: u16, day: u8 },
+    /// Reading the input failed.
+    #[error("failed to read input: {0}")]
+    Io(#[from] std::io::Error),
+    /// The puzzle input is malformed as a whole, e.g. something it must contain is missing.
+    ///
+    /// Also used for the files the binary reads besides the inputs.
+    #[error("invalid input: {0}")]
+    Parse(String),
+    /// The puzzle input is malformed at a known location, preferred whenever there is one.
+    #[error("invalid input at {0}")]
+    InvalidInput(#[from] ParseError),
+    /// The solver does not declare a parameter of this name.
+    #[error("unknown parameter `{0}`")]
+    UnknownParam(String),
+    /// The puzzle has no answer for the given input.
+    #[error("no solution found")]
+    NoSolution,
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
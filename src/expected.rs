@@ -0,0 +1,221 @@
+use std::{
+    collections::BTreeMap,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::{input::InputProvider, registry::Solution, Answer, Error, Result};
+
+/// Known-correct answers keyed by `(year, day, part)`, stored next to the inputs.
+///
+/// The file has one `<year> <day> <part> <answer>` line per entry, newlines within an answer are
+/// written as `\n`.
+#[derive(Debug, Default)]
+pub struct Expected {
+    path: PathBuf,
+    answers: BTreeMap<(u16, u8, u8), Answer>,
+}
+
+impl Expected {
+    /// Default location, `input/answers.txt`.
+    pub const PATH: &'static str = "input/answers.txt";
+
+    /// Loads the answers from `path`, a missing file is an empty database.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut answers = BTreeMap::new();
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            let mut fields = line.splitn(4, ' ');
+            let mut next = || {
+                fields
+                    .next()
+                    .ok_or_else(|| Error::Parse(format!("incomplete answer entry `{line}`")))
+            };
+            let key = (
+                next()?.parse().map_err(|_| bad_entry(line))?,
+                next()?.parse().map_err(|_| bad_entry(line))?,
+                next()?.parse().map_err(|_| bad_entry(line))?,
+            );
+            let answer = unescape(next()?).parse().unwrap();
+            answers.insert(key, answer);
+        }
+
+        Ok(Expected { path, answers })
+    }
+
+    /// Writes all answers back to the file they were loaded from.
+    pub fn save(&self) -> Result<()> {
+        let content: String = self
+            .answers
+            .iter()
+            .map(|((year, day, part), answer)| {
+                format!("{year} {day} {part} {}\n", escape(&answer.to_string()))
+            })
+            .collect();
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&Answer> {
+        self.answers.get(&(year, day, part))
+    }
+
+    pub fn insert(&mut self, year: u16, day: u8, part: u8, answer: Answer) {
+        self.answers.insert((year, day, part), answer);
+    }
+
+    /// Checks `answer` against the stored one, recording it if there is none yet.
+    pub fn check(&mut self, year: u16, day: u8, part: u8, answer: Answer) -> Verdict {
+        match self.get(year, day, part) {
+            Some(expected) if *expected == answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.to_owned(),
+                actual: answer,
+            },
+            None => {
+                self.insert(year, day, part, answer.to_owned());
+                Verdict::Recorded(answer)
+            }
+        }
+    }
+
+    /// The verdict on what running `solution` returned, see [`Expected::check`].
+    pub fn verify(&mut self, solution: &Solution, result: Result<Answer>) -> Verdict {
+        match result {
+            Ok(answer) => self.check(solution.year, solution.day, solution.part, answer),
+            Err(Error::MissingInput { .. }) => Verdict::Skipped,
+            Err(err) => Verdict::Failed(err),
+        }
+    }
+}
+
+fn bad_entry(line: &str) -> Error {
+    Error::Parse(format!("invalid answer entry `{line}`"))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            },
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Outcome of checking one solution against the [`Expected`] answers.
+#[derive(Debug)]
+pub enum Verdict {
+    /// Matches the stored answer.
+    Correct,
+    /// No answer was stored, the new one got recorded.
+    Recorded(Answer),
+    /// Differs from the stored answer.
+    Wrong { expected: Answer, actual: Answer },
+    /// There is no input for this day.
+    Skipped,
+    /// The solution returned an error.
+    Failed(Error),
+}
+
+impl Verdict {
+    /// Whether this counts as a regression.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Wrong { .. } | Verdict::Failed(_))
+    }
+}
+
+/// Runs every solution and checks its answer, recording answers seen for the first time.
+///
+/// The caller decides whether to [`save`](Expected::save) the newly recorded answers.
+pub fn verify<'a>(
+    solutions: impl IntoIterator<Item = &'a Solution>,
+    inputs: &dyn InputProvider,
+    expected: &mut Expected,
+) -> Vec<(&'a Solution, Verdict)> {
+    solutions
+        .into_iter()
+        .map(|solution| (solution, expected.verify(solution, solution.run(inputs))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        input::{default_provider, Memory},
+        registry::{find, solutions},
+        Answer,
+    };
+
+    use super::{verify, Expected, Verdict};
+
+    #[test]
+    fn verify_and_record() {
+        let path = std::env::temp_dir().join("aoc-expected-test.txt");
+        let _ = std::fs::remove_file(&path);
+
+        let solutions = [find(2021, 1, 1).unwrap(), find(2021, 1, 2).unwrap()];
+        let inputs =
+            Memory::new().with(2021, 1, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263");
+
+        let mut expected = Expected::load(&path).unwrap();
+        expected.insert(2021, 1, 2, Answer::Int(4));
+        let verdicts = verify(&solutions, &inputs, &mut expected);
+        assert!(matches!(verdicts[0].1, Verdict::Recorded(Answer::Int(7))));
+        assert!(matches!(
+            verdicts[1].1,
+            Verdict::Wrong {
+                expected: Answer::Int(4),
+                actual: Answer::Int(5)
+            }
+        ));
+
+        expected.insert(2021, 1, 2, Answer::art([[true], [false]]));
+        expected.save().unwrap();
+        let expected = Expected::load(&path).unwrap();
+        assert_eq!(expected.get(2021, 1, 1), Some(&Answer::Int(7)));
+        assert_eq!(
+            expected.get(2021, 1, 2),
+            Some(&Answer::Art("#\n.".to_owned()))
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Checks every solution against the real inputs, run with `cargo test --release -- --ignored`.
+    ///
+    /// The answers file is left alone, the binary records answers seen for the first time.
+    #[test]
+    #[ignore]
+    fn real_inputs() {
+        let solutions = solutions();
+        let mut expected = Expected::load(Expected::PATH).unwrap();
+        let verdicts = verify(&solutions, &default_provider(), &mut expected);
+
+        let failures: Vec<_> = verdicts
+            .iter()
+            .filter(|(_, verdict)| verdict.is_failure())
+            .map(|(s, verdict)| format!("{} day {} part {}: {verdict:?}", s.year, s.day, s.part))
+            .collect();
+        assert!(failures.is_empty(), "{failures:#?}");
+    }
+}
@@ -0,0 +1,342 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use glam::IVec2;
+
+use crate::{parse::ParseError, Result};
+
+/// Right, down, left, up, with `y` growing downwards like the lines of the input.
+pub const DIRECTIONS4: [IVec2; 4] = [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y];
+
+/// [`DIRECTIONS4`] plus the diagonals, clockwise starting at right.
+pub const DIRECTIONS8: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+];
+
+/// Dense 2D grid, stored row by row.
+///
+/// Positions are `IVec2 { x: column, y: row }`, `(0, 0)` is the top left corner.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows, which must all have the same length.
+    ///
+    /// A row of another length is reported as the line of the input it was parsed from, at the
+    /// first cell it lacks or has too many.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for row in rows {
+            let expected = *width.get_or_insert(row.len());
+            if expected != row.len() {
+                let column = row.len().min(expected) + 1;
+                let message = format!("expected a row of {expected} cells");
+                return Err(ParseError::new(height + 1, column, message).into());
+            }
+            cells.extend(row);
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        })
+    }
+
+    /// Parses one row per line, converting each character with `cell`.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        cell(c).ok_or_else(|| {
+                            ParseError::new(y + 1, x + 1, format!("unexpected `{c}`")).into()
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// `IVec2 { x: width, y: height }`
+    pub fn dim(&self) -> IVec2 {
+        IVec2::new(self.width as i32, self.height as i32)
+    }
+
+    pub fn contains(&self, pos: IVec2) -> bool {
+        pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < self.width && (pos.y as usize) < self.height
+    }
+
+    fn offset(&self, pos: IVec2) -> Option<usize> {
+        self.contains(pos)
+            .then(|| pos.y as usize * self.width + pos.x as usize)
+    }
+
+    pub fn get(&self, pos: IVec2) -> Option<&T> {
+        self.offset(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: IVec2) -> Option<&mut T> {
+        self.offset(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Access as if the grid repeated infinitely in all directions.
+    pub fn get_wrapping(&self, pos: IVec2) -> &T {
+        &self[pos.rem_euclid(self.dim())]
+    }
+
+    pub fn swap(&mut self, a: IVec2, b: IVec2) {
+        let (a, b) = (self.offset(a).unwrap(), self.offset(b).unwrap());
+        self.cells.swap(a, b);
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> {
+        let width = self.width as i32;
+        (0..self.height as i32).flat_map(move |y| (0..width).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Neighbours of `pos` in the [`DIRECTIONS4`], skipping those outside the grid.
+    pub fn neighbours4(&self, pos: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        DIRECTIONS4
+            .into_iter()
+            .map(move |d| pos + d)
+            .filter(|&p| self.contains(p))
+    }
+
+    /// Neighbours of `pos` in the [`DIRECTIONS8`], skipping those outside the grid.
+    pub fn neighbours8(&self, pos: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        DIRECTIONS8
+            .into_iter()
+            .map(move |d| pos + d)
+            .filter(|&p| self.contains(p))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// Positions walking from `start` (excluded) in direction `dir` until leaving the grid.
+    ///
+    /// With a diagonal `dir` this gives a view of a diagonal.
+    pub fn ray(&self, start: IVec2, dir: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        std::iter::successors(Some(start + dir), move |&p| Some(p + dir))
+            .take_while(|&p| self.contains(p))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Rotates the grid by 90° clockwise.
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).rev().cloned())
+            .collect();
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+
+    /// Rotates the grid by 90° counterclockwise.
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .rev()
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: IVec2) -> &Self::Output {
+        match self.offset(pos) {
+            Some(i) => &self.cells[i],
+            None => panic!(
+                "{pos} is outside of the {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, pos: IVec2) -> &mut Self::Output {
+        match self.offset(pos) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "{pos} is outside of the {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+/// Indexing by `(x, y)`.
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        &self[IVec2::new(x as i32, y as i32)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        &mut self[IVec2::new(x as i32, y as i32)]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::Grid;
+
+    const INPUT: &str = "123
+456";
+
+    fn grid() -> Grid<u32> {
+        Grid::parse(INPUT, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn access() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[IVec2::new(2, 1)], 6);
+        assert_eq!(grid[(1, 0)], 2);
+        assert_eq!(grid.get(IVec2::new(3, 0)), None);
+        assert_eq!(grid.get(IVec2::new(0, -1)), None);
+        assert_eq!(*grid.get_wrapping(IVec2::new(-1, 2)), 3);
+
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(1).collect::<Vec<_>>(), [&2, &5]);
+        assert_eq!(
+            grid.ray(IVec2::ZERO, IVec2::ONE).collect::<Vec<_>>(),
+            [IVec2::ONE]
+        );
+        assert_eq!(grid.neighbours4(IVec2::ZERO).count(), 2);
+        assert_eq!(grid.neighbours8(IVec2::new(1, 0)).count(), 5);
+
+        let err = Grid::parse("12\n3", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 2, column 2: expected a row of 2 cells"
+        );
+        let err = Grid::parse("1x", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid input at line 1, column 2: unexpected `x`"
+        );
+    }
+
+    #[test]
+    fn transform() {
+        let grid = grid();
+        assert_eq!(grid.to_string(), INPUT);
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36");
+        assert_eq!(grid.rotate_cw().to_string(), "41\n52\n63");
+        assert_eq!(grid.rotate_ccw().to_string(), "36\n25\n14");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+}
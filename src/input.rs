@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    io::{ErrorKind, Read},
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use crate::{Error, Result};
+
+/// Resolves `(year, day)` to the puzzle input.
+pub trait InputProvider {
+    /// Returns the input of the given day, or [`Error::MissingInput`] when this provider has none.
+    fn input(&self, year: u16, day: u8) -> Result<String>;
+}
+
+/// Reads `<dir>/<year>/day<day>.txt`, e.g. `input/2022/day5.txt`.
+#[derive(Debug, Clone)]
+pub struct CacheDir {
+    dir: PathBuf,
+}
+
+impl CacheDir {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        CacheDir { dir: dir.into() }
+    }
+}
+
+impl InputProvider for CacheDir {
+    fn input(&self, year: u16, day: u8) -> Result<String> {
+        let file = self
+            .dir
+            .join(year.to_string())
+            .join(format!("day{day}.txt"));
+        std::fs::read_to_string(file).map_err(|err| match err.kind() {
+            ErrorKind::NotFound => Error::MissingInput { year, day },
+            _ => Error::Io(err),
+        })
+    }
+}
+
+/// Reads the input text from the environment variable `<prefix>_<year>_<day>`, e.g. `AOC_INPUT_2022_5`.
+#[derive(Debug, Clone)]
+pub struct EnvVar {
+    prefix: String,
+}
+
+impl EnvVar {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        EnvVar {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl Default for EnvVar {
+    fn default() -> Self {
+        EnvVar::new("AOC_INPUT")
+    }
+}
+
+impl InputProvider for EnvVar {
+    fn input(&self, year: u16, day: u8) -> Result<String> {
+        std::env::var(format!("{}_{year}_{day}", self.prefix))
+            .map_err(|_| Error::MissingInput { year, day })
+    }
+}
+
+/// Reads stdin once and hands it out for every day.
+#[derive(Debug, Default)]
+pub struct Stdin {
+    input: OnceLock<String>,
+}
+
+impl InputProvider for Stdin {
+    fn input(&self, _year: u16, _day: u8) -> Result<String> {
+        if let Some(input) = self.input.get() {
+            return Ok(input.to_owned());
+        }
+
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        Ok(self.input.get_or_init(|| input).to_owned())
+    }
+}
+
+/// Inputs kept in memory.
+#[derive(Debug, Default, Clone)]
+pub struct Memory {
+    inputs: HashMap<(u16, u8), String>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Memory::default()
+    }
+
+    /// Adds the input of the given day.
+    pub fn with(mut self, year: u16, day: u8, input: impl Into<String>) -> Self {
+        self.inputs.insert((year, day), input.into());
+        self
+    }
+}
+
+impl InputProvider for Memory {
+    fn input(&self, year: u16, day: u8) -> Result<String> {
+        self.inputs
+            .get(&(year, day))
+            .cloned()
+            .ok_or(Error::MissingInput { year, day })
+    }
+}
+
+/// Asks each provider in turn and returns the first input found.
+#[derive(Default)]
+pub struct Chain {
+    providers: Vec<Box<dyn InputProvider>>,
+}
+
+impl Chain {
+    pub fn new() -> Self {
+        Chain::default()
+    }
+
+    pub fn with(mut self, provider: impl InputProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+}
+
+impl InputProvider for Chain {
+    fn input(&self, year: u16, day: u8) -> Result<String> {
+        for provider in &self.providers {
+            match provider.input(year, day) {
+                Err(Error::MissingInput { .. }) => continue,
+                res => return res,
+            }
+        }
+        Err(Error::MissingInput { year, day })
+    }
+}
+
+/// Environment variables first, then the `input` directory.
+pub fn default_provider() -> Chain {
+    Chain::new()
+        .with(EnvVar::default())
+        .with(CacheDir::new("input"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    use super::{CacheDir, Chain, InputProvider, Memory};
+
+    #[test]
+    fn memory() {
+        let memory = Memory::new().with(2021, 1, "199\n200");
+        assert_eq!(memory.input(2021, 1).unwrap(), "199\n200");
+        assert!(matches!(
+            memory.input(2021, 2),
+            Err(Error::MissingInput { year: 2021, day: 2 })
+        ));
+    }
+
+    #[test]
+    fn chain() {
+        let dir = std::env::temp_dir().join("aoc-input-provider-test");
+        std::fs::create_dir_all(dir.join("2022")).unwrap();
+        std::fs::write(dir.join("2022").join("day3.txt"), "from disk").unwrap();
+
+        let chain = Chain::new()
+            .with(Memory::new().with(2022, 1, "from memory"))
+            .with(CacheDir::new(&dir));
+        assert_eq!(chain.input(2022, 1).unwrap(), "from memory");
+        assert_eq!(chain.input(2022, 3).unwrap(), "from disk");
+        assert!(matches!(
+            chain.input(2022, 4),
+            Err(Error::MissingInput { .. })
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,29 +1,49 @@
-use aoc_runner_derive::aoc_lib;
-
-// mod day1;
-// mod day2;
-// mod day3;
-// mod day4;
-// mod day5;
-// mod day6;
-// mod day7;
-// mod day8;
-// mod day9;
-// mod day10;
-// mod day11;
-// mod day12;
-// mod day13;
-// mod day14;
-// mod day15;
-// mod day16;
-// mod day17;
-// mod day18;
-// mod day19;
-// mod day20;
-// mod day21;
-// mod day22;
-// mod day23;
-// mod day24;
-// mod day25;
-
-aoc_lib! { year = 2024 }
+pub mod answer;
+pub mod bench;
+pub mod cycle;
+pub mod error;
+pub mod expected;
+pub mod grid;
+pub mod input;
+pub mod ocr;
+pub mod parallel;
+pub mod params;
+pub mod parse;
+pub mod registry;
+pub mod search;
+pub mod visual;
+pub mod vm;
+
+#[path = "2021/mod.rs"]
+pub mod year2021;
+#[path = "2022/mod.rs"]
+pub mod year2022;
+#[path = "2023/mod.rs"]
+pub mod year2023;
+
+pub use answer::Answer;
+pub use error::{Error, Result};
+pub use params::{Param, Params};
+
+/// Solver interface of the 2021 solutions.
+///
+/// Each part is solved on freshly parsed input, so `part2` must not rely on `part1` having run.
+pub trait AdventOfCode: Sized {
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    /// Parses the puzzle input.
+    fn parse(input: &str) -> Result<Self>;
+
+    /// Parameters declared for the given part, see [`Param`].
+    fn params(_part: u8) -> &'static [Param] {
+        &[]
+    }
+
+    /// Applies the parameter values, called right after parsing.
+    fn configure(&mut self, _params: &Params) {}
+
+    fn part1(&mut self) -> Result<Self::Answer1>;
+
+    fn part2(&mut self) -> Result<Self::Answer2>;
+}
@@ -0,0 +1,430 @@
+use std::{
+    collections::HashMap,
+    io::{self, IsTerminal},
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use advent_of_code::{
+    bench::{bench, Budget, Report},
+    expected::{Expected, Verdict},
+    input::{CacheDir, Chain, EnvVar, InputProvider, Memory, Stdin},
+    registry::{solutions, Solution, DEFAULT_VARIANT},
+    visual::{self, FileSink, Sink, Terminal},
+    Answer, Error, Params,
+};
+use clap::Parser;
+use tracing_subscriber::EnvFilter;
+
+/// Runs the Advent of Code solutions.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Year(s) to run: `2023`, `2021-2022` or `all`
+    #[arg(short, long, default_value = "all")]
+    year: Selection,
+    /// Day(s) to run: `17`, `1-5` or `all`
+    #[arg(short, long, default_value = "all")]
+    day: Selection,
+    /// Part(s) to run: `1`, `2` or `all`
+    #[arg(short, long, default_value = "all")]
+    part: Selection,
+    /// Implementation(s) to run: a variant name or `all`, whose answers must agree
+    #[arg(long, default_value = DEFAULT_VARIANT)]
+    variant: String,
+    /// Directory holding `<year>/day<day>.txt` inputs
+    #[arg(long, default_value = "input")]
+    input_dir: PathBuf,
+    /// Use this file as input for every selected day
+    #[arg(short, long, conflicts_with = "stdin")]
+    input: Option<PathBuf>,
+    /// Read the input for every selected day from stdin
+    #[arg(long)]
+    stdin: bool,
+    /// Override puzzle parameters, e.g. `y=10,max=20`
+    #[arg(long)]
+    param: Option<String>,
+    /// Show what the solutions draw on the `terminal` or write it to a file
+    #[arg(long, value_name = "terminal|FILE")]
+    visual: Option<String>,
+    /// File with the expected answers
+    #[arg(long, default_value = Expected::PATH)]
+    answers: PathBuf,
+    /// Benchmark the generator and each part instead of checking answers
+    #[arg(long)]
+    bench: bool,
+    /// Maximum number of runs per solution
+    #[arg(long, default_value_t = 100)]
+    bench_runs: usize,
+    /// Time after which no further runs are started, in seconds
+    #[arg(long, default_value_t = 5.0)]
+    bench_time: f64,
+    /// Where the benchmark results are written to
+    #[arg(long, default_value = "bench_output.txt")]
+    bench_output: PathBuf,
+    /// Previous results to compare against, defaults to `--bench-output`
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    /// Slowdown of the median, in percent, that is flagged as a regression
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+/// An inclusive range of numbers selected on the command line.
+#[derive(Debug, Clone)]
+struct Selection(Option<RangeInclusive<u16>>);
+
+impl Selection {
+    fn contains(&self, value: u16) -> bool {
+        self.0.as_ref().is_none_or(|range| range.contains(&value))
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("invalid number `{s}`"))
+        };
+
+        match s.split_once('-') {
+            _ if s == "all" => Ok(Selection(None)),
+            Some((start, end)) => Ok(Selection(Some(parse(start)?..=parse(end)?))),
+            None => parse(s).map(|n| Selection(Some(n..=n))),
+        }
+    }
+}
+
+/// The first answer of each part, to check the other variants against.
+#[derive(Default)]
+struct Agreement(HashMap<(u16, u8, u8), (&'static str, Answer)>);
+
+impl Agreement {
+    /// The variant that gave a different answer before, if any.
+    fn check(&mut self, solution: &Solution, answer: &Answer) -> Option<&'static str> {
+        let key = (solution.year, solution.day, solution.part);
+        let (variant, first) = self
+            .0
+            .entry(key)
+            .or_insert_with(|| (solution.variant, answer.to_owned()));
+        (first != answer).then_some(*variant)
+    }
+}
+
+fn name(solution: &Solution) -> String {
+    let name = format!(
+        "{} day {:>2} part {}",
+        solution.year, solution.day, solution.part
+    );
+    match solution.is_default() {
+        true => name,
+        false => format!("{name} ({})", solution.variant),
+    }
+}
+
+/// Runs `f`, turning a panic into its message.
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default()
+    })
+}
+
+/// Runs `solution`, timing it without reading the input, or returns the message of its panic.
+fn run(
+    solution: &Solution,
+    inputs: &dyn InputProvider,
+) -> Result<(Result<Answer, Error>, Duration), String> {
+    let input = match inputs.input(solution.year, solution.day) {
+        Ok(input) => input,
+        Err(err) => return Ok((Err(err), Duration::ZERO)),
+    };
+
+    let start = Instant::now();
+    let result = catch(|| solution.solve(&input))?;
+    Ok((result, start.elapsed()))
+}
+
+fn print_answer(name: &str, time: Duration, answer: &Answer, note: &str) {
+    match answer {
+        Answer::Art(art) => println!("{name}: {time:>10.2?}{note}\n{art}"),
+        answer => println!("{name}: {time:>10.2?}  {answer}{note}"),
+    }
+}
+
+fn run_bench(args: &Args, selected: &[Solution], inputs: &dyn InputProvider) -> ExitCode {
+    let baseline_path = args.baseline.as_ref().unwrap_or(&args.bench_output);
+    let baseline = match Report::load(baseline_path) {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            eprintln!("failed to load {}: {err}", baseline_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let budget = Budget {
+        runs: args.bench_runs,
+        time: Duration::from_secs_f64(args.bench_time),
+    };
+
+    // keep the results of solutions that are not part of this run
+    let mut report = Report {
+        results: baseline.results.clone(),
+    };
+    let mut failed = false;
+    let mut agreement = Agreement::default();
+    for solution in selected {
+        let name = name(solution);
+        let input = match inputs.input(solution.year, solution.day) {
+            Ok(input) => input,
+            Err(err @ Error::MissingInput { .. }) => {
+                println!("{name}: skipped, {err}");
+                continue;
+            }
+            Err(err) => {
+                failed = true;
+                println!("{name}: FAILED, {err}");
+                continue;
+            }
+        };
+
+        let results = match catch(|| bench(solution, &input, budget)) {
+            Ok(Ok((results, answer))) => {
+                if let Some(variant) = agreement.check(solution, &answer) {
+                    failed = true;
+                    println!("{name}: MISMATCH, {answer} differs from the {variant} variant");
+                }
+                results
+            }
+            Ok(Err(err)) => {
+                failed = true;
+                println!("{name}: FAILED, {err}");
+                continue;
+            }
+            Err(message) => {
+                failed = true;
+                println!("{name}: PANICKED, {message}");
+                continue;
+            }
+        };
+
+        for (key, stats) in results {
+            report.results.insert(key.clone(), stats);
+            let change = match report.compare(&baseline, &key) {
+                Some(ratio) if (ratio - 1.0) * 100.0 > args.threshold => {
+                    format!("{:+6.1}% REGRESSION", (ratio - 1.0) * 100.0)
+                }
+                Some(ratio) => format!("{:+6.1}%", (ratio - 1.0) * 100.0),
+                None => String::new(),
+            };
+            println!(
+                "{name} {:<9}  min {:>10.2?}  median {:>10.2?}  p95 {:>10.2?}  ({:>3} runs)  {change}",
+                key.4, stats.min, stats.median, stats.p95, stats.runs
+            );
+        }
+    }
+
+    if let Err(err) = report.save(&args.bench_output) {
+        eprintln!("failed to save {}: {err}", args.bench_output.display());
+        failed = true;
+    }
+
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+    let args = Args::parse();
+
+    let mut selected: Vec<_> = solutions()
+        .into_iter()
+        .filter(|s| {
+            args.year.contains(s.year)
+                && args.day.contains(s.day.into())
+                && args.part.contains(s.part.into())
+                && (args.variant == "all" || s.variant == args.variant)
+        })
+        .collect();
+    if selected.is_empty() {
+        eprintln!("no solution matches the selection");
+        return ExitCode::FAILURE;
+    }
+
+    // answers to sample inputs or with changed parameters must not end up in the database
+    let mut custom = args.input.is_some() || args.stdin;
+
+    if let Some(overrides) = &args.param {
+        let overrides = match Params::parse_overrides(overrides) {
+            Ok(overrides) => overrides,
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::FAILURE;
+            }
+        };
+        for (name, value) in overrides {
+            let mut applied = false;
+            for solution in selected.iter_mut().filter(|s| s.params().contains(name)) {
+                solution.params_mut().set(name, value).unwrap();
+                applied = true;
+            }
+            if !applied {
+                eprintln!("no selected solution takes the parameter `{name}`");
+                return ExitCode::FAILURE;
+            }
+        }
+        custom = true;
+    }
+
+    let inputs: Box<dyn InputProvider> = if let Some(file) = &args.input {
+        let input = match std::fs::read_to_string(file) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("failed to read {}: {err}", file.display());
+                return ExitCode::FAILURE;
+            }
+        };
+        let memory = selected.iter().fold(Memory::new(), |memory, s| {
+            memory.with(s.year, s.day, input.as_str())
+        });
+        Box::new(memory)
+    } else if args.stdin {
+        Box::new(Stdin::default())
+    } else {
+        Box::new(
+            Chain::new()
+                .with(EnvVar::default())
+                .with(CacheDir::new(&args.input_dir)),
+        )
+    };
+
+    if let Some(target) = &args.visual {
+        let sink: Box<dyn Sink> = match target.as_str() {
+            "terminal" => Box::new(Terminal {
+                colours: io::stderr().is_terminal(),
+            }),
+            path => match FileSink::create(path) {
+                Ok(sink) => Box::new(sink),
+                Err(err) => {
+                    eprintln!("failed to create {path}: {err}");
+                    return ExitCode::FAILURE;
+                }
+            },
+        };
+        visual::set_sink(Some(sink));
+    }
+
+    // keep the default hook from interleaving its message with the results
+    panic::set_hook(Box::new(|_| ()));
+
+    if args.bench {
+        return run_bench(&args, &selected, inputs.as_ref());
+    }
+
+    let mut expected = match Expected::load(&args.answers) {
+        Ok(expected) => expected,
+        Err(err) => {
+            eprintln!("failed to load {}: {err}", args.answers.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+    let mut recorded = false;
+    let mut total = Duration::ZERO;
+    let mut agreement = Agreement::default();
+    for solution in &selected {
+        let name = name(solution);
+        let (result, time) = match run(solution, inputs.as_ref()) {
+            Ok(ran) => ran,
+            Err(message) => {
+                failed = true;
+                println!("{name}: PANICKED, {message}");
+                continue;
+            }
+        };
+
+        let answer = result.as_ref().ok().cloned();
+        if let Some(answer) = &answer {
+            total += time;
+            if let Some(variant) = agreement.check(solution, answer) {
+                failed = true;
+                println!("{name}: MISMATCH, {answer} differs from the {variant} variant");
+                continue;
+            }
+            if custom {
+                print_answer(&name, time, answer, "");
+                continue;
+            }
+        }
+
+        let note = match expected.verify(solution, result) {
+            Verdict::Correct => String::new(),
+            Verdict::Recorded(_) => {
+                recorded = true;
+                " (new)".to_owned()
+            }
+            Verdict::Wrong { expected, .. } => {
+                failed = true;
+                format!(" WRONG, expected {expected}")
+            }
+            Verdict::Skipped => {
+                println!("{name}: skipped, no input");
+                continue;
+            }
+            Verdict::Failed(err) => {
+                failed = true;
+                println!("{name}: FAILED, {err}");
+                continue;
+            }
+        };
+        if let Some(answer) = &answer {
+            print_answer(&name, time, answer, &note);
+        }
+    }
+    println!("total: {total:.2?}");
+
+    if recorded {
+        if let Err(err) = expected.save() {
+            eprintln!("failed to save {}: {err}", args.answers.display());
+            failed = true;
+        }
+    }
+
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Selection;
+
+    #[test]
+    fn selection() {
+        let all: Selection = "all".parse().unwrap();
+        assert!(all.contains(2021) && all.contains(25));
+
+        let range: Selection = "3-5".parse().unwrap();
+        assert!(!range.contains(2) && range.contains(3) && range.contains(5) && !range.contains(6));
+
+        let single: Selection = "17".parse().unwrap();
+        assert!(single.contains(17) && !single.contains(16));
+
+        assert!("x".parse::<Selection>().is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::Answer;
+
+/// A font used to draw letters with pixels, as in the puzzles that display their answer.
+struct Font {
+    width: usize,
+    height: usize,
+    /// Horizontal distance between two letters.
+    stride: usize,
+    glyphs: &'static [(char, &'static str)],
+}
+
+/// The 4x6 font, used by most puzzles.
+const SMALL: Font = Font {
+    width: 4,
+    height: 6,
+    stride: 5,
+    glyphs: &[
+        ('A', ".##.#..##..######..##..#"),
+        ('B', "###.#..####.#..##..####."),
+        ('C', ".##.#..##...#...#..#.##."),
+        ('E', "#####...###.#...#...####"),
+        ('F', "#####...###.#...#...#..."),
+        ('G', ".##.#..##...#.###..#.###"),
+        ('H', "#..##..######..##..##..#"),
+        ('I', ".###..#...#...#...#..###"),
+        ('J', "..##...#...#...##..#.##."),
+        ('K', "#..##.#.##..#.#.#.#.#..#"),
+        ('L', "#...#...#...#...#...####"),
+        ('O', ".##.#..##..##..##..#.##."),
+        ('P', "###.#..##..####.#...#..."),
+        ('R', "###.#..##..####.#.#.#..#"),
+        ('S', ".####...#....##....####."),
+        ('U', "#..##..##..##..##..#.##."),
+        ('Z', "####...#..#..#..#...####"),
+    ],
+};
+
+/// The 6x10 font of 2018 day 10.
+const LARGE: Font = Font {
+    width: 6,
+    height: 10,
+    stride: 8,
+    glyphs: &[
+        (
+            'A',
+            "..##...#..#.#....##....##....########....##....##....##....#",
+        ),
+        (
+            'B',
+            "#####.#....##....##....######.#....##....##....##....######.",
+        ),
+        (
+            'C',
+            ".####.#....##.....#.....#.....#.....#.....#.....#....#.####.",
+        ),
+        (
+            'E',
+            "#######.....#.....#.....#####.#.....#.....#.....#.....######",
+        ),
+        (
+            'F',
+            "#######.....#.....#.....#####.#.....#.....#.....#.....#.....",
+        ),
+        (
+            'G',
+            ".####.#....##.....#.....#.....#..####....##....##...##.###.#",
+        ),
+        (
+            'H',
+            "#....##....##....##....########....##....##....##....##....#",
+        ),
+        (
+            'J',
+            "...###....#.....#.....#.....#.....#.....#.#...#.#...#..###..",
+        ),
+        (
+            'K',
+            "#....##...#.#..#..#.#...##....##....#.#...#..#..#...#.#....#",
+        ),
+        (
+            'L',
+            "#.....#.....#.....#.....#.....#.....#.....#.....#.....######",
+        ),
+        (
+            'N',
+            "#....###...###...##.#..##.#..##..#.##..#.##...###...###....#",
+        ),
+        (
+            'P',
+            "#####.#....##....##....######.#.....#.....#.....#.....#.....",
+        ),
+        (
+            'R',
+            "#####.#....##....##....######.#..#..#...#.#...#.#....##....#",
+        ),
+        (
+            'X',
+            "#....##....#.#..#..#..#...##....##...#..#..#..#.#....##....#",
+        ),
+        (
+            'Z',
+            "######.....#.....#....#....#....#....#....#.....#.....######",
+        ),
+    ],
+};
+
+impl Font {
+    /// Reads the letters starting in the top left corner, `None` if a glyph is not in the font.
+    fn decode(&self, rows: &[Vec<bool>]) -> Option<String> {
+        let width = rows.iter().map(Vec::len).max()?;
+        let pixel = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or_default();
+
+        let mut letters = String::new();
+        for start in (0..width).step_by(self.stride) {
+            let glyph: String = (0..self.height)
+                .flat_map(|y| (start..start + self.width).map(move |x| (x, y)))
+                .map(|(x, y)| if pixel(x, y) { '#' } else { '.' })
+                .collect();
+            if !glyph.contains('#')
+                && (start..width).all(|x| (0..self.height).all(|y| !pixel(x, y)))
+            {
+                // only blank columns left
+                break;
+            }
+            let (letter, _) = self.glyphs.iter().find(|(_, g)| *g == glyph)?;
+            letters.push(*letter);
+        }
+
+        (!letters.is_empty()).then_some(letters)
+    }
+}
+
+/// Reads the capital letters drawn by `rows` of pixels, in either of the fonts used by the puzzles.
+///
+/// Empty rows below the letters are ignored. Returns `None` if the pixels are not made of known
+/// glyphs.
+pub fn decode<R, P>(rows: R) -> Option<String>
+where
+    R: IntoIterator<Item = P>,
+    P: IntoIterator<Item = bool>,
+{
+    let mut rows: Vec<Vec<bool>> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+    while rows.last().is_some_and(|row| !row.contains(&true)) {
+        rows.pop();
+    }
+
+    [SMALL, LARGE]
+        .iter()
+        .find(|font| font.height == rows.len())?
+        .decode(&rows)
+}
+
+/// The letters drawn by `rows` as [`Answer::Text`], or the pixels as [`Answer::Art`] if they can't
+/// be read, like the pictures of the examples.
+pub fn answer<R, P>(rows: R) -> Answer
+where
+    R: IntoIterator<Item = P>,
+    P: IntoIterator<Item = bool>,
+{
+    let rows: Vec<Vec<bool>> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+    match decode(rows.iter().map(|r| r.iter().copied())) {
+        Some(letters) => Answer::Text(letters),
+        None => Answer::art(rows),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Answer;
+
+    use super::{answer, decode, LARGE, SMALL};
+
+    fn pixels(art: &str) -> Vec<Vec<bool>> {
+        art.lines()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    #[test]
+    fn small() {
+        let art = "\
+###..####.#..#.####..##..#....####.#..#.
+#..#.#....#.#.....#.#..#.#....#....#..#.
+#..#.###..##.....#..#....#....###..####.
+###..#....#.#...#...#.##.#....#....#..#.
+#.#..#....#.#..#....#..#.#....#....#..#.
+#..#.####.#..#.####..###.####.####.#..#.
+........................................";
+        assert_eq!(decode(pixels(art)).as_deref(), Some("REKZGLEH"));
+
+        // the last letter may lack the blank column after it
+        let art = "\
+.##..###.
+#..#.#..#
+#..#.###.
+####.#..#
+#..#.#..#
+#..#.###.";
+        assert_eq!(decode(pixels(art)).as_deref(), Some("AB"));
+
+        assert_eq!(decode(pixels("#...#\n.#.#.\n..#..")), None);
+        assert_eq!(answer(pixels("##\n#.")), Answer::Art("##\n#.".to_owned()));
+    }
+
+    #[test]
+    fn large() {
+        let art = "\
+#....#..######
+#....#.......#
+.#..#........#
+.#..#.......#.
+..##.......#..
+..##......#...
+.#..#....#....
+.#..#...#.....
+#....#..#.....
+#....#..######";
+        assert_eq!(answer(pixels(art)), Answer::Text("XZ".to_owned()));
+    }
+
+    #[test]
+    fn fonts() {
+        for font in [SMALL, LARGE] {
+            for (letter, glyph) in font.glyphs {
+                assert_eq!(glyph.len(), font.width * font.height, "{letter}");
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Iteration that is spread over all cores with the `parallel` feature, and sequential without.
+//!
+//! Callers import `rayon::prelude::*` behind the same feature, the adapters used on the returned
+//! iterator must exist both in [`Iterator`] and in rayon's parallel iterators.
+
+/// `into_par_iter()` with the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub fn par_iter<I: rayon::iter::IntoParallelIterator>(iter: I) -> I::Iter {
+    iter.into_par_iter()
+}
+
+/// `into_iter()` as the `parallel` feature is disabled.
+#[cfg(not(feature = "parallel"))]
+pub fn par_iter<I: IntoIterator>(iter: I) -> I::IntoIter {
+    iter.into_iter()
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use crate::{Error, Result};
+
+/// A named puzzle parameter and its value for the real input.
+///
+/// Used for values the puzzle text states separately from the input, e.g. the row to scan in
+/// 2022 day 15, which differs between the sample and the real input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: i64,
+}
+
+impl Param {
+    pub const fn new(name: &'static str, default: i64) -> Self {
+        Param { name, default }
+    }
+}
+
+/// Values of the parameters declared by a solver, starting out at their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<&'static str, i64>,
+}
+
+impl Params {
+    pub fn new(declared: &[Param]) -> Self {
+        Params {
+            values: declared.iter().map(|p| (p.name, p.default)).collect(),
+        }
+    }
+
+    /// Overrides a declared parameter.
+    pub fn set(&mut self, name: &str, value: i64) -> Result<()> {
+        match self.values.get_mut(name) {
+            Some(v) => {
+                *v = value;
+                Ok(())
+            }
+            None => Err(Error::UnknownParam(name.to_owned())),
+        }
+    }
+
+    /// Overrides a declared parameter, panics if it is unknown.
+    pub fn with(mut self, name: &str, value: i64) -> Self {
+        self.set(name, value).unwrap();
+        self
+    }
+
+    /// Parses overrides in the form `name=value,name=value`.
+    pub fn parse_overrides(overrides: &str) -> Result<Vec<(&str, i64)>> {
+        overrides
+            .split(',')
+            .filter(|e| !e.is_empty())
+            .map(|entry| {
+                entry
+                    .split_once('=')
+                    .and_then(|(name, value)| Some((name.trim(), value.trim().parse().ok()?)))
+                    .ok_or_else(|| Error::Parse(format!("invalid parameter `{entry}`")))
+            })
+            .collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Returns the value of a declared parameter.
+    ///
+    /// Panics if the solver did not declare `name`.
+    pub fn get(&self, name: &str) -> i64 {
+        match self.values.get(name) {
+            Some(&v) => v,
+            None => panic!("undeclared parameter `{name}`"),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, i64)> + '_ {
+        self.values.iter().map(|(&name, &value)| (name, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    use super::{Param, Params};
+
+    const PARAMS: &[Param] = &[Param::new("y", 2_000_000), Param::new("max", 4_000_000)];
+
+    #[test]
+    fn overrides() {
+        let mut params = Params::new(PARAMS);
+        assert_eq!(params.get("y"), 2_000_000);
+
+        for (name, value) in Params::parse_overrides("y=10, max=20").unwrap() {
+            params.set(name, value).unwrap();
+        }
+        assert_eq!(params.get("y"), 10);
+        assert_eq!(params.get("max"), 20);
+
+        assert!(!params.contains("x"));
+        assert!(matches!(params.set("x", 1), Err(Error::UnknownParam(_))));
+        assert!(matches!(Params::parse_overrides("y"), Err(Error::Parse(_))));
+    }
+}
@@ -0,0 +1,347 @@
+use std::{fmt, str::FromStr};
+
+use glam::{IVec2, IVec3};
+use nom::{
+    character::complete::{char, digit1, line_ending, none_of, one_of, space0, space1},
+    combinator::{map, map_opt, map_res, opt, peek, recognize},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::{many1, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+};
+
+use crate::{grid::Grid, Result};
+
+/// Result of the nom parsers, keeping the `context` of a failure.
+pub type IResult<'a, O> = nom::IResult<&'a str, O, VerboseError<&'a str>>;
+
+/// Location and cause of malformed puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// What was being parsed, outermost first.
+    pub context: Vec<String>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            context: vec![],
+            message: message.into(),
+        }
+    }
+
+    /// An error at the start of `rest`, which must be the end of `input`.
+    pub fn at(input: &str, rest: &str, message: impl Into<String>) -> Self {
+        let before = &input[..input.len() - rest.len()];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError::new(
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+            message,
+        )
+    }
+
+    /// Moves the error down by `lines`, for input parsed line by line.
+    fn offset(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if !self.context.is_empty() {
+            write!(f, " (in {})", self.context.join(" > "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The nom error types that can be located in the parsed input.
+pub trait NomError<'a> {
+    fn locate(self, input: &'a str) -> ParseError;
+}
+
+impl<'a> NomError<'a> for nom::error::Error<&'a str> {
+    fn locate(self, input: &'a str) -> ParseError {
+        ParseError::at(input, self.input, expected(self.code.description()))
+    }
+}
+
+impl<'a> NomError<'a> for VerboseError<&'a str> {
+    fn locate(self, input: &'a str) -> ParseError {
+        // the innermost error comes first, followed by the contexts it happened in
+        let mut errors = self.errors.into_iter();
+        let Some((rest, kind)) = errors.next() else {
+            return ParseError::new(1, 1, "invalid input");
+        };
+        let message = match kind {
+            VerboseErrorKind::Char(c) => expected(&format!("`{c}`")),
+            VerboseErrorKind::Nom(kind) => expected(kind.description()),
+            VerboseErrorKind::Context(context) => expected(context),
+        };
+
+        let mut error = ParseError::at(input, rest, message);
+        error.context = errors
+            .rev()
+            .filter_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => Some(context.to_owned()),
+                _ => None,
+            })
+            .collect();
+        error
+    }
+}
+
+fn expected(what: &str) -> String {
+    format!("expected {}", what.to_lowercase())
+}
+
+/// Turns the result of parsing all of `input` into a located error.
+///
+/// Fails if anything but trailing newlines is left over.
+pub fn finish<'a, O, E: NomError<'a>>(
+    input: &'a str,
+    result: nom::IResult<&'a str, O, E>,
+) -> Result<O> {
+    match result {
+        Ok((rest, output)) if rest.trim_end_matches('\n').is_empty() => Ok(output),
+        Ok((rest, _)) => Err(ParseError::at(input, rest, "unexpected trailing input").into()),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(e.locate(input).into()),
+        Err(nom::Err::Incomplete(_)) => {
+            Err(ParseError::at(input, "", "unexpected end of input").into())
+        }
+    }
+}
+
+/// Parses every line of `input` with `parser`, reporting errors with their line.
+pub fn lines<'a, O, E: NomError<'a>>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> nom::IResult<&'a str, O, E>,
+) -> Result<Vec<O>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| finish_line(i, line, parser(line)))
+        .collect()
+}
+
+/// Like [`finish`] for the line with the 0-based `index`, parsed on its own.
+pub fn finish_line<'a, O, E: NomError<'a>>(
+    index: usize,
+    line: &'a str,
+    result: nom::IResult<&'a str, O, E>,
+) -> Result<O> {
+    finish(line, result).map_err(|err| match err {
+        crate::Error::InvalidInput(e) => e.offset(index).into(),
+        err => err,
+    })
+}
+
+/// A signed integer of any primitive type, e.g. `-12` or `+7`.
+pub fn int<T: FromStr>(input: &str) -> IResult<'_, T> {
+    context(
+        "integer",
+        map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse),
+    )(input)
+}
+
+/// `parser` with any spaces or tabs around it.
+pub fn ws<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, O> {
+    delimited(space0, parser, space0)
+}
+
+/// One or more `item`s separated by `sep`, with any spaces or tabs around the separators.
+pub fn list<'a, O, S>(
+    sep: impl FnMut(&'a str) -> IResult<'a, S>,
+    item: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated_list1(ws(sep), item)
+}
+
+/// One or more `item`s separated by spaces or tabs, like `1  2 3`.
+pub fn spaced<'a, O>(
+    item: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated_list1(space1, item)
+}
+
+/// A `key: value` pair.
+pub fn key_value<'a, K, V>(
+    key: impl FnMut(&'a str) -> IResult<'a, K>,
+    value: impl FnMut(&'a str) -> IResult<'a, V>,
+) -> impl FnMut(&'a str) -> IResult<'a, (K, V)> {
+    separated_pair(key, ws(char(':')), value)
+}
+
+/// Integer coordinates `x,y`, spaces are allowed after the comma.
+pub fn coords2<T: FromStr>(input: &str) -> IResult<'_, (T, T)> {
+    separated_pair(int, ws(char(',')), int)(input)
+}
+
+/// Integer coordinates `x,y,z`, spaces are allowed after the commas.
+pub fn coords3<T: FromStr>(input: &str) -> IResult<'_, (T, T, T)> {
+    tuple((
+        terminated(int, ws(char(','))),
+        terminated(int, ws(char(','))),
+        int,
+    ))(input)
+}
+
+pub fn ivec2(input: &str) -> IResult<'_, IVec2> {
+    map(coords2, IVec2::from)(input)
+}
+
+pub fn ivec3(input: &str) -> IResult<'_, IVec3> {
+    map(coords3, IVec3::from)(input)
+}
+
+/// An empty line, i.e. two line endings in a row.
+pub fn blank_line(input: &str) -> IResult<'_, ()> {
+    map(pair(line_ending, line_ending), |_| ())(input)
+}
+
+/// One or more blocks of lines separated by blank lines, each parsed by `block`.
+pub fn blocks<'a, O>(
+    block: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated_list1(blank_line, block)
+}
+
+/// Lines of characters up to a blank line or the end of input, converting each character with
+/// `cell`.
+///
+/// All rows must have the same width.
+pub fn grid<'a, T>(
+    mut cell: impl FnMut(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> IResult<'a, Grid<T>> {
+    move |input| {
+        let mut rows: Vec<Vec<T>> = vec![];
+        let mut rest = input;
+        loop {
+            let (r, row) = many1(map_opt(none_of("\r\n"), &mut cell))(rest)?;
+            if rows.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(nom::Err::Failure(VerboseError {
+                    errors: vec![(rest, VerboseErrorKind::Context("row of the same width"))],
+                }));
+            }
+            rows.push(row);
+
+            // the next row must start right after the line ending
+            match preceded(line_ending, peek(none_of("\r\n")))(r) {
+                Ok((next, _)) => rest = next,
+                Err(nom::Err::Error(_)) => {
+                    rest = r;
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let grid = Grid::from_rows(rows).expect("rows have the same width");
+        Ok((rest, grid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec3;
+    use nom::{
+        character::complete::{alpha1, char, digit1, line_ending},
+        combinator::map_res,
+        error::context,
+        multi::separated_list1,
+        sequence::separated_pair,
+    };
+
+    use crate::Error;
+
+    use super::{
+        blocks, coords2, finish, grid, int, ivec3, key_value, lines, list, spaced, IResult,
+        ParseError,
+    };
+
+    fn pair(input: &str) -> IResult<'_, (u32, u32)> {
+        context(
+            "pair",
+            separated_pair(
+                map_res(digit1, str::parse),
+                char(','),
+                map_res(digit1, str::parse),
+            ),
+        )(input)
+    }
+
+    fn pairs(input: &str) -> IResult<'_, Vec<(u32, u32)>> {
+        separated_list1(line_ending, pair)(input)
+    }
+
+    fn error(result: crate::Result<impl std::fmt::Debug>) -> ParseError {
+        match result {
+            Err(Error::InvalidInput(e)) => e,
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn located() {
+        let input = "1,2\n3,4\n";
+        assert_eq!(finish(input, pairs(input)).unwrap(), [(1, 2), (3, 4)]);
+
+        let input = "1,2\n3;4";
+        let e = error(finish(input, pairs(input)));
+        assert_eq!(e.to_string(), "line 1, column 4: unexpected trailing input");
+
+        let e = error(finish("x,2", pairs("x,2")));
+        assert_eq!(e.context, ["pair"]);
+        assert_eq!(e.to_string(), "line 1, column 1: expected digit (in pair)");
+
+        let e = error(lines("1,2\n3,4\n5,,6", pair));
+        assert_eq!((e.line, e.column), (3, 3));
+    }
+
+    #[test]
+    fn combinators() {
+        assert_eq!(int::<i64>("-12x"), Ok(("x", -12)));
+        assert_eq!(int::<u8>("+7"), Ok(("", 7)));
+        assert!(int::<u8>("-7").is_err());
+
+        assert_eq!(coords2::<i32>("3, -4"), Ok(("", (3, -4))));
+        assert_eq!(ivec3("1,2,3"), Ok(("", IVec3::new(1, 2, 3))));
+        assert_eq!(
+            list(char(','), int::<u32>)("1, 2 ,3"),
+            Ok(("", vec![1, 2, 3]))
+        );
+        assert_eq!(spaced(int::<i32>)("1  -2\t3"), Ok(("", vec![1, -2, 3])));
+        assert_eq!(
+            key_value(alpha1, spaced(alpha1))("abc: de fg"),
+            Ok(("", ("abc", vec!["de", "fg"])))
+        );
+
+        let input = "#.\n.#\n\n##\n##\n";
+        let parsed = finish(input, blocks(grid(|c| Some(c == '#')))(input)).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].row(1), [false, true]);
+
+        let e = error(finish("#.\n#", grid(|c| Some(c == '#'))("#.\n#")));
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: expected row of the same width"
+        );
+    }
+}
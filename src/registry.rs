@@ -0,0 +1,333 @@
+use std::borrow::{Borrow, Cow};
+
+use crate::{
+    input::InputProvider,
+    params::{Param, Params},
+    AdventOfCode, Answer, Result,
+};
+
+/// Runs the generator (or parser) and returns the part, ready to be solved.
+type Runner = Box<dyn Fn(&str, &Params) -> Result<Prepared>>;
+
+/// A part with its input already generated.
+pub struct Prepared(Box<dyn FnOnce() -> Result<Answer>>);
+
+impl Prepared {
+    fn new(solver: impl FnOnce() -> Result<Answer> + 'static) -> Self {
+        Prepared(Box::new(solver))
+    }
+
+    /// Runs the part.
+    pub fn solve(self) -> Result<Answer> {
+        (self.0)()
+    }
+}
+
+/// Name of the main implementation of a part, the one run unless another is asked for.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// A single registered solution, identified by `(year, day, part, variant)`.
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// Name of the implementation, parts may have alternate ones that must give the same answer.
+    pub variant: &'static str,
+    params: Params,
+    runner: Runner,
+}
+
+impl Solution {
+    /// Registers a solver working directly on the input text.
+    pub fn new<O>(year: u16, day: u8, part: u8, solver: fn(&str) -> O) -> Self
+    where
+        O: Into<Answer> + 'static,
+    {
+        Solution {
+            year,
+            day,
+            part,
+            variant: DEFAULT_VARIANT,
+            params: Params::default(),
+            runner: Box::new(move |input, _| {
+                let input = input.to_owned();
+                Ok(Prepared::new(move || Ok(solver(&input).into())))
+            }),
+        }
+    }
+
+    /// Registers a solver working on the output of an input generator.
+    pub fn with_generator<G, B, O>(
+        year: u16,
+        day: u8,
+        part: u8,
+        generator: fn(&str) -> Result<G>,
+        solver: fn(&B) -> O,
+    ) -> Self
+    where
+        G: Borrow<B> + 'static,
+        B: ?Sized + 'static,
+        O: Into<Answer> + 'static,
+    {
+        Solution {
+            year,
+            day,
+            part,
+            variant: DEFAULT_VARIANT,
+            params: Params::default(),
+            runner: Box::new(move |input, _| {
+                let data = generator(input)?;
+                Ok(Prepared::new(move || Ok(solver(data.borrow()).into())))
+            }),
+        }
+    }
+
+    /// Like [`Solution::with_generator`] for a solver that can fail, e.g. with
+    /// [`Error::NoSolution`](crate::Error::NoSolution).
+    pub fn try_with_generator<G, B, O>(
+        year: u16,
+        day: u8,
+        part: u8,
+        generator: fn(&str) -> Result<G>,
+        solver: fn(&B) -> Result<O>,
+    ) -> Self
+    where
+        G: Borrow<B> + 'static,
+        B: ?Sized + 'static,
+        O: Into<Answer> + 'static,
+    {
+        Solution {
+            year,
+            day,
+            part,
+            variant: DEFAULT_VARIANT,
+            params: Params::default(),
+            runner: Box::new(move |input, _| {
+                let data = generator(input)?;
+                Ok(Prepared::new(move || solver(data.borrow()).map(Into::into)))
+            }),
+        }
+    }
+
+    /// Registers a solver depending on the `declared` puzzle parameters.
+    pub fn with_params<G, B, O>(
+        year: u16,
+        day: u8,
+        part: u8,
+        generator: fn(&str) -> Result<G>,
+        solver: fn(&B, &Params) -> O,
+        declared: &[Param],
+    ) -> Self
+    where
+        G: Borrow<B> + 'static,
+        B: ?Sized + 'static,
+        O: Into<Answer> + 'static,
+    {
+        Solution {
+            year,
+            day,
+            part,
+            variant: DEFAULT_VARIANT,
+            params: Params::new(declared),
+            runner: Box::new(move |input, params| {
+                let data = generator(input)?;
+                let params = params.to_owned();
+                Ok(Prepared::new(move || {
+                    Ok(solver(data.borrow(), &params).into())
+                }))
+            }),
+        }
+    }
+
+    /// Registers an [`AdventOfCode`] solution (2021).
+    pub fn aoc<D>(year: u16, day: u8, part: u8) -> Self
+    where
+        D: AdventOfCode + 'static,
+    {
+        Solution {
+            year,
+            day,
+            part,
+            variant: DEFAULT_VARIANT,
+            params: Params::new(D::params(part)),
+            runner: Box::new(move |input, params| {
+                let mut data = D::parse(input)?;
+                data.configure(params);
+                Ok(Prepared::new(move || match part {
+                    1 => data.part1().map(Into::into),
+                    _ => data.part2().map(Into::into),
+                }))
+            }),
+        }
+    }
+
+    /// Registers an [`AdventOfCode`] solution with a `solver` other than its `part1` or `part2`.
+    pub fn aoc_with<D, O>(year: u16, day: u8, part: u8, solver: fn(&mut D) -> Result<O>) -> Self
+    where
+        D: AdventOfCode + 'static,
+        O: Into<Answer> + 'static,
+    {
+        Solution {
+            year,
+            day,
+            part,
+            variant: DEFAULT_VARIANT,
+            params: Params::new(D::params(part)),
+            runner: Box::new(move |input, params| {
+                let mut data = D::parse(input)?;
+                data.configure(params);
+                Ok(Prepared::new(move || solver(&mut data).map(Into::into)))
+            }),
+        }
+    }
+
+    /// Names the solution as an alternate implementation of its part.
+    pub fn variant(mut self, name: &'static str) -> Self {
+        self.variant = name;
+        self
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.variant == DEFAULT_VARIANT
+    }
+
+    /// Puzzle parameters, set to the values for the real input unless overridden.
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    pub fn params_mut(&mut self) -> &mut Params {
+        &mut self.params
+    }
+
+    /// Runs only the input generator, so the part can be timed on its own.
+    ///
+    /// Windows line endings are converted and trailing newlines are stripped, as `cargo aoc` did
+    /// for the 2022 and 2023 solutions.
+    pub fn prepare(&self, input: &str) -> Result<Prepared> {
+        let input = match input.contains('\r') {
+            true => Cow::Owned(input.replace("\r\n", "\n")),
+            false => Cow::Borrowed(input),
+        };
+        (self.runner)(input.trim_end_matches('\n'), &self.params)
+    }
+
+    /// Runs the solution on the given input text.
+    pub fn solve(&self, input: &str) -> Result<Answer> {
+        self.prepare(input)?.solve()
+    }
+
+    /// Runs the solution on the input resolved by `inputs`.
+    pub fn run(&self, inputs: &dyn InputProvider) -> Result<Answer> {
+        self.solve(&inputs.input(self.year, self.day)?)
+    }
+}
+
+/// All registered solutions, ordered by year, day and part, each part's default variant first.
+pub fn solutions() -> Vec<Solution> {
+    let mut solutions = crate::year2021::solutions();
+    solutions.extend(crate::year2022::solutions());
+    solutions.extend(crate::year2023::solutions());
+    solutions
+}
+
+/// Looks up the default variant of the solution for `(year, day, part)`.
+pub fn find(year: u16, day: u8, part: u8) -> Option<Solution> {
+    variants(year, day, part)
+        .into_iter()
+        .find(Solution::is_default)
+}
+
+/// All the implementations of `(year, day, part)`.
+pub fn variants(year: u16, day: u8, part: u8) -> Vec<Solution> {
+    solutions()
+        .into_iter()
+        .filter(|s| s.year == year && s.day == day && s.part == part)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{input::Memory, Answer, Error};
+
+    use super::{find, solutions, variants};
+
+    #[test]
+    fn registered() {
+        let solutions: Vec<_> = solutions().into_iter().filter(|s| s.is_default()).collect();
+
+        // 2022 only covers day 1 to 22
+        assert_eq!(solutions.iter().filter(|s| s.year == 2021).count(), 50);
+        assert_eq!(solutions.iter().filter(|s| s.year == 2022).count(), 44);
+
+        assert!(find(2021, 23, 2).is_some());
+        assert!(find(2022, 16, 1).is_some());
+        assert!(find(2023, 17, 2).is_some());
+        assert!(find(2023, 26, 1).is_none());
+    }
+
+    #[test]
+    fn variants_agree() {
+        // every part has one default, with alternates of distinct names after it
+        let mut solutions = solutions().into_iter().peekable();
+        while let Some(first) = solutions.next() {
+            assert!(first.is_default(), "{} day {}", first.year, first.day);
+            let mut names = vec![first.variant];
+            while let Some(s) = solutions
+                .next_if(|s| (s.year, s.day, s.part) == (first.year, first.day, first.part))
+            {
+                assert!(
+                    !names.contains(&s.variant),
+                    "{} day {} {}",
+                    s.year,
+                    s.day,
+                    s.variant
+                );
+                names.push(s.variant);
+            }
+        }
+
+        let input = "start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end";
+        let all = variants(2021, 12, 2);
+        assert!(all.iter().any(|s| s.variant == "recursive"));
+        for solution in all {
+            assert_eq!(
+                solution.solve(input).unwrap(),
+                Answer::Int(36),
+                "{}",
+                solution.variant
+            );
+        }
+    }
+
+    #[test]
+    fn run() {
+        let inputs =
+            Memory::new().with(2021, 1, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263");
+        assert_eq!(
+            find(2021, 1, 1).unwrap().run(&inputs).unwrap(),
+            Answer::Int(7)
+        );
+        assert_eq!(
+            find(2021, 1, 2).unwrap().run(&inputs).unwrap(),
+            Answer::Int(5)
+        );
+        assert!(matches!(
+            find(2021, 2, 1).unwrap().run(&inputs),
+            Err(Error::MissingInput { .. })
+        ));
+    }
+
+    #[test]
+    fn params() {
+        let mut solution = find(2021, 6, 1).unwrap();
+        assert_eq!(solution.params().get("days"), 80);
+
+        solution.params_mut().set("days", 18).unwrap();
+        assert_eq!(solution.solve("3,4,3,1,2").unwrap(), Answer::Int(26));
+        assert!(matches!(
+            solution.params_mut().set("y", 10),
+            Err(Error::UnknownParam(_))
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use fxhash::{FxHashMap, FxHashSet};
+
+/// A shortest path found by one of the searches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    /// All states from the start to the goal, both included.
+    pub states: Vec<S>,
+}
+
+/// Visited states with the one they were reached from, indexed by insertion order.
+struct Visited<S, C> {
+    index: FxHashMap<S, usize>,
+    nodes: Vec<(S, Option<usize>, C)>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Visited<S, C> {
+    fn new(start: S, cost: C) -> Self {
+        Visited {
+            index: [(start.clone(), 0)].into_iter().collect(),
+            nodes: vec![(start, None, cost)],
+        }
+    }
+
+    fn path(&self, mut idx: usize) -> Path<S, C> {
+        let cost = self.nodes[idx].2;
+        let mut states = vec![];
+        loop {
+            let (state, parent, _) = &self.nodes[idx];
+            states.push(state.clone());
+            match parent {
+                Some(parent) => idx = *parent,
+                None => break,
+            }
+        }
+        states.reverse();
+        Path { cost, states }
+    }
+}
+
+/// Breadth-first search, every step costs one.
+pub fn bfs<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new(start, 0);
+    let mut queue = VecDeque::from([0]);
+    while let Some(idx) = queue.pop_front() {
+        let (state, _, cost) = &visited.nodes[idx];
+        if goal(state) {
+            return Some(visited.path(idx));
+        }
+
+        let cost = *cost + 1;
+        for next in neighbours(state) {
+            if !visited.index.contains_key(&next) {
+                let next_idx = visited.nodes.len();
+                visited.index.insert(next.clone(), next_idx);
+                visited.nodes.push((next, Some(idx), cost));
+                queue.push_back(next_idx);
+            }
+        }
+    }
+
+    None
+}
+
+/// All states reachable from `start`, including itself.
+pub fn reachable<S, I>(start: S, mut neighbours: impl FnMut(&S) -> I) -> FxHashSet<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited: FxHashSet<S> = [start.clone()].into_iter().collect();
+    let mut stack = vec![start];
+    while let Some(state) = stack.pop() {
+        for next in neighbours(&state) {
+            if visited.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+    visited
+}
+
+/// Cheapest path for non-negative step costs, `neighbours` yields the next states with the cost of
+/// getting there.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbours, |_| C::default(), goal)
+}
+
+/// Like [`dijkstra`], guided by a `heuristic` that must never overestimate the remaining cost.
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut visited = Visited::new(start, C::default());
+    // use reverse since we want to `pop()` the small values first
+    let mut queue = BinaryHeap::from([Reverse((C::default(), C::default(), 0))]);
+    while let Some(Reverse((_, cost, idx))) = queue.pop() {
+        let (state, _, best) = &visited.nodes[idx];
+        if cost > *best {
+            // a cheaper way was found after this entry was queued
+            continue;
+        }
+        if goal(state) {
+            return Some(visited.path(idx));
+        }
+
+        for (next, step) in neighbours(state) {
+            let next_cost = cost + step;
+            let next_idx = match visited.index.get(&next) {
+                Some(&i) if visited.nodes[i].2 <= next_cost => continue,
+                Some(&i) => {
+                    visited.nodes[i].1 = Some(idx);
+                    visited.nodes[i].2 = next_cost;
+                    i
+                }
+                None => {
+                    let i = visited.nodes.len();
+                    visited.index.insert(next.clone(), i);
+                    visited.nodes.push((next.clone(), Some(idx), next_cost));
+                    i
+                }
+            };
+            queue.push(Reverse((next_cost + heuristic(&next), next_cost, next_idx)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use crate::grid::Grid;
+
+    use super::{astar, bfs, dijkstra, reachable};
+
+    const INPUT: &str = "12111
+19991
+11191
+99191
+11111";
+
+    #[test]
+    fn shortest_paths() {
+        let grid = Grid::parse(INPUT, |c| c.to_digit(10)).unwrap();
+        let end = grid.dim() - IVec2::ONE;
+        let neighbours = |&p: &IVec2| grid.neighbours4(p).map(|n| (n, grid[n]));
+
+        let path = bfs(IVec2::ZERO, |&p| grid.neighbours4(p), |&p| p == end).unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.states.len(), 9);
+
+        let path = dijkstra(IVec2::ZERO, neighbours, |&p| p == end).unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.states[0], IVec2::ZERO);
+        assert_eq!(path.states.last(), Some(&end));
+        let sum: u32 = path.states[1..].iter().map(|&p| grid[p]).sum();
+        assert_eq!(sum, path.cost);
+
+        let manhattan = |p: &IVec2| (end - *p).abs().element_sum() as u32;
+        let path = astar(IVec2::ZERO, neighbours, manhattan, |&p| p == end).unwrap();
+        assert_eq!(path.cost, 8);
+
+        let walls = |&p: &IVec2| grid.neighbours4(p).filter(|&n| grid[n] == 1);
+        assert_eq!(reachable(IVec2::ZERO, walls).len(), 17);
+        assert_eq!(bfs(IVec2::ZERO, walls, |&p| p == IVec2::new(1, 0)), None);
+    }
+}
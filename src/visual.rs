@@ -0,0 +1,302 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    rc::Rc,
+};
+
+use glam::IVec2;
+
+use crate::grid::Grid;
+
+/// Colours of the cells of a [`Frame`], the 8 basic terminal colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Colour {
+    /// ANSI escape code setting the foreground colour.
+    fn ansi(self) -> &'static str {
+        match self {
+            Colour::Black => "\x1b[30m",
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+            Colour::Blue => "\x1b[34m",
+            Colour::Magenta => "\x1b[35m",
+            Colour::Cyan => "\x1b[36m",
+            Colour::White => "\x1b[37m",
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// A picture of the state of a solution, one character per cell.
+///
+/// Positions are `IVec2 { x: column, y: row }` relative to the `origin`, the position of the top
+/// left cell, so that sparse maps can be drawn in their own coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub title: Option<String>,
+    origin: IVec2,
+    cells: Grid<(char, Option<Colour>)>,
+}
+
+impl Frame {
+    /// A frame of `size` cells set to `fill`, whose top left cell is at `origin`.
+    pub fn new(origin: IVec2, size: IVec2, fill: char) -> Self {
+        Frame {
+            title: None,
+            origin,
+            cells: Grid::new(size.x.max(0) as usize, size.y.max(0) as usize, (fill, None)),
+        }
+    }
+
+    /// A frame drawing each cell of `grid` with `cell`.
+    pub fn from_grid<T>(grid: &Grid<T>, mut cell: impl FnMut(&T) -> char) -> Self {
+        Frame {
+            title: None,
+            origin: IVec2::ZERO,
+            cells: grid.map(|c| (cell(c), None)),
+        }
+    }
+
+    /// A frame just large enough for the `points`, the other cells are set to `fill`.
+    pub fn from_points(points: impl IntoIterator<Item = (IVec2, char)>, fill: char) -> Self {
+        let points: Vec<_> = points.into_iter().collect();
+        let min = points.iter().map(|&(p, _)| p).reduce(IVec2::min);
+        let max = points.iter().map(|&(p, _)| p).reduce(IVec2::max);
+        let (Some(min), Some(max)) = (min, max) else {
+            return Frame::new(IVec2::ZERO, IVec2::ZERO, fill);
+        };
+
+        let mut frame = Frame::new(min, max - min + IVec2::ONE, fill);
+        for (pos, c) in points {
+            frame.set(pos, c);
+        }
+        frame
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Position of the top left cell.
+    pub fn origin(&self) -> IVec2 {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn get(&self, pos: IVec2) -> Option<char> {
+        self.cells.get(pos - self.origin).map(|&(c, _)| c)
+    }
+
+    /// Draws `c` at `pos`, cells outside of the frame are ignored.
+    pub fn set(&mut self, pos: IVec2, c: char) {
+        if let Some(cell) = self.cells.get_mut(pos - self.origin) {
+            *cell = (c, None);
+        }
+    }
+
+    /// Draws `c` in `colour` at `pos`, cells outside of the frame are ignored.
+    pub fn paint(&mut self, pos: IVec2, c: char, colour: Colour) {
+        if let Some(cell) = self.cells.get_mut(pos - self.origin) {
+            *cell = (c, Some(colour));
+        }
+    }
+
+    /// The frame as text, with ANSI colour codes if `colours` is set.
+    fn render(&self, colours: bool) -> String {
+        let mut text = String::new();
+        if let Some(title) = &self.title {
+            text += title;
+            text.push('\n');
+        }
+        for row in self.cells.rows() {
+            let mut current = None;
+            for &(c, colour) in row {
+                if colours && colour != current {
+                    text += colour.map_or(RESET, Colour::ansi);
+                    current = colour;
+                }
+                text.push(c);
+            }
+            if current.is_some() {
+                text += RESET;
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// The text of the frame without colours, one line per row.
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.render(false).trim_end_matches('\n'))
+    }
+}
+
+/// State of a solution that can be drawn.
+pub trait Visualise {
+    fn frame(&self) -> Frame;
+}
+
+/// Destination of the frames drawn by the solutions.
+pub trait Sink {
+    fn show(&mut self, frame: &Frame) -> io::Result<()>;
+}
+
+/// Prints the frames to stderr, keeping stdout for the answers.
+#[derive(Debug, Clone, Default)]
+pub struct Terminal {
+    pub colours: bool,
+}
+
+impl Sink for Terminal {
+    fn show(&mut self, frame: &Frame) -> io::Result<()> {
+        writeln!(io::stderr().lock(), "{}", frame.render(self.colours))
+    }
+}
+
+/// Writes the frames as plain text to a file, separated by blank lines.
+#[derive(Debug)]
+pub struct FileSink {
+    file: BufWriter<File>,
+}
+
+impl FileSink {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(FileSink {
+            file: BufWriter::new(File::create(path)?),
+        })
+    }
+}
+
+impl Sink for FileSink {
+    fn show(&mut self, frame: &Frame) -> io::Result<()> {
+        writeln!(self.file, "{}", frame.render(false))?;
+        self.file.flush()
+    }
+}
+
+/// Keeps the frames in memory, shared by its clones so they can be looked at after a run.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    frames: Rc<RefCell<Vec<Frame>>>,
+}
+
+impl Recorder {
+    pub fn frames(&self) -> Vec<Frame> {
+        self.frames.borrow().clone()
+    }
+}
+
+impl Sink for Recorder {
+    fn show(&mut self, frame: &Frame) -> io::Result<()> {
+        self.frames.borrow_mut().push(frame.to_owned());
+        Ok(())
+    }
+}
+
+thread_local! {
+    static SINK: RefCell<Option<Box<dyn Sink>>> = const { RefCell::new(None) };
+}
+
+/// Sends the frames drawn on this thread to `sink`, or nowhere with `None`, the default.
+///
+/// Solutions running on other threads, like the rayon ones, stay silent.
+pub fn set_sink(sink: Option<Box<dyn Sink>>) {
+    SINK.with(|s| *s.borrow_mut() = sink);
+}
+
+/// Whether frames drawn on this thread go anywhere.
+pub fn enabled() -> bool {
+    SINK.with(|s| s.borrow().is_some())
+}
+
+/// Shows the frame made by `draw`, which is only called if a sink is set.
+///
+/// A failing sink is removed after logging the error, so that the solution can go on.
+pub fn show(draw: impl FnOnce() -> Frame) {
+    SINK.with(|s| {
+        let mut sink = s.borrow_mut();
+        if let Some(Err(err)) = sink.as_mut().map(|sink| sink.show(&draw())) {
+            tracing::warn!("visualisation stopped: {err}");
+            *sink = None;
+        }
+    });
+}
+
+/// Shows the current frame of `state`.
+pub fn draw(state: &(impl Visualise + ?Sized)) {
+    show(|| state.frame());
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use crate::grid::Grid;
+
+    use super::{draw, enabled, set_sink, show, Colour, Frame, Recorder, Visualise};
+
+    struct Dot(IVec2);
+
+    impl Visualise for Dot {
+        fn frame(&self) -> Frame {
+            Frame::from_points([(IVec2::ZERO, '+'), (self.0, '#')], '.')
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let grid = Grid::from_rows([vec![1, 0], vec![0, 1]]).unwrap();
+        let mut frame = Frame::from_grid(&grid, |&c| if c == 1 { '#' } else { '.' });
+        frame.paint(IVec2::new(1, 0), '@', Colour::Red);
+        assert_eq!(frame.to_string(), "#@\n.#");
+        assert_eq!(frame.render(true), "#\x1b[31m@\x1b[0m\n.#\n");
+
+        let frame = Dot(IVec2::new(-2, 1)).frame().with_title("dot");
+        assert_eq!(frame.origin(), IVec2::new(-2, 0));
+        assert_eq!(frame.get(IVec2::new(-2, 1)), Some('#'));
+        assert_eq!(frame.to_string(), "dot\n..+\n#..");
+    }
+
+    #[test]
+    fn sinks() {
+        // silent by default
+        assert!(!enabled());
+        show(|| unreachable!("nothing is drawn without a sink"));
+
+        let recorder = Recorder::default();
+        set_sink(Some(Box::new(recorder.clone())));
+        draw(&Dot(IVec2::ONE));
+        draw(&Dot(IVec2::X));
+        set_sink(None);
+        draw(&Dot(IVec2::Y));
+
+        let frames = recorder.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].to_string(), "+#");
+    }
+}
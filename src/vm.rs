@@ -0,0 +1,265 @@
+use std::collections::VecDeque;
+
+use fxhash::FxHashSet;
+
+/// The register file of a machine with `N` registers.
+pub type Registers<const N: usize> = [i64; N];
+
+/// What the machine does after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Goes on with the next instruction.
+    Next,
+    /// Moves the program counter by this offset.
+    Jump(isize),
+    /// The instruction needs an input that is not there yet, it is retried on the next run.
+    Wait,
+}
+
+/// An instruction of a machine with `N` registers.
+pub trait Instruction<const N: usize> {
+    /// Cycles taken by the instruction, its effects only show at the end of the last one.
+    fn cycles(&self) -> usize {
+        1
+    }
+
+    /// Applies the instruction, taking its inputs from the front of `input`.
+    ///
+    /// An instruction returning [`Flow::Wait`] must leave the registers untouched.
+    fn execute(&self, regs: &mut Registers<N>, input: &mut VecDeque<i64>) -> Flow;
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// The program counter left the program.
+    Halted,
+    /// The instruction at this breakpoint is next, running again executes it.
+    Breakpoint(usize),
+    /// The instruction at this address waits for an input.
+    Input(usize),
+}
+
+/// An instruction executed by the machine, as recorded in the trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace<const N: usize> {
+    /// The last cycle of the instruction.
+    pub cycle: usize,
+    pub pc: usize,
+    /// The registers once the instruction is done.
+    pub regs: Registers<N>,
+}
+
+/// Cycle accurate interpreter of a `program` of instructions `I`.
+#[derive(Debug, Clone)]
+pub struct Vm<'a, I, const N: usize> {
+    program: &'a [I],
+    regs: Registers<N>,
+    pc: usize,
+    cycle: usize,
+    input: VecDeque<i64>,
+    breakpoints: FxHashSet<usize>,
+    /// Set when stopped at a breakpoint, so that the next run gets past it.
+    paused: bool,
+    trace: Option<Vec<Trace<N>>>,
+}
+
+impl<'a, I: Instruction<N>, const N: usize> Vm<'a, I, N> {
+    /// A machine at the start of `program` with all registers at 0.
+    pub fn new(program: &'a [I]) -> Self {
+        Vm {
+            program,
+            regs: [0; N],
+            pc: 0,
+            cycle: 0,
+            input: VecDeque::new(),
+            breakpoints: FxHashSet::default(),
+            paused: false,
+            trace: None,
+        }
+    }
+
+    pub fn with_registers(mut self, regs: Registers<N>) -> Self {
+        self.regs = regs;
+        self
+    }
+
+    /// Records every executed instruction, see [`Vm::trace`].
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    pub fn regs(&self) -> &Registers<N> {
+        &self.regs
+    }
+
+    pub fn regs_mut(&mut self) -> &mut Registers<N> {
+        &mut self.regs
+    }
+
+    /// Address of the next instruction.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The current cycle, counted from 1, or the cycles done once stopped.
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    /// Queues inputs for the instructions reading them.
+    pub fn push_input(&mut self, values: impl IntoIterator<Item = i64>) {
+        self.input.extend(values);
+    }
+
+    /// Stops the runs before the instruction at `pc`.
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.remove(&pc);
+    }
+
+    /// The instructions executed so far, empty unless made [`Vm::with_trace`].
+    pub fn trace(&self) -> &[Trace<N>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Runs until the program halts, hits a breakpoint or waits for input.
+    pub fn run(&mut self) -> Stop {
+        self.run_with(|_| {})
+    }
+
+    /// Runs like [`Vm::run`], calling `hook` during every cycle, before the effects of the
+    /// instruction being executed.
+    pub fn run_with(&mut self, mut hook: impl FnMut(&Self)) -> Stop {
+        loop {
+            if let Err(stop) = self.step_with(&mut hook) {
+                return stop;
+            }
+        }
+    }
+
+    /// Executes the next instruction, calling `hook` during each of its cycles.
+    pub fn step_with(&mut self, mut hook: impl FnMut(&Self)) -> Result<(), Stop> {
+        let Some(instruction) = self.program.get(self.pc) else {
+            return Err(Stop::Halted);
+        };
+        if !self.paused && self.breakpoints.contains(&self.pc) {
+            self.paused = true;
+            return Err(Stop::Breakpoint(self.pc));
+        }
+        self.paused = false;
+
+        // an instruction waiting for input takes no time
+        let mut regs = self.regs;
+        let flow = instruction.execute(&mut regs, &mut self.input);
+        if flow == Flow::Wait {
+            return Err(Stop::Input(self.pc));
+        }
+
+        for _ in 0..instruction.cycles() {
+            self.cycle += 1;
+            hook(self);
+        }
+        self.regs = regs;
+        if let Some(trace) = &mut self.trace {
+            trace.push(Trace {
+                cycle: self.cycle,
+                pc: self.pc,
+                regs,
+            });
+        }
+
+        self.pc = match flow {
+            Flow::Jump(offset) => self.pc.wrapping_add_signed(offset),
+            _ => self.pc + 1,
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::{Flow, Instruction, Registers, Stop, Trace, Vm};
+
+    /// A counter machine: `a` accumulates, `b` counts down.
+    enum Op {
+        Read,
+        Add,
+        Dec,
+        Jnz(isize),
+        Sleep,
+    }
+
+    impl Instruction<2> for Op {
+        fn cycles(&self) -> usize {
+            match self {
+                Op::Sleep => 3,
+                _ => 1,
+            }
+        }
+
+        fn execute(&self, regs: &mut Registers<2>, input: &mut VecDeque<i64>) -> Flow {
+            match self {
+                Op::Read => match input.pop_front() {
+                    Some(v) => regs[1] = v,
+                    None => return Flow::Wait,
+                },
+                Op::Add => regs[0] += regs[1],
+                Op::Dec => regs[1] -= 1,
+                Op::Jnz(offset) if regs[1] != 0 => return Flow::Jump(*offset),
+                Op::Jnz(_) | Op::Sleep => {}
+            }
+            Flow::Next
+        }
+    }
+
+    // a = n + (n - 1) + ... + 1
+    const TRIANGLE: [Op; 5] = [Op::Read, Op::Add, Op::Dec, Op::Jnz(-2), Op::Sleep];
+
+    #[test]
+    fn run() {
+        let mut vm = Vm::new(&TRIANGLE);
+        assert_eq!(vm.run(), Stop::Input(0));
+        assert_eq!(vm.cycle(), 0);
+
+        vm.push_input([4]);
+        let mut cycles = vec![];
+        assert_eq!(
+            vm.run_with(|vm| cycles.push((vm.cycle(), vm.pc()))),
+            Stop::Halted
+        );
+        assert_eq!(vm.regs(), &[10, 0]);
+        assert_eq!(vm.cycle(), 1 + 3 * 4 + 3);
+        assert_eq!(cycles[cycles.len() - 3..], [(14, 4), (15, 4), (16, 4)]);
+    }
+
+    #[test]
+    fn breakpoints() {
+        let mut vm = Vm::new(&TRIANGLE).with_trace();
+        vm.push_input([3]);
+        vm.add_breakpoint(1);
+
+        let mut sums = vec![];
+        while let Stop::Breakpoint(pc) = vm.run() {
+            assert_eq!(pc, 1);
+            sums.push(vm.regs()[0]);
+        }
+        assert_eq!(sums, [0, 3, 5]);
+
+        assert_eq!(vm.trace().len(), 1 + 3 * 3 + 1);
+        assert_eq!(
+            vm.trace()[1],
+            Trace {
+                cycle: 2,
+                pc: 1,
+                regs: [3, 3]
+            }
+        );
+    }
+}